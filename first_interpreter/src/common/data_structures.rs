@@ -1,4 +1,11 @@
 // use std::ops::{Deref, DerefMut};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use serde::{Deserialize, Serialize};
 
 /// Implements a Stack using a vector
 pub struct Stack<T>(Vec<T>);
@@ -30,7 +37,7 @@ impl<T> Stack<T> {
     }
 
     pub fn peek_mut(&mut self) -> Option<&mut T> {
-        self.0.get_mut(self.0.len() - 1)
+        self.0.last_mut()
     }
 
     pub fn iter(&self) -> std::slice::Iter<T> {
@@ -42,6 +49,64 @@ impl<T> Stack<T> {
     }
 }
 
+/// Identifies a node of the syntax tree
+///
+/// The parser gives every expression and statement it builds its own id,
+/// and so does the JSON decoder. Ids are never handed out twice in a run,
+/// so the side tables of an Interpreter that runs one program after
+/// another keep what they recorded about the earlier ones. Nodes built
+/// anywhere else keep the default id of 0.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct NodeId(u32);
+
+/// The id last given to a node
+static LAST_ID: AtomicU32 = AtomicU32::new(0);
+
+impl NodeId {
+    /// Returns an id no other node has been given
+    pub fn fresh() -> Self {
+        NodeId(LAST_ID.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+}
+
+impl Display for NodeId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// Associates data with syntax tree nodes without storing it in the tree
+///
+/// Passes such as the resolver fill a side table while walking the tree,
+/// and later passes look the data up by the id of the node.
+#[derive(Debug, Clone)]
+pub struct SideTable<T>(HashMap<NodeId, T>);
+
+impl<T> SideTable<T> {
+    /// Creates a new empty side table
+    pub fn new() -> Self {
+        SideTable(HashMap::new())
+    }
+
+    /// Attaches a value to a node, returning the value it replaced
+    pub fn insert(&mut self, id: NodeId, value: T) -> Option<T> {
+        self.0.insert(id, value)
+    }
+
+    pub fn get(&self, id: NodeId) -> Option<&T> {
+        self.0.get(&id)
+    }
+}
+
+impl<T> Default for SideTable<T> {
+    fn default() -> Self {
+        SideTable::new()
+    }
+}
+
 // impl<T> Deref for Stack<T> {
 //     type Target = Vec<T>;
 
@@ -59,6 +124,24 @@ impl<T> Stack<T> {
 // TODO: write tests for the stack
 #[cfg(test)]
 mod tests {
+    use super::*;
+
     #[test]
     fn test_new_stack() {}
+
+    #[test]
+    fn test_side_table() {
+        let mut table = SideTable::new();
+        let (first, second) = (NodeId::fresh(), NodeId::fresh());
+        assert_eq!(table.get(first), None);
+
+        assert_eq!(table.insert(first, "a"), None);
+        assert_eq!(table.insert(second, "b"), None);
+        assert_eq!(table.insert(first, "c"), Some("a"));
+
+        assert_eq!(table.get(first), Some(&"c"));
+        assert_eq!(table.get(second), Some(&"b"));
+        assert_ne!(first, second);
+        assert_eq!(NodeId::default().to_string(), "#0");
+    }
 }
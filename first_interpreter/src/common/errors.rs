@@ -56,6 +56,8 @@ impl ParseError {
 pub struct RuntimeError {
     token: Token,
     message: String,
+    /// The calls the error unwound through, innermost first
+    stack: Vec<String>,
 }
 
 impl RuntimeError {
@@ -63,14 +65,56 @@ impl RuntimeError {
         RuntimeError {
             token,
             message: message.to_string(),
+            stack: Vec::new(),
         }
     }
+
+    /// Returns the message
+    pub fn message(&self) -> String {
+        self.message.clone()
+    }
+
+    /// Returns the line the error happened on
+    pub fn line(&self) -> u32 {
+        self.token.line()
+    }
+
+    /// Returns the calls the error unwound through, innermost first
+    pub fn stack(&self) -> Vec<String> {
+        self.stack.clone()
+    }
+}
+
+/// Represents a value thrown by a throw statement
+#[derive(Debug)]
+pub struct ThrownValue {
+    keyword: Token,
+    value: Value,
+}
+
+impl ThrownValue {
+    /// Constructs a new ThrownValue
+    pub fn new(keyword: Token, value: Value) -> ThrownValue {
+        ThrownValue { keyword, value }
+    }
+
+    /// Returns the throw keyword
+    pub fn keyword(&self) -> &Token {
+        &self.keyword
+    }
+
+    /// Returns the thrown value
+    pub fn value(&self) -> Value {
+        self.value.clone()
+    }
 }
 
 /// Denotes what kinds of errors occurred
 /// Non-exhaustive, other kinds might be added in the future
 #[non_exhaustive]
 #[derive(Debug)]
+// the variants are named after the errors they hold
+#[allow(clippy::enum_variant_names)]
 pub enum Error {
     /// Error used for syntax errors
     SyntaxError(SyntaxError),
@@ -79,11 +123,11 @@ pub enum Error {
     /// Error used for uncategorized errors
     GenericError(String),
     /// Error used for parsing errors
-    ParseError(ParseError),
+    ///
+    /// Boxed, like runtime errors, to keep every `Result` carrying an Error small
+    ParseError(Box<ParseError>),
     /// Error used for runtime errors
-    RuntimeError(RuntimeError),
-    /// Not an error, used as a hack for Return statements
-    Return(Value),
+    RuntimeError(Box<RuntimeError>),
 }
 
 impl std::fmt::Display for Error {
@@ -109,8 +153,13 @@ impl std::fmt::Display for Error {
                     err.message
                 ),
             },
-            Error::RuntimeError(err) => write!(f, "{:?}\n[line {}]", err.message, err.token.line()),
-            Error::Return(v) => write!(f, "Return {:?}", v),
+            Error::RuntimeError(err) => {
+                write!(f, "{:?}\n[line {}]", err.message, err.token.line())?;
+                for frame in err.stack.iter() {
+                    write!(f, "\n    in {}", frame)?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -119,7 +168,7 @@ impl std::error::Error for Error {}
 
 impl Error {
     /// This logs an error on a line with a given message
-    fn report(&self) {
+    pub fn report(&self) {
         eprintln!("{}", self);
     }
 
@@ -146,15 +195,37 @@ impl Error {
 
     /// This logs a [`Error::ParseError`] with a given token and message
     pub fn report_parse(token: Token, message: &str) -> Self {
-        let err = Error::ParseError(ParseError::new(token, message));
+        let err = Error::ParseError(Box::new(ParseError::new(token, message)));
         err.report();
         err
     }
 
     /// This logs a [`Error::RuntimeError`] with a given token and message
     pub fn report_runtime(token: Token, message: &str) -> Self {
-        let err = Error::RuntimeError(RuntimeError::new(token, message));
+        let err = Error::RuntimeError(Box::new(RuntimeError::new(token, message)));
         err.report();
         err
     }
+
+    /// This creates a [`Error::RuntimeError`] without logging it
+    ///
+    /// Used while interpreting, where a try statement may still catch the error
+    pub fn runtime(token: Token, message: &str) -> Self {
+        Error::RuntimeError(Box::new(RuntimeError::new(token, message)))
+    }
+
+    /// This creates a [`Error::GenericError`] without logging it
+    ///
+    /// Used while interpreting, where a try statement may still catch the error
+    pub fn generic(message: &str) -> Self {
+        Error::GenericError(message.to_string())
+    }
+
+    /// Records a call a runtime error unwound through
+    pub fn with_frame(mut self, frame: String) -> Self {
+        if let Error::RuntimeError(err) = &mut self {
+            err.stack.push(frame);
+        }
+        self
+    }
 }
@@ -6,5 +6,5 @@ pub mod data_structures;
 pub use {
     errors::Error,
     constants::MAX_FUNCTION_ARGUMENTS_SIZE,
-    data_structures::Stack,
+    data_structures::{NodeId, SideTable, Stack},
 };
@@ -1,23 +1,38 @@
+use crate::common::NodeId;
 use crate::rlox::token::Token;
+use serde::{Deserialize, Serialize};
 
 use super::expr::Expr;
 
 /// Represents an assign expression
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Assign {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
     name: Token,
     value: Box<Expr>,
+    /// The operator of a compound assignment, like the `+` of `+=`
+    operator: Option<Token>,
 }
 
 impl Assign {
     /// Constructs a new Assign expression
-    pub fn new(name: Token, value: Expr) -> Assign {
+    pub fn new(id: NodeId, name: Token, value: Expr) -> Assign {
         Assign {
+            id,
             name,
             value: Box::new(value),
+            operator: None,
         }
     }
 
+    /// Makes the assignment combine the current value with
+    /// the new one using the operator, like `+=` does
+    pub fn compound(mut self, operator: Token) -> Assign {
+        self.operator = Some(operator);
+        self
+    }
+
     /// Returns the name of the Assign
     pub fn name(&self) -> Token {
         self.name.clone()
@@ -27,4 +42,14 @@ impl Assign {
     pub fn value(&self) -> Expr {
         *self.value.clone()
     }
+
+    /// Returns the operator of a compound assignment
+    pub fn operator(&self) -> Option<Token> {
+        self.operator.clone()
+    }
+
+    /// Returns the id the parser gave this node
+    pub fn id(&self) -> NodeId {
+        self.id
+    }
 }
@@ -0,0 +1,51 @@
+use crate::common::NodeId;
+use crate::rlox::token::Token;
+use crate::stmt::Pattern;
+use serde::{Deserialize, Serialize};
+
+use super::expr::Expr;
+
+/// Represents an assignment that destructures its value
+/// into existing variables, like `[a, b] = [b, a]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssignPattern {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
+    pattern: Pattern,
+    value: Box<Expr>,
+    /// One id for every name the pattern binds, in the order the pattern
+    /// binds them, for the resolver to tell how far away each variable is
+    targets: Vec<NodeId>,
+}
+
+impl AssignPattern {
+    /// Constructs a new AssignPattern expression
+    pub fn new(id: NodeId, pattern: Pattern, value: Expr, targets: Vec<NodeId>) -> AssignPattern {
+        AssignPattern {
+            id,
+            pattern,
+            value: Box::new(value),
+            targets,
+        }
+    }
+
+    /// Returns the pattern assigned to
+    pub fn pattern(&self) -> Pattern {
+        self.pattern.clone()
+    }
+
+    /// Returns the value destructured
+    pub fn value(&self) -> Expr {
+        *self.value.clone()
+    }
+
+    /// Returns the names the pattern binds, each with the id
+    /// the resolver records the variable it refers to under
+    pub fn targets(&self) -> Vec<(Token, NodeId)> {
+        self.pattern
+            .bindings()
+            .into_iter()
+            .zip(self.targets.iter().copied())
+            .collect()
+    }
+}
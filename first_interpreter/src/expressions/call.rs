@@ -1,22 +1,36 @@
+use crate::common::NodeId;
 use crate::rlox::token::Token;
+use serde::{Deserialize, Serialize};
 
 use super::expr::Expr;
 
 /// Represents a binary expression
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Call {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
     callee: Box<Expr>,
     paren: Token,
     arguments: Vec<Expr>,
+    /// Arguments passed by parameter name, which follow the positional ones
+    named_arguments: Vec<(Token, Expr)>,
 }
 
 impl Call {
     /// Constructs a new binary expression
-    pub fn new(callee: Expr, paren: Token, arguments: Vec<Expr>) -> Call {
+    pub fn new(
+        id: NodeId,
+        callee: Expr,
+        paren: Token,
+        arguments: Vec<Expr>,
+        named_arguments: Vec<(Token, Expr)>,
+    ) -> Call {
         Call {
+            id,
             callee: Box::new(callee),
             paren,
             arguments,
+            named_arguments,
         }
     }
 
@@ -34,4 +48,9 @@ impl Call {
     pub fn arguments(&self) -> &Vec<Expr> {
         &self.arguments
     }
+
+    /// Returns the named arguments
+    pub fn named_arguments(&self) -> &Vec<(Token, Expr)> {
+        &self.named_arguments
+    }
 }
@@ -1,26 +1,38 @@
-use crate::expressions::{Assign, Binary, Call, Grouping, Literal, Logical, Unary, Variable};
+use crate::expressions::{
+    Assign, AssignPattern, Binary, Call, Get, Grouping, Index, Lambda, List, Literal, Logical, Map,
+    Set, SetIndex, Super, Ternary, This, Unary, Variable,
+};
+use serde::{Deserialize, Serialize};
 
 /// Trait for a structure implementing all the methods to
 /// handle different expressions
 pub trait Visitor<R> {
     fn visit_assign_expr(&mut self, expr: &Assign) -> R;
+    fn visit_assign_pattern_expr(&mut self, expr: &AssignPattern) -> R;
     fn visit_binary_expr(&mut self, expr: &Binary) -> R;
     fn visit_call_expr(&mut self, expr: &Call) -> R;
-    // fn visit_get_expr(&mut self, expr: &Get) -> R;
+    fn visit_get_expr(&mut self, expr: &Get) -> R;
     fn visit_grouping_expr(&mut self, expr: &Grouping) -> R;
+    fn visit_index_expr(&mut self, expr: &Index) -> R;
+    fn visit_lambda_expr(&mut self, expr: &Lambda) -> R;
+    fn visit_list_expr(&mut self, expr: &List) -> R;
     fn visit_literal_expr(&mut self, expr: &Literal) -> R;
     fn visit_logical_expr(&mut self, expr: &Logical) -> R;
-    // fn visit_set_expr(&mut self, expr: &Set) -> R;
-    // fn visit_super_expr(&mut self, expr: &Super) -> R;
-    // fn visit_this_expr(&mut self, expr: &This) -> R;
+    fn visit_map_expr(&mut self, expr: &Map) -> R;
+    fn visit_set_index_expr(&mut self, expr: &SetIndex) -> R;
+    fn visit_set_expr(&mut self, expr: &Set) -> R;
+    fn visit_super_expr(&mut self, expr: &Super) -> R;
+    fn visit_ternary_expr(&mut self, expr: &Ternary) -> R;
+    fn visit_this_expr(&mut self, expr: &This) -> R;
     fn visit_unary_expr(&mut self, expr: &Unary) -> R;
     fn visit_variable_expr(&mut self, expr: &Variable) -> R;
 }
 
 /// Represents all forms of expressions using wrappers
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Expr {
     Assign(Assign),
+    AssignPattern(AssignPattern),
     Binary(Binary),
     Literal(Literal),
     Grouping(Grouping),
@@ -28,6 +40,16 @@ pub enum Expr {
     Variable(Variable),
     Logical(Logical),
     Call(Call),
+    Get(Get),
+    List(List),
+    Map(Map),
+    Index(Index),
+    SetIndex(SetIndex),
+    Set(Set),
+    This(This),
+    Super(Super),
+    Lambda(Lambda),
+    Ternary(Ternary),
 }
 
 impl Expr {
@@ -35,6 +57,7 @@ impl Expr {
     pub fn accept<R>(&self, visitor: &mut dyn Visitor<R>) -> R {
         match self {
             Expr::Assign(expr) => visitor.visit_assign_expr(expr),
+            Expr::AssignPattern(expr) => visitor.visit_assign_pattern_expr(expr),
             Expr::Binary(expr) => visitor.visit_binary_expr(expr),
             Expr::Literal(expr) => visitor.visit_literal_expr(expr),
             Expr::Grouping(expr) => visitor.visit_grouping_expr(expr),
@@ -42,6 +65,16 @@ impl Expr {
             Expr::Variable(expr) => visitor.visit_variable_expr(expr),
             Expr::Logical(expr) => visitor.visit_logical_expr(expr),
             Expr::Call(expr) => visitor.visit_call_expr(expr),
+            Expr::Get(expr) => visitor.visit_get_expr(expr),
+            Expr::List(expr) => visitor.visit_list_expr(expr),
+            Expr::Map(expr) => visitor.visit_map_expr(expr),
+            Expr::Index(expr) => visitor.visit_index_expr(expr),
+            Expr::SetIndex(expr) => visitor.visit_set_index_expr(expr),
+            Expr::Set(expr) => visitor.visit_set_expr(expr),
+            Expr::This(expr) => visitor.visit_this_expr(expr),
+            Expr::Super(expr) => visitor.visit_super_expr(expr),
+            Expr::Lambda(expr) => visitor.visit_lambda_expr(expr),
+            Expr::Ternary(expr) => visitor.visit_ternary_expr(expr),
         }
     }
 }
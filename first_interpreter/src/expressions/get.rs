@@ -0,0 +1,51 @@
+use crate::common::NodeId;
+use crate::rlox::token::Token;
+use serde::{Deserialize, Serialize};
+
+use super::expr::Expr;
+
+/// Represents a property access expression
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Get {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
+    object: Box<Expr>,
+    name: Token,
+    /// Optional accesses, like `object?.name`, skip the rest of
+    /// the chain they are part of when the object is nil
+    is_optional: bool,
+}
+
+impl Get {
+    /// Constructs a new Get expression
+    pub fn new(id: NodeId, object: Expr, name: Token) -> Get {
+        Get {
+            id,
+            object: Box::new(object),
+            name,
+            is_optional: false,
+        }
+    }
+
+    /// Makes the access evaluate to nil instead of failing
+    /// when the object is nil, like `?.` does
+    pub fn optional(mut self) -> Get {
+        self.is_optional = true;
+        self
+    }
+
+    /// Returns the object the property is read from
+    pub fn object(&self) -> Expr {
+        *self.object.clone()
+    }
+
+    /// Returns the name of the property
+    pub fn name(&self) -> &Token {
+        &self.name
+    }
+
+    /// Returns true if the access was written with `?.`
+    pub fn is_optional(&self) -> bool {
+        self.is_optional
+    }
+}
@@ -1,15 +1,20 @@
 use super::expr::Expr;
+use crate::common::NodeId;
+use serde::{Deserialize, Serialize};
 
 /// Represents a Grouping expression
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Grouping {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
     expression: Box<Expr>,
 }
 
 impl Grouping {
     /// Constructs a new Grouping
-    pub fn new(expression: Expr) -> Grouping {
+    pub fn new(id: NodeId, expression: Expr) -> Grouping {
         Grouping {
+            id,
             expression: Box::new(expression),
         }
     }
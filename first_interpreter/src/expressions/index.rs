@@ -0,0 +1,42 @@
+use crate::common::NodeId;
+use crate::rlox::token::Token;
+use serde::{Deserialize, Serialize};
+
+use super::expr::Expr;
+
+/// Represents an index expression, like `xs[i]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Index {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
+    object: Box<Expr>,
+    bracket: Token,
+    index: Box<Expr>,
+}
+
+impl Index {
+    /// Constructs a new Index expression
+    pub fn new(id: NodeId, object: Expr, bracket: Token, index: Expr) -> Index {
+        Index {
+            id,
+            object: Box::new(object),
+            bracket,
+            index: Box::new(index),
+        }
+    }
+
+    /// Returns the object being indexed
+    pub fn object(&self) -> Expr {
+        *self.object.clone()
+    }
+
+    /// Returns the closing bracket
+    pub fn bracket(&self) -> &Token {
+        &self.bracket
+    }
+
+    /// Returns the index
+    pub fn index(&self) -> Expr {
+        *self.index.clone()
+    }
+}
@@ -0,0 +1,83 @@
+use crate::common::NodeId;
+use crate::rlox::token::Token;
+use crate::stmt::{Function, Param, Stmt};
+use serde::{Deserialize, Serialize};
+
+/// Represents an anonymous function, like `fun (a) { ... }` or `(a) => a * 2`
+///
+/// The body of an arrow function written as an expression is
+/// a single return statement keyed by the arrow itself
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lambda {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
+    /// The `fun` keyword or the `=>` arrow
+    keyword: Token,
+    params: Vec<Param>,
+    body: Vec<Stmt>,
+    /// Anonymous generators are written `fun* (a) { ... }`
+    is_generator: bool,
+}
+
+impl Lambda {
+    /// Constructs a new Lambda expression
+    pub fn new(id: NodeId, keyword: Token, params: Vec<Param>, body: Vec<Stmt>) -> Lambda {
+        Lambda {
+            id,
+            keyword,
+            params,
+            body,
+            is_generator: false,
+        }
+    }
+
+    /// Marks the function as a generator
+    pub fn generator(mut self) -> Lambda {
+        self.is_generator = true;
+        self
+    }
+
+    /// Returns true if calling the function makes a generator
+    pub fn is_generator(&self) -> bool {
+        self.is_generator
+    }
+
+    /// Returns the `fun` keyword or the `=>` arrow
+    pub fn keyword(&self) -> &Token {
+        &self.keyword
+    }
+
+    /// Returns the parameters
+    pub fn params(&self) -> Vec<Param> {
+        self.params.clone()
+    }
+
+    /// Returns the body
+    pub fn body(&self) -> Vec<Stmt> {
+        self.body.clone()
+    }
+
+    /// Returns the expression an arrow function evaluates to,
+    /// if it was written with one instead of a block
+    pub fn arrow_expression(&self) -> Option<crate::expressions::Expr> {
+        match self.body.as_slice() {
+            [Stmt::Return(ret)] if ret.keyword() == self.keyword => ret.value(),
+            _ => None,
+        }
+    }
+
+    /// Returns the function declaration the lambda makes at runtime,
+    /// named by its keyword
+    pub fn declaration(&self) -> Function {
+        let function = Function::new(
+            self.id,
+            self.keyword.clone(),
+            self.params.clone(),
+            self.body.clone(),
+        );
+        match self.is_generator {
+            true => function.generator(),
+            false => function,
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use crate::common::NodeId;
+use crate::rlox::token::Token;
+use serde::{Deserialize, Serialize};
+
+use super::expr::Expr;
+
+/// Represents a list literal expression
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct List {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
+    bracket: Token,
+    elements: Vec<Expr>,
+}
+
+impl List {
+    /// Constructs a new List expression
+    pub fn new(id: NodeId, bracket: Token, elements: Vec<Expr>) -> List {
+        List {
+            id,
+            bracket,
+            elements,
+        }
+    }
+
+    /// Returns the elements
+    pub fn elements(&self) -> &Vec<Expr> {
+        &self.elements
+    }
+}
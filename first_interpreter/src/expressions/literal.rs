@@ -1,15 +1,20 @@
+use crate::common::NodeId;
 use crate::rlox::Value;
+use serde::{Deserialize, Serialize};
 
 /// This represents a literal value used in expressions
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Literal {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
+    #[serde(with = "literal_value")]
     value: Value,
 }
 
 impl Literal {
     /// Constructs a new Literal
-    pub fn new(value: Value) -> Literal {
-        Literal { value }
+    pub fn new(id: NodeId, value: Value) -> Literal {
+        Literal { id, value }
     }
 
     /// Returns the literal value
@@ -17,3 +22,29 @@ impl Literal {
         self.value.clone()
     }
 }
+
+/// Encodes the value of a Literal through the [`TokenLiteral`] it holds,
+/// since the parser only ever builds literals from token literals
+///
+/// [`TokenLiteral`]: crate::rlox::TokenLiteral
+mod literal_value {
+    use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::rlox::{TokenLiteral, Value};
+
+    pub fn serialize<S: Serializer>(value: &Value, serializer: S) -> Result<S::Ok, S::Error> {
+        if !value.is::<TokenLiteral>() {
+            return Err(ser::Error::custom("literal must hold a token literal"));
+        }
+        value
+            .borrowed::<TokenLiteral>()
+            .map_err(ser::Error::custom)?
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Value, D::Error> {
+        TokenLiteral::deserialize(deserializer)
+            .map(Value::new)
+            .map_err(de::Error::custom)
+    }
+}
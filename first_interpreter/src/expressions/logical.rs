@@ -1,10 +1,14 @@
+use crate::common::NodeId;
 use crate::rlox::token::Token;
+use serde::{Deserialize, Serialize};
 
 use super::expr::Expr;
 
 /// Represents a logical expression
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Logical {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
     left: Box<Expr>,
     operator: Token,
     right: Box<Expr>,
@@ -12,8 +16,9 @@ pub struct Logical {
 
 impl Logical {
     /// Constructs a new logical expression
-    pub fn new(left: Expr, operator: Token, right: Expr) -> Logical {
+    pub fn new(id: NodeId, left: Expr, operator: Token, right: Expr) -> Logical {
         Logical {
+            id,
             left: Box::new(left),
             operator,
             right: Box::new(right),
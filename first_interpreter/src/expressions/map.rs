@@ -0,0 +1,32 @@
+use crate::common::NodeId;
+use crate::rlox::token::Token;
+use serde::{Deserialize, Serialize};
+
+use super::expr::Expr;
+
+/// Represents a map literal expression
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Map {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
+    brace: Token,
+    /// The key and value of every entry, in source order
+    entries: Vec<(Expr, Expr)>,
+}
+
+impl Map {
+    /// Constructs a new Map expression
+    pub fn new(id: NodeId, brace: Token, entries: Vec<(Expr, Expr)>) -> Map {
+        Map { id, brace, entries }
+    }
+
+    /// Returns the opening brace
+    pub fn brace(&self) -> &Token {
+        &self.brace
+    }
+
+    /// Returns the entries
+    pub fn entries(&self) -> &Vec<(Expr, Expr)> {
+        &self.entries
+    }
+}
@@ -1,14 +1,26 @@
 pub mod assign;
+pub mod assign_pattern;
 pub mod binary;
 pub mod expr;
+pub mod get;
 pub mod grouping;
+pub mod index;
+pub mod lambda;
+pub mod list;
 pub mod literal;
 pub mod logical;
+pub mod map;
+pub mod set;
+pub mod set_index;
+pub mod super_;
+pub mod ternary;
+pub mod this;
 pub mod unary;
 pub mod variable;
 pub mod call;
 
 pub use {
-    assign::Assign, binary::Binary, expr::Expr, grouping::Grouping, literal::Literal,
-    logical::Logical, unary::Unary, variable::Variable, call::Call,
+    assign::Assign, assign_pattern::AssignPattern, binary::Binary, expr::Expr, get::Get, grouping::Grouping, index::Index,
+    lambda::Lambda, list::List, literal::Literal, logical::Logical, map::Map, set::Set,
+    set_index::SetIndex, super_::Super, ternary::Ternary, this::This, unary::Unary, variable::Variable, call::Call,
 };
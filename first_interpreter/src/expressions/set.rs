@@ -0,0 +1,57 @@
+use crate::common::NodeId;
+use crate::rlox::token::Token;
+use serde::{Deserialize, Serialize};
+
+use super::expr::Expr;
+
+/// Represents an assignment to a property, like `object.name = value`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Set {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
+    object: Box<Expr>,
+    name: Token,
+    value: Box<Expr>,
+    /// The operator of a compound assignment, like the `+` of `+=`
+    operator: Option<Token>,
+}
+
+impl Set {
+    /// Constructs a new Set expression
+    pub fn new(id: NodeId, object: Expr, name: Token, value: Expr) -> Set {
+        Set {
+            id,
+            object: Box::new(object),
+            name,
+            value: Box::new(value),
+            operator: None,
+        }
+    }
+
+    /// Makes the assignment combine the current value with
+    /// the new one using the operator, like `+=` does
+    pub fn compound(mut self, operator: Token) -> Set {
+        self.operator = Some(operator);
+        self
+    }
+
+    /// Returns the object the property is set on
+    pub fn object(&self) -> Expr {
+        *self.object.clone()
+    }
+
+    /// Returns the name of the property
+    pub fn name(&self) -> &Token {
+        &self.name
+    }
+
+    /// Returns the value assigned
+    pub fn value(&self) -> Expr {
+        *self.value.clone()
+    }
+
+    /// Returns the operator of a compound assignment
+    pub fn operator(&self) -> Option<Token> {
+        self.operator.clone()
+    }
+}
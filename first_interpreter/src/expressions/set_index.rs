@@ -0,0 +1,64 @@
+use crate::common::NodeId;
+use crate::rlox::token::Token;
+use serde::{Deserialize, Serialize};
+
+use super::expr::Expr;
+
+/// Represents an assignment to an index, like `xs[i] = value`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetIndex {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
+    object: Box<Expr>,
+    bracket: Token,
+    index: Box<Expr>,
+    value: Box<Expr>,
+    /// The operator of a compound assignment, like the `+` of `+=`
+    operator: Option<Token>,
+}
+
+impl SetIndex {
+    /// Constructs a new SetIndex expression
+    pub fn new(id: NodeId, object: Expr, bracket: Token, index: Expr, value: Expr) -> SetIndex {
+        SetIndex {
+            id,
+            object: Box::new(object),
+            bracket,
+            index: Box::new(index),
+            value: Box::new(value),
+            operator: None,
+        }
+    }
+
+    /// Makes the assignment combine the current value with
+    /// the new one using the operator, like `+=` does
+    pub fn compound(mut self, operator: Token) -> SetIndex {
+        self.operator = Some(operator);
+        self
+    }
+
+    /// Returns the object being indexed
+    pub fn object(&self) -> Expr {
+        *self.object.clone()
+    }
+
+    /// Returns the closing bracket
+    pub fn bracket(&self) -> &Token {
+        &self.bracket
+    }
+
+    /// Returns the index
+    pub fn index(&self) -> Expr {
+        *self.index.clone()
+    }
+
+    /// Returns the value assigned
+    pub fn value(&self) -> Expr {
+        *self.value.clone()
+    }
+
+    /// Returns the operator of a compound assignment
+    pub fn operator(&self) -> Option<Token> {
+        self.operator.clone()
+    }
+}
@@ -0,0 +1,38 @@
+use crate::common::NodeId;
+use crate::rlox::token::Token;
+use serde::{Deserialize, Serialize};
+
+/// Represents a superclass method access, like `super.name`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Super {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
+    keyword: Token,
+    method: Token,
+}
+
+impl Super {
+    /// Constructs a new Super expression
+    pub fn new(id: NodeId, keyword: Token, method: Token) -> Super {
+        Super {
+            id,
+            keyword,
+            method,
+        }
+    }
+
+    /// Returns the `super` keyword
+    pub fn keyword(&self) -> &Token {
+        &self.keyword
+    }
+
+    /// Returns the name of the method
+    pub fn method(&self) -> &Token {
+        &self.method
+    }
+
+    /// Returns the id the parser gave this node
+    pub fn id(&self) -> NodeId {
+        self.id
+    }
+}
@@ -0,0 +1,56 @@
+use crate::common::NodeId;
+use crate::rlox::token::Token;
+use serde::{Deserialize, Serialize};
+
+use super::expr::Expr;
+
+/// Represents a conditional expression, like `cond ? a : b`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ternary {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
+    condition: Box<Expr>,
+    /// The `?`, where errors from the condition are reported
+    question: Token,
+    then_branch: Box<Expr>,
+    else_branch: Box<Expr>,
+}
+
+impl Ternary {
+    /// Constructs a new Ternary expression
+    pub fn new(
+        id: NodeId,
+        condition: Expr,
+        question: Token,
+        then_branch: Expr,
+        else_branch: Expr,
+    ) -> Ternary {
+        Ternary {
+            id,
+            condition: Box::new(condition),
+            question,
+            then_branch: Box::new(then_branch),
+            else_branch: Box::new(else_branch),
+        }
+    }
+
+    /// Returns the condition
+    pub fn condition(&self) -> Expr {
+        *self.condition.clone()
+    }
+
+    /// Returns the `?` token
+    pub fn question(&self) -> &Token {
+        &self.question
+    }
+
+    /// Returns the expression evaluated when the condition is true
+    pub fn then_branch(&self) -> Expr {
+        *self.then_branch.clone()
+    }
+
+    /// Returns the expression evaluated when the condition is false
+    pub fn else_branch(&self) -> Expr {
+        *self.else_branch.clone()
+    }
+}
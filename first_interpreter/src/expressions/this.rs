@@ -0,0 +1,28 @@
+use crate::common::NodeId;
+use crate::rlox::token::Token;
+use serde::{Deserialize, Serialize};
+
+/// Represents the `this` expression inside a method
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct This {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
+    keyword: Token,
+}
+
+impl This {
+    /// Constructs a new This expression
+    pub fn new(id: NodeId, keyword: Token) -> This {
+        This { id, keyword }
+    }
+
+    /// Returns the `this` keyword
+    pub fn keyword(&self) -> &Token {
+        &self.keyword
+    }
+
+    /// Returns the id the parser gave this node
+    pub fn id(&self) -> NodeId {
+        self.id
+    }
+}
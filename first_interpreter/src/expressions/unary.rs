@@ -1,18 +1,23 @@
+use crate::common::NodeId;
 use crate::rlox::token::Token;
+use serde::{Deserialize, Serialize};
 
 use super::expr::Expr;
 
 /// Represents a Unary expression
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Unary {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
     operator: Token,
     right: Box<Expr>,
 }
 
 impl Unary {
     /// Constructs a Unary
-    pub fn new(operator: Token, right: Expr) -> Unary {
+    pub fn new(id: NodeId, operator: Token, right: Expr) -> Unary {
         Unary {
+            id,
             operator,
             right: Box::new(right),
         }
@@ -1,19 +1,28 @@
+use crate::common::NodeId;
 use crate::rlox::token::Token;
+use serde::{Deserialize, Serialize};
 
 /// Represents a Variable expression
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Variable {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
     name: Token,
 }
 
 impl Variable {
     /// Constructs a Variable
-    pub fn new(name: Token) -> Variable {
-        Variable { name }
+    pub fn new(id: NodeId, name: Token) -> Variable {
+        Variable { id, name }
     }
 
     /// Returns the name of the variable
     pub fn name(&self) -> &Token {
         &self.name
     }
+
+    /// Returns the id the parser gave this node
+    pub fn id(&self) -> NodeId {
+        self.id
+    }
 }
@@ -6,6 +6,9 @@ mod rlox;
 mod stmt;
 mod visitors;
 
+const USAGE: &str = "Usage: rlox [--dump-tokens | --dump-ast | --dump-dot | --dump-cfg | --dump-json | --run-json] [script]
+       rlox fmt [--check] script";
+
 fn main() -> Result<(), &'static str> {
     let args: Vec<_> = std::env::args().collect();
     match args.len() {
@@ -15,8 +18,40 @@ fn main() -> Result<(), &'static str> {
         2 => {
             let _ = rlox::run_file(&args[1]);
         }
+        3 if args[1] == "fmt" => {
+            let _ = rlox::format_file(&args[2], false);
+        }
+        3 => match args[1].as_str() {
+            "--dump-tokens" => {
+                let _ = rlox::dump_tokens(&args[2]);
+            }
+            "--dump-ast" => {
+                let _ = rlox::dump_ast(&args[2]);
+            }
+            "--dump-dot" => {
+                let _ = rlox::dump_dot(&args[2]);
+            }
+            "--dump-cfg" => {
+                let _ = rlox::dump_cfg(&args[2]);
+            }
+            "--dump-json" => {
+                let _ = rlox::dump_json(&args[2]);
+            }
+            "--run-json" => {
+                let _ = rlox::run_json_file(&args[2]);
+            }
+            _ => {
+                println!("{}", USAGE);
+                return Err("Incorrect usage");
+            }
+        },
+        4 if args[1] == "fmt" && args[2] == "--check" => {
+            if rlox::format_file(&args[3], true).is_err() {
+                return Err("Source file is not formatted");
+            }
+        }
         _ => {
-            println!("Usage: rlox [script]");
+            println!("{}", USAGE);
             return Err("Incorrect usage");
         }
     }
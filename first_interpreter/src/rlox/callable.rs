@@ -1,14 +1,87 @@
+use std::fmt::Display;
+
 use crate::{
     common::Error,
-    rlox::{Interpreter, Value},
+    rlox::{Interpreter, Token, Unwind, Value},
 };
 
+/// Represents the number of arguments a callable object takes,
+/// from a minimum up to a maximum, or without a maximum
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Arity {
+    min: usize,
+    max: Option<usize>,
+}
+
+impl Arity {
+    /// Constructs an Arity taking exactly the given number of arguments
+    pub const fn exact(count: usize) -> Arity {
+        Arity {
+            min: count,
+            max: Some(count),
+        }
+    }
+
+    /// Constructs an Arity taking from min up to max arguments
+    pub const fn range(min: usize, max: usize) -> Arity {
+        Arity {
+            min,
+            max: Some(max),
+        }
+    }
+
+    /// Constructs an Arity taking min arguments or more
+    pub const fn at_least(min: usize) -> Arity {
+        Arity { min, max: None }
+    }
+
+    /// Returns the most arguments taken, if there is a limit
+    pub fn max(&self) -> Option<usize> {
+        self.max
+    }
+
+    /// Returns true if a call may pass that many arguments
+    pub fn accepts(&self, count: usize) -> bool {
+        count >= self.min && self.max.is_none_or(|max| count <= max)
+    }
+}
+
+impl Display for Arity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.max {
+            Some(max) if max == self.min => write!(f, "{}", max),
+            Some(max) => write!(f, "{} to {}", self.min, max),
+            None => write!(f, "at least {}", self.min),
+        }
+    }
+}
+
 /// Represents an interface for callable objects in Rlox
 pub trait RloxCallable {
     /// Returns the number of arguments for the callable object
-    fn arity(&self) -> usize;
+    fn arity(&self) -> Arity;
     /// Performs the set of statements grouped under this callable
-    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, Error>;
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, Unwind>;
+    /// Performs the call with named arguments after the positional ones
+    ///
+    /// Callables take no named arguments unless they override this.
+    /// The paren is where errors about the arguments are reported.
+    fn call_with_named(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<Value>,
+        named: Vec<(Token, Value)>,
+        _paren: &Token,
+    ) -> Result<Value, Unwind> {
+        match named.first() {
+            Some((name, _)) => Err(Error::runtime(
+                name.clone(),
+                "Native functions do not take named arguments.",
+            )
+            .into()),
+            None => self.call(interpreter, arguments),
+        }
+    }
 }
 
 // /// Represents callable types in Rlox
@@ -62,3 +135,35 @@ pub trait RloxCallable {
 //         }
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arity() {
+        let cases = vec![
+            (
+                Arity::exact(2),
+                vec![(1, false), (2, true), (3, false)],
+                "2",
+            ),
+            (
+                Arity::range(1, 3),
+                vec![(0, false), (1, true), (3, true), (4, false)],
+                "1 to 3",
+            ),
+            (
+                Arity::at_least(1),
+                vec![(0, false), (1, true), (200, true)],
+                "at least 1",
+            ),
+        ];
+        for (arity, counts, display) in cases {
+            for (count, accepted) in counts {
+                assert_eq!(arity.accepts(count), accepted);
+            }
+            assert_eq!(arity.to_string(), display);
+        }
+    }
+}
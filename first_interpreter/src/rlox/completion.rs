@@ -0,0 +1,62 @@
+use crate::{
+    common::errors::{Error, ThrownValue},
+    rlox::Value,
+};
+
+/// Represents how the execution of a statement ended
+///
+/// Anything other than [`Completion::Normal`] is handed up from the
+/// statement to its enclosing statements until one of them handles it:
+/// a function call for a return, a loop for a break or continue and a
+/// try statement for a throw.
+#[derive(Debug)]
+pub enum Completion {
+    /// The statement ran to its end
+    Normal,
+    /// A return statement ran, carrying the returned value
+    Return(Value),
+    /// A break statement ran, carrying the label of the loop it leaves
+    Break(Option<String>),
+    /// A continue statement ran, carrying the label of the loop it continues
+    Continue(Option<String>),
+    /// A throw statement ran, carrying the thrown value
+    Throw(ThrownValue),
+}
+
+/// Represents why the evaluation of an expression ended without a value
+///
+/// A throw inside a called function leaves the call as
+/// [`Completion::Throw`] and unwinds through the expressions around
+/// the call, up to the statement that made it, where it becomes the
+/// completion of that statement.
+#[derive(Debug)]
+pub enum Unwind {
+    /// The evaluation failed
+    Error(Error),
+    /// A throw statement ran inside a call, carrying its completion
+    Throw(Completion),
+}
+
+impl Unwind {
+    /// Returns what the statement the evaluation was part of ends with
+    pub fn into_completion(self) -> Result<Completion, Error> {
+        match self {
+            Unwind::Error(err) => Err(err),
+            Unwind::Throw(completion) => Ok(completion),
+        }
+    }
+
+    /// Records a call an error unwound through
+    pub fn with_frame(self, frame: String) -> Self {
+        match self {
+            Unwind::Error(err) => Unwind::Error(err.with_frame(frame)),
+            unwind => unwind,
+        }
+    }
+}
+
+impl From<Error> for Unwind {
+    fn from(err: Error) -> Self {
+        Unwind::Error(err)
+    }
+}
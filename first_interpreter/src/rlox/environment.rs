@@ -1,4 +1,8 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
 
 use crate::{
     common::Error,
@@ -9,6 +13,8 @@ use crate::{
 /// Represents some kind of recursive storage for variables to values
 pub struct Environment {
     state: HashMap<String, Value>,
+    /// The names in this environment that were declared with `const`
+    constants: HashSet<String>,
     parent: Option<Rc<RefCell<Environment>>>,
 }
 
@@ -16,6 +22,7 @@ impl Default for Environment {
     fn default() -> Self {
         Self {
             state: Default::default(),
+            constants: Default::default(),
             parent: Default::default(),
         }
     }
@@ -26,6 +33,7 @@ impl Environment {
     pub(crate) fn new() -> Environment {
         Environment {
             state: HashMap::new(),
+            constants: HashSet::new(),
             parent: None,
         }
     }
@@ -35,15 +43,52 @@ impl Environment {
     pub(crate) fn with_parent(parent: Rc<RefCell<Environment>>) -> Environment {
         Environment {
             state: HashMap::new(),
+            constants: HashSet::new(),
             parent: Some(parent),
         }
     }
 
     /// Defines a new variable by storing it in the Environment table
+    ///
+    /// Used for names no declaration can clash with, like parameters
     pub(crate) fn define(&mut self, name: String, value: Value) {
         self.state.insert(name, value);
     }
 
+    /// Defines the name a declaration introduces, which cannot be
+    /// assigned to afterwards when `is_const` is set
+    ///
+    /// A constant cannot be declared again, as that would make it assignable
+    pub(crate) fn declare(
+        &mut self,
+        name: &Token,
+        value: Value,
+        is_const: bool,
+    ) -> Result<(), Error> {
+        if self.constants.contains(&name.lexeme()) {
+            return Err(Error::runtime(
+                name.clone(),
+                &format!("Cannot redeclare constant '{}'.", name.lexeme()),
+            ));
+        }
+        if is_const {
+            self.constants.insert(name.lexeme());
+        }
+        self.state.insert(name.lexeme(), value);
+        Ok(())
+    }
+
+    /// Errors if the variable is a constant of this environment
+    fn check_assignable(&self, name: &Token) -> Result<(), Error> {
+        match self.constants.contains(&name.lexeme()) {
+            true => Err(Error::runtime(
+                name.clone(),
+                &format!("Cannot assign to constant '{}'.", name.lexeme()),
+            )),
+            false => Ok(()),
+        }
+    }
+
     /// Returns the value of a variable
     pub(crate) fn get(&self, name: &Token) -> Result<Value, Error> {
         // check this environment for the token
@@ -57,7 +102,7 @@ impl Environment {
             return v.borrow().get(name);
         }
 
-        Err(Error::report_runtime(
+        Err(Error::runtime(
             name.clone(),
             &format!("Undefined variable '{}'.", lexeme),
         ))
@@ -68,21 +113,64 @@ impl Environment {
     pub(crate) fn assign(&mut self, name: Token, value: Value) -> Result<(), Error> {
         let lexeme = name.lexeme();
         if self.state.contains_key(&lexeme) {
+            self.check_assignable(&name)?;
             self.state.insert(lexeme.clone(), value);
             return Ok(());
         }
 
         // check the ancestor environment
-        let parent = self.parent.take();
-        if let Some(parent_environment) = parent {
-            parent_environment.borrow_mut().assign(name, value)?;
-            self.parent = Some(parent_environment);
-            return Ok(());
+        if let Some(parent_environment) = &self.parent {
+            return parent_environment.borrow_mut().assign(name, value);
         }
 
-        Err(Error::report_runtime(
+        Err(Error::runtime(
             name,
             &format!("Undefined variable '{}'.", lexeme),
         ))
     }
+
+    /// Returns the value of a variable defined in the environment
+    /// the given number of scopes above this one
+    pub(crate) fn get_at(&self, distance: usize, name: &Token) -> Result<Value, Error> {
+        if distance == 0 {
+            return match self.state.get(&name.lexeme()) {
+                Some(v) => Ok(v.clone()),
+                None => Err(Error::runtime(
+                    name.clone(),
+                    &format!("Undefined variable '{}'.", name.lexeme()),
+                )),
+            };
+        }
+
+        match &self.parent {
+            Some(parent) => parent.borrow().get_at(distance - 1, name),
+            None => Err(Error::runtime(
+                name.clone(),
+                &format!("Undefined variable '{}'.", name.lexeme()),
+            )),
+        }
+    }
+
+    /// Assigns a new value to a variable defined in the environment
+    /// the given number of scopes above this one
+    pub(crate) fn assign_at(
+        &mut self,
+        distance: usize,
+        name: Token,
+        value: Value,
+    ) -> Result<(), Error> {
+        if distance == 0 {
+            self.check_assignable(&name)?;
+            self.state.insert(name.lexeme(), value);
+            return Ok(());
+        }
+
+        match &self.parent {
+            Some(parent) => parent.borrow_mut().assign_at(distance - 1, name, value),
+            None => Err(Error::runtime(
+                name.clone(),
+                &format!("Undefined variable '{}'.", name.lexeme()),
+            )),
+        }
+    }
 }
@@ -0,0 +1,69 @@
+use std::fmt::Display;
+
+use crate::{
+    common::Error,
+    rlox::{Token, TokenLiteral, Value},
+};
+
+/// Represents a runtime error caught by a try statement, as a value
+/// scripts can read the message, line and stack of
+#[derive(Debug, Clone)]
+pub struct ErrorObject {
+    message: String,
+    /// The line the error happened on, if it is known
+    line: Option<u32>,
+    /// The calls the error unwound through, innermost first
+    stack: Vec<String>,
+}
+
+impl ErrorObject {
+    /// Constructs a new ErrorObject
+    pub fn new(message: String, line: Option<u32>, stack: Vec<String>) -> ErrorObject {
+        ErrorObject {
+            message,
+            line,
+            stack,
+        }
+    }
+
+    /// Builds the value a catch clause binds for an error, or returns
+    /// the error back if it cannot be caught
+    pub fn catch(err: Error) -> Result<Value, Error> {
+        match err {
+            Error::RuntimeError(err) => Ok(Value::new(ErrorObject::new(
+                err.message(),
+                Some(err.line()),
+                err.stack(),
+            ))),
+            Error::GenericError(message) => {
+                Ok(Value::new(ErrorObject::new(message, None, Vec::new())))
+            }
+            err => Err(err),
+        }
+    }
+
+    /// Returns the value of a property
+    pub fn get(&self, name: &Token) -> Result<Value, Error> {
+        let value = match name.lexeme().as_str() {
+            "message" => TokenLiteral::String(self.message.clone()),
+            "line" => match self.line {
+                Some(line) => TokenLiteral::Integer(line as i32),
+                None => TokenLiteral::Nil,
+            },
+            "stack" => TokenLiteral::String(self.stack.join("\n")),
+            _ => {
+                return Err(Error::runtime(
+                    name.clone(),
+                    &format!("Undefined property '{}'.", name.lexeme()),
+                ))
+            }
+        };
+        Ok(Value::new(value))
+    }
+}
+
+impl Display for ErrorObject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<error {:?}>", self.message)
+    }
+}
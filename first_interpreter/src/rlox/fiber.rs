@@ -0,0 +1,458 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    fmt::Display,
+    rc::Rc,
+};
+
+use corosensei::{stack::DefaultStack, Coroutine, CoroutineResult, Yielder};
+
+use crate::{
+    common::Error,
+    rlox::{Arity, Interpreter, NativeCallable, RloxCallable, Token, TokenLiteral, Unwind, Value},
+};
+
+/// The functions read from `Fiber`, with the number of arguments they take
+const FUNCTIONS: [(&str, Arity); 5] = [
+    ("now", Arity::exact(0)),
+    ("run", Arity::exact(0)),
+    ("sleep", Arity::exact(1)),
+    ("spawn", Arity::exact(1)),
+    ("yield", Arity::range(0, 1)),
+];
+
+/// The methods of a fiber, with the number of arguments they take
+const METHODS: [(&str, Arity); 2] = [("isDone", Arity::exact(0)), ("resume", Arity::range(0, 1))];
+
+/// The size of the call stack of every fiber, the same as the
+/// one the main thread gets on most platforms
+const STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// The coroutine a fiber runs on, which is resumed with the value
+/// passed to `resume`, yields the value passed to `Fiber.yield`
+/// and returns what the function of the fiber returned
+type FiberCoroutine = Coroutine<Option<Value>, Value, Result<Value, Unwind>, DefaultStack>;
+
+/// Represents a fiber, which runs a function on its own call stack
+/// until it yields, and picks up from there when resumed
+///
+/// A fiber can yield or sleep from any function it calls, as the
+/// frames of every call stay on its stack while it is paused.
+///
+/// Cloning a fiber clones the reference, so every copy is the same fiber
+#[derive(Clone)]
+pub struct RloxFiber {
+    state: Rc<RefCell<State>>,
+    /// The virtual time the fiber asked to sleep until, which the
+    /// scheduler reads once the fiber yields
+    wake: Rc<Cell<Option<i32>>>,
+}
+
+/// Connects the Interpreter running a fiber to whoever resumed it
+pub struct FiberLink<'a> {
+    yielder: &'a Yielder<Option<Value>, Value>,
+    wake: Rc<Cell<Option<i32>>>,
+}
+
+impl FiberLink<'_> {
+    /// Hands the value to whoever resumed the fiber, and waits
+    /// to be resumed again, returning the value resumed with
+    fn suspend(&self, value: Value) -> Value {
+        self.yielder
+            .suspend(value)
+            .unwrap_or_else(|| Value::new(TokenLiteral::Nil))
+    }
+}
+
+impl std::fmt::Debug for RloxFiber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+/// The progress of a fiber through its function
+enum State {
+    /// The fiber has not been resumed yet
+    New(Value),
+    /// The fiber is paused at a `Fiber.yield` or a `Fiber.sleep`
+    Suspended(FiberCoroutine),
+    /// The fiber is running, or resuming another fiber
+    Running,
+    /// The function of the fiber returned or failed
+    Done,
+}
+
+impl RloxFiber {
+    /// Constructs a new RloxFiber that calls the function once resumed
+    fn new(function: Value) -> RloxFiber {
+        RloxFiber {
+            state: Rc::new(RefCell::new(State::New(function))),
+            wake: Rc::new(Cell::new(None)),
+        }
+    }
+
+    /// Returns true if the function of the fiber returned or failed
+    pub fn is_done(&self) -> bool {
+        matches!(*self.state.borrow(), State::Done)
+    }
+
+    /// Returns true if both are the same fiber
+    pub fn same(&self, other: &RloxFiber) -> bool {
+        Rc::ptr_eq(&self.state, &other.state)
+    }
+
+    /// Returns the native method with the given name, bound to this fiber
+    pub fn method(&self, name: &Token) -> Result<Value, Error> {
+        match METHODS.iter().find(|(method, _)| *method == name.lexeme()) {
+            Some(_) => Ok(Value::new(NativeCallable::FiberMethod(FiberMethod {
+                fiber: self.clone(),
+                name: name.clone(),
+            }))),
+            None => Err(Error::runtime(
+                name.clone(),
+                &format!("Undefined fiber method '{}'.", name.lexeme()),
+            )),
+        }
+    }
+
+    /// Runs the fiber until it yields, returning the yielded value,
+    /// or until its function returns, returning what it returned
+    ///
+    /// The first resume calls the function with the value, if any,
+    /// and every later one hands the value to the paused `Fiber.yield`.
+    pub fn resume(
+        &self,
+        interpreter: &mut Interpreter,
+        value: Option<Value>,
+        paren: &Token,
+    ) -> Result<Value, Unwind> {
+        let state = std::mem::replace(&mut *self.state.borrow_mut(), State::Running);
+        let mut coroutine = match state {
+            State::New(function) => match self.start(interpreter, function, paren) {
+                Ok(coroutine) => coroutine,
+                Err(err) => {
+                    *self.state.borrow_mut() = State::Done;
+                    return Err(err.into());
+                }
+            },
+            State::Suspended(coroutine) => coroutine,
+            State::Running => {
+                return Err(Error::runtime(
+                    paren.clone(),
+                    "Cannot resume a fiber that is already running.",
+                )
+                .into())
+            }
+            State::Done => {
+                *self.state.borrow_mut() = State::Done;
+                return Err(Error::runtime(
+                    paren.clone(),
+                    "Cannot resume a fiber that has finished.",
+                )
+                .into());
+            }
+        };
+
+        match coroutine.resume(value) {
+            CoroutineResult::Yield(value) => {
+                *self.state.borrow_mut() = State::Suspended(coroutine);
+                Ok(value)
+            }
+            CoroutineResult::Return(result) => {
+                *self.state.borrow_mut() = State::Done;
+                result
+            }
+        }
+    }
+
+    /// Makes the coroutine that calls the function of the fiber on its
+    /// own stack, with an Interpreter sharing the globals and resolved
+    /// variables of the one that resumed it first
+    fn start(
+        &self,
+        interpreter: &Interpreter,
+        function: Value,
+        paren: &Token,
+    ) -> Result<FiberCoroutine, Error> {
+        let stack = DefaultStack::new(STACK_SIZE)
+            .map_err(|_| Error::runtime(paren.clone(), "Failed to start fiber."))?;
+        let globals = interpreter.globals();
+        let locals = interpreter.locals().clone();
+        let wake = self.wake.clone();
+        let paren = paren.clone();
+
+        Ok(Coroutine::with_stack(stack, move |yielder, value| {
+            let link = FiberLink { yielder, wake };
+            let mut interpreter = Interpreter::for_fiber(globals, locals, link);
+            interpreter.call_value(function, value.into_iter().collect(), &paren)
+        }))
+    }
+}
+
+impl Display for RloxFiber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<fiber>")
+    }
+}
+
+/// Represents the global `Fiber`, which makes fibers when called
+/// and holds the functions for pausing and scheduling them
+#[derive(Debug, Clone, Default)]
+pub struct FiberClass {
+    scheduler: Rc<RefCell<Scheduler>>,
+}
+
+/// Runs the fibers spawned onto it in turns, on a virtual clock
+/// that only moves on when every fiber is asleep
+#[derive(Debug, Default)]
+struct Scheduler {
+    /// The virtual time in milliseconds
+    clock: i32,
+    /// The fibers waiting for their turn
+    ready: VecDeque<RloxFiber>,
+    /// The fibers asleep, with the time they wake up at
+    sleeping: Vec<(i32, RloxFiber)>,
+}
+
+impl Scheduler {
+    /// Returns the fiber whose turn it is, moving the clock on
+    /// to when the next fiber wakes up if every fiber is asleep
+    fn next(&mut self) -> Option<RloxFiber> {
+        if self.ready.is_empty() {
+            if let Some(wake) = self.sleeping.iter().map(|(wake, _)| *wake).min() {
+                self.clock = self.clock.max(wake);
+            }
+        }
+        // fibers that woke up at the same time take their turns in
+        // the order they went to sleep in
+        let clock = self.clock;
+        let (mut awake, asleep) = std::mem::take(&mut self.sleeping)
+            .into_iter()
+            .partition::<Vec<_>, _>(|(wake, _)| *wake <= clock);
+        self.sleeping = asleep;
+        awake.sort_by_key(|(wake, _)| *wake);
+        self.ready.extend(awake.into_iter().map(|(_, fiber)| fiber));
+        self.ready.pop_front()
+    }
+}
+
+impl FiberClass {
+    /// Constructs a new FiberClass with nothing scheduled
+    pub fn new() -> FiberClass {
+        FiberClass::default()
+    }
+
+    /// Returns the function with the given name, like `Fiber.sleep`
+    pub fn get(&self, name: &Token) -> Result<Value, Error> {
+        match FUNCTIONS
+            .iter()
+            .find(|(function, _)| *function == name.lexeme())
+        {
+            Some(_) => Ok(Value::new(NativeCallable::FiberFunction(FiberFunction {
+                scheduler: self.scheduler.clone(),
+                name: name.clone(),
+            }))),
+            None => Err(Error::runtime(
+                name.clone(),
+                &format!("Undefined property '{}'.", name.lexeme()),
+            )),
+        }
+    }
+}
+
+impl Display for FiberClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<class Fiber>")
+    }
+}
+
+impl RloxCallable for FiberClass {
+    fn arity(&self) -> Arity {
+        Arity::exact(1)
+    }
+
+    fn call(&self, _interpreter: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, Unwind> {
+        let function = arguments
+            .into_iter()
+            .next()
+            .expect("arity is checked by the caller");
+        Ok(Value::new(RloxFiber::new(function)))
+    }
+}
+
+/// Represents a native function read from `Fiber`, like `Fiber.sleep`
+#[derive(Debug, Clone)]
+pub struct FiberFunction {
+    scheduler: Rc<RefCell<Scheduler>>,
+    /// The name the function was read with, used to report errors
+    name: Token,
+}
+
+impl FiberFunction {
+    /// Returns true if both are the same function of the same scheduler
+    pub fn same(&self, other: &FiberFunction) -> bool {
+        Rc::ptr_eq(&self.scheduler, &other.scheduler) && self.name.lexeme() == other.name.lexeme()
+    }
+
+    /// Returns the link of the fiber running the code, or an
+    /// error naming what cannot be done outside of a fiber
+    fn link<'a, 'b>(
+        &self,
+        interpreter: &'a Interpreter<'b>,
+        action: &str,
+    ) -> Result<&'a FiberLink<'b>, Error> {
+        interpreter.fiber().ok_or_else(|| {
+            Error::runtime(
+                self.name.clone(),
+                &format!("Cannot {} outside of a fiber.", action),
+            )
+        })
+    }
+}
+
+impl Display for FiberFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<native fn {}>", self.name.lexeme())
+    }
+}
+
+impl RloxCallable for FiberFunction {
+    fn arity(&self) -> Arity {
+        FUNCTIONS
+            .iter()
+            .find(|(function, _)| *function == self.name.lexeme())
+            .map(|(_, arity)| *arity)
+            .expect("fiber functions are only bound by FiberClass::get")
+    }
+
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, Unwind> {
+        let mut arguments = arguments.into_iter();
+        let nil = Value::new(TokenLiteral::Nil);
+
+        let value = match self.name.lexeme().as_str() {
+            "now" => Value::new(TokenLiteral::Integer(self.scheduler.borrow().clock)),
+            "yield" => {
+                let link = self.link(interpreter, "yield")?;
+                link.suspend(arguments.next().unwrap_or(nil))
+            }
+            "sleep" => {
+                let duration = arguments.next().and_then(|value| value.owned().ok());
+                let duration = match duration {
+                    Some(TokenLiteral::Integer(duration)) if duration >= 0 => duration,
+                    _ => {
+                        return Err(Error::runtime(
+                            self.name.clone(),
+                            "Sleep duration must be a non-negative integer.",
+                        )
+                        .into())
+                    }
+                };
+                let link = self.link(interpreter, "sleep")?;
+                let clock = self.scheduler.borrow().clock;
+                link.wake.set(Some(clock.saturating_add(duration)));
+                link.suspend(nil);
+                Value::new(TokenLiteral::Nil)
+            }
+            "spawn" => {
+                let function = arguments.next().expect("arity is checked by the caller");
+                let fiber = RloxFiber::new(function);
+                self.scheduler.borrow_mut().ready.push_back(fiber.clone());
+                Value::new(fiber)
+            }
+            "run" => {
+                // the scheduler is not borrowed while a fiber runs,
+                // so the fiber can spawn others and read the clock
+                loop {
+                    let Some(fiber) = self.scheduler.borrow_mut().next() else {
+                        break;
+                    };
+                    fiber.resume(interpreter, None, &self.name)?;
+                    if fiber.is_done() {
+                        continue;
+                    }
+                    let mut scheduler = self.scheduler.borrow_mut();
+                    match fiber.wake.take() {
+                        Some(wake) => scheduler.sleeping.push((wake, fiber)),
+                        None => scheduler.ready.push_back(fiber),
+                    }
+                }
+                Value::new(TokenLiteral::Nil)
+            }
+            _ => unreachable!("fiber functions are only bound by FiberClass::get"),
+        };
+        Ok(value)
+    }
+}
+
+/// Represents a native method read from a fiber, like `worker.resume`
+#[derive(Debug, Clone)]
+pub struct FiberMethod {
+    fiber: RloxFiber,
+    /// The name the method was read with, used to report errors
+    name: Token,
+}
+
+impl FiberMethod {
+    /// Returns true if both are the same method of the same fiber
+    pub fn same(&self, other: &FiberMethod) -> bool {
+        self.fiber.same(&other.fiber) && self.name.lexeme() == other.name.lexeme()
+    }
+}
+
+impl Display for FiberMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<native fn {}>", self.name.lexeme())
+    }
+}
+
+impl RloxCallable for FiberMethod {
+    fn arity(&self) -> Arity {
+        METHODS
+            .iter()
+            .find(|(method, _)| *method == self.name.lexeme())
+            .map(|(_, arity)| *arity)
+            .expect("fiber methods are only bound by RloxFiber::method")
+    }
+
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, Unwind> {
+        match self.name.lexeme().as_str() {
+            "isDone" => Ok(Value::new(TokenLiteral::Boolean(self.fiber.is_done()))),
+            "resume" => {
+                let value = arguments.into_iter().next();
+                self.fiber.resume(interpreter, value, &self.name)
+            }
+            _ => unreachable!("fiber methods are only bound by RloxFiber::method"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fiber() -> RloxFiber {
+        RloxFiber::new(Value::new(TokenLiteral::Nil))
+    }
+
+    #[test]
+    fn test_scheduler_order() {
+        let (a, b, c, d) = (fiber(), fiber(), fiber(), fiber());
+        let mut scheduler = Scheduler::default();
+        scheduler.sleeping.push((20, a.clone()));
+        scheduler.sleeping.push((10, b.clone()));
+        scheduler.sleeping.push((10, c.clone()));
+        scheduler.ready.push_back(d.clone());
+
+        // the clock stands still while any fiber is ready
+        assert!(scheduler.next().unwrap().same(&d));
+        assert_eq!(scheduler.clock, 0);
+
+        // fibers waking up together keep the order they went to sleep in
+        assert!(scheduler.next().unwrap().same(&b));
+        assert_eq!(scheduler.clock, 10);
+        assert!(scheduler.next().unwrap().same(&c));
+        assert!(scheduler.next().unwrap().same(&a));
+        assert_eq!(scheduler.clock, 20);
+        assert!(scheduler.next().is_none());
+    }
+}
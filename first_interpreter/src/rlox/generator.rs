@@ -0,0 +1,577 @@
+use std::{cell::RefCell, fmt::Display, rc::Rc};
+
+use crate::{
+    common::Error,
+    rlox::{
+        environment::Environment, Arity, Completion, ErrorObject, Interpreter, NativeCallable,
+        RloxCallable, RloxIterator, Token, TokenLiteral, Unwind, Value,
+    },
+    stmt::{ForIn, Stmt, Try, While},
+};
+
+/// The native methods of a generator, with the number of arguments they take
+const METHODS: [(&str, Arity); 3] = [
+    ("hasNext", Arity::exact(0)),
+    ("iterator", Arity::exact(0)),
+    ("next", Arity::exact(0)),
+];
+
+/// Represents a call to a generator function, whose body runs up to its
+/// next `yield` every time `next()` is called on it
+///
+/// Cloning a generator clones the reference, so every copy resumes the same body
+#[derive(Clone)]
+pub struct RloxGenerator {
+    name: String,
+    state: Rc<RefCell<State>>,
+}
+
+impl std::fmt::Debug for RloxGenerator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+/// The progress of a generator through its body
+enum State {
+    /// The body is paused, with what is left of it on the frames
+    Suspended(Vec<Frame>),
+    /// The body is paused after yielding a value that `hasNext()` ran
+    /// ahead for, which the next resume hands out without running it
+    Peeked(Value, Vec<Frame>),
+    /// The body is running, so it cannot be resumed until it pauses
+    Running,
+    /// The body returned or failed, so it has nothing more to yield
+    Done,
+}
+
+/// Represents a statement the body of a generator is in the middle of
+///
+/// Only statements with a `yield` in them get a frame, to run them one
+/// step at a time. The Interpreter runs every other statement at once.
+enum Frame {
+    /// Statements run one after another in a scope
+    Block {
+        statements: Vec<Stmt>,
+        next: usize,
+        environment: Rc<RefCell<Environment>>,
+    },
+    /// A while loop, which runs its increment before checking
+    /// its condition again once it is `looping`
+    While {
+        stmt: While,
+        environment: Rc<RefCell<Environment>>,
+        looping: bool,
+    },
+    /// A for-in loop with its progress through the iterable
+    ForIn {
+        stmt: ForIn,
+        iterator: RloxIterator,
+        environment: Rc<RefCell<Environment>>,
+    },
+    /// A try statement with the part of it that is running
+    Try {
+        stmt: Try,
+        stage: Stage,
+        environment: Rc<RefCell<Environment>>,
+    },
+}
+
+/// The part of a try statement that is running
+enum Stage {
+    Body,
+    Catch,
+    /// The finally block, with the outcome of the rest of the
+    /// statement to hand on once it completes normally
+    Finally(Option<Result<Completion, Error>>),
+}
+
+/// What running the frame on top for one step led to
+enum Step {
+    /// The body goes on from the frames as they are
+    Next,
+    /// The body yielded a value
+    Yield(Value),
+    /// A statement did not complete normally, or failed
+    Abrupt(Result<Completion, Error>),
+}
+
+impl RloxGenerator {
+    /// Constructs a new RloxGenerator, paused before the first
+    /// statement of the body, with the parameters bound in the scope
+    pub fn new(
+        name: String,
+        body: Vec<Stmt>,
+        environment: Rc<RefCell<Environment>>,
+    ) -> RloxGenerator {
+        let frame = Frame::Block {
+            statements: body,
+            next: 0,
+            environment,
+        };
+        RloxGenerator {
+            name,
+            state: Rc::new(RefCell::new(State::Suspended(vec![frame]))),
+        }
+    }
+
+    /// Returns true if both values are the same generator
+    pub fn same(&self, other: &RloxGenerator) -> bool {
+        Rc::ptr_eq(&self.state, &other.state)
+    }
+
+    /// Returns the native method with the given name, bound to this generator
+    pub fn method(&self, name: &Token) -> Result<Value, Error> {
+        match METHODS.iter().find(|(method, _)| *method == name.lexeme()) {
+            Some(_) => Ok(Value::new(NativeCallable::GeneratorMethod(
+                GeneratorMethod {
+                    generator: self.clone(),
+                    name: name.clone(),
+                },
+            ))),
+            None => Err(Error::runtime(
+                name.clone(),
+                &format!("Undefined generator method '{}'.", name.lexeme()),
+            )),
+        }
+    }
+
+    /// Runs the body up to its next `yield` and returns the yielded value,
+    /// or None once the body has returned
+    ///
+    /// An exception or error from the body ends the generator
+    pub fn resume(
+        &self,
+        interpreter: &mut Interpreter,
+        token: &Token,
+    ) -> Result<Option<Value>, Unwind> {
+        let state = std::mem::replace(&mut *self.state.borrow_mut(), State::Running);
+        let mut frames = match state {
+            State::Suspended(frames) => frames,
+            State::Peeked(value, frames) => {
+                *self.state.borrow_mut() = State::Suspended(frames);
+                return Ok(Some(value));
+            }
+            State::Running => {
+                return Err(Error::runtime(
+                    token.clone(),
+                    "Cannot resume a generator from inside its own body.",
+                )
+                .into())
+            }
+            State::Done => {
+                *self.state.borrow_mut() = State::Done;
+                return Ok(None);
+            }
+        };
+
+        let result = run(interpreter, &mut frames);
+        *self.state.borrow_mut() = match result {
+            Ok(Some(_)) => State::Suspended(frames),
+            _ => State::Done,
+        };
+        result
+    }
+
+    /// Returns true if the body yields another value, running it up
+    /// to that `yield` and keeping the value for the next resume
+    pub fn has_next(&self, interpreter: &mut Interpreter, token: &Token) -> Result<bool, Unwind> {
+        let Some(value) = self.resume(interpreter, token)? else {
+            return Ok(false);
+        };
+        let mut state = self.state.borrow_mut();
+        match std::mem::replace(&mut *state, State::Done) {
+            State::Suspended(frames) => *state = State::Peeked(value, frames),
+            _ => unreachable!("a generator is suspended after it yields"),
+        }
+        Ok(true)
+    }
+}
+
+impl Display for RloxGenerator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<generator {}>", self.name)
+    }
+}
+
+/// Represents a native method read from a generator, like `lines.next`
+#[derive(Debug, Clone)]
+pub struct GeneratorMethod {
+    generator: RloxGenerator,
+    /// The name the method was read with, used to report errors
+    name: Token,
+}
+
+impl GeneratorMethod {
+    /// Returns true if both are the same method of the same generator
+    pub fn same(&self, other: &GeneratorMethod) -> bool {
+        self.generator.same(&other.generator) && self.name.lexeme() == other.name.lexeme()
+    }
+}
+
+impl Display for GeneratorMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<native fn {}>", self.name.lexeme())
+    }
+}
+
+impl RloxCallable for GeneratorMethod {
+    fn arity(&self) -> Arity {
+        Arity::exact(0)
+    }
+
+    fn call(&self, interpreter: &mut Interpreter, _arguments: Vec<Value>) -> Result<Value, Unwind> {
+        match self.name.lexeme().as_str() {
+            // a generator is its own iterator, so for-in loops can run it
+            "iterator" => Ok(Value::new(self.generator.clone())),
+            "hasNext" => Ok(Value::new(TokenLiteral::Boolean(
+                self.generator.has_next(interpreter, &self.name)?,
+            ))),
+            // nil once the body has returned, which `hasNext()` tells
+            // apart from a yielded nil
+            "next" => Ok(self
+                .generator
+                .resume(interpreter, &self.name)?
+                .unwrap_or_else(|| Value::new(TokenLiteral::Nil))),
+            _ => unreachable!("generator methods are only bound by RloxGenerator::method"),
+        }
+    }
+}
+
+/// Runs the frames until the body yields a value, returning None
+/// once there are no frames left
+fn run(interpreter: &mut Interpreter, frames: &mut Vec<Frame>) -> Result<Option<Value>, Unwind> {
+    while !frames.is_empty() {
+        let outcome = match step(interpreter, frames) {
+            Step::Next => continue,
+            Step::Yield(value) => return Ok(Some(value)),
+            Step::Abrupt(outcome) => outcome,
+        };
+        match unwind(frames, outcome) {
+            // an exception leaves the body through the call that resumed it
+            Some(Ok(completion @ Completion::Throw(_))) => return Err(Unwind::Throw(completion)),
+            Some(Err(err)) => return Err(err.into()),
+            // a return ends the body, and its value is not yielded
+            Some(Ok(_)) => return Ok(None),
+            None => {}
+        }
+    }
+    Ok(None)
+}
+
+/// Runs the frame on top for one step, which takes it off the frames
+/// once it is over
+fn step(interpreter: &mut Interpreter, frames: &mut Vec<Frame>) -> Step {
+    let frame = frames.pop().expect("only frames that exist are run");
+    match frame {
+        Frame::Block {
+            statements,
+            next,
+            environment,
+        } => {
+            let Some(stmt) = statements.get(next).cloned() else {
+                return Step::Next;
+            };
+            frames.push(Frame::Block {
+                statements,
+                next: next + 1,
+                environment: environment.clone(),
+            });
+            enter(interpreter, frames, stmt, environment)
+        }
+        Frame::While {
+            stmt,
+            environment,
+            looping,
+        } => {
+            if let (true, Some(increment)) = (looping, stmt.increment()) {
+                if let Err(err) = interpreter.evaluate_in(increment, environment.clone()) {
+                    return Step::Abrupt(err.into_completion());
+                }
+            }
+            match interpreter.evaluate_in(stmt.condition(), environment.clone()) {
+                Ok(value) => match value.owned::<TokenLiteral>() {
+                    Ok(TokenLiteral::Boolean(true)) => {
+                        let body = vec![*stmt.body()];
+                        frames.push(Frame::While {
+                            stmt,
+                            environment: environment.clone(),
+                            looping: true,
+                        });
+                        frames.push(block(body, environment));
+                        Step::Next
+                    }
+                    Ok(TokenLiteral::Boolean(false)) => Step::Next,
+                    _ => Step::Abrupt(Err(Error::generic(
+                        "Condition in while statement must evaluate to 'true' or 'false'",
+                    ))),
+                },
+                Err(err) => Step::Abrupt(err.into_completion()),
+            }
+        }
+        Frame::ForIn {
+            stmt,
+            mut iterator,
+            environment,
+        } => match iterator.next(interpreter) {
+            Ok(Some(value)) => {
+                // every iteration gets a fresh variable, the same as in the Interpreter
+                let mut scope = Environment::with_parent(environment.clone());
+                scope.define(stmt.name().lexeme(), value);
+                let body = vec![*stmt.body()];
+                frames.push(Frame::ForIn {
+                    stmt,
+                    iterator,
+                    environment,
+                });
+                frames.push(block(body, Rc::new(RefCell::new(scope))));
+                Step::Next
+            }
+            Ok(None) => Step::Next,
+            Err(err) => Step::Abrupt(err.into_completion()),
+        },
+        // the part of the try statement that was running completed normally
+        Frame::Try {
+            stmt,
+            stage,
+            environment,
+        } => match (stage, stmt.finally()) {
+            (Stage::Finally(Some(outcome)), _) => Step::Abrupt(outcome),
+            (Stage::Finally(None), _) | (_, None) => Step::Next,
+            (_, Some(finally)) => {
+                let scope = Environment::with_parent(environment.clone());
+                frames.push(Frame::Try {
+                    stmt,
+                    stage: Stage::Finally(None),
+                    environment,
+                });
+                frames.push(block(finally, Rc::new(RefCell::new(scope))));
+                Step::Next
+            }
+        },
+    }
+}
+
+/// Starts running a statement of the body in the given scope
+///
+/// A statement without a `yield` in it runs to its end right away,
+/// and any other one gets the frames it needs to run step by step
+fn enter(
+    interpreter: &mut Interpreter,
+    frames: &mut Vec<Frame>,
+    stmt: Stmt,
+    environment: Rc<RefCell<Environment>>,
+) -> Step {
+    if !yields(&stmt) {
+        return match interpreter.execute_block(vec![stmt], environment) {
+            Ok(Completion::Normal) => Step::Next,
+            outcome => Step::Abrupt(outcome),
+        };
+    }
+    match stmt {
+        Stmt::Yield(stmt) => match interpreter.evaluate_in(stmt.value(), environment) {
+            Ok(value) => Step::Yield(value),
+            Err(err) => Step::Abrupt(err.into_completion()),
+        },
+        Stmt::Block(stmt) => {
+            let scope = Environment::with_parent(environment);
+            frames.push(block(stmt.statements(), Rc::new(RefCell::new(scope))));
+            Step::Next
+        }
+        Stmt::If(stmt) => match interpreter.evaluate_in(stmt.condition(), environment.clone()) {
+            Ok(value) => {
+                let branch = match value.owned::<TokenLiteral>() {
+                    Ok(TokenLiteral::Boolean(true)) => Some(stmt.then_branch()),
+                    Ok(TokenLiteral::Boolean(false)) => stmt.else_branch(),
+                    _ => {
+                        return Step::Abrupt(Err(Error::runtime(
+                            stmt.keyword(),
+                            "Condition in if statement must evaluate to 'true' or 'false'",
+                        )))
+                    }
+                };
+                if let Some(branch) = branch {
+                    frames.push(block(vec![branch], environment));
+                }
+                Step::Next
+            }
+            Err(err) => Step::Abrupt(err.into_completion()),
+        },
+        Stmt::While(stmt) => {
+            frames.push(Frame::While {
+                stmt,
+                environment,
+                looping: false,
+            });
+            Step::Next
+        }
+        Stmt::ForIn(stmt) => {
+            let iterable = match interpreter.evaluate_in(stmt.iterable(), environment.clone()) {
+                Ok(iterable) => iterable,
+                Err(err) => return Step::Abrupt(err.into_completion()),
+            };
+            match RloxIterator::new(interpreter, iterable, &stmt.keyword()) {
+                Ok(iterator) => {
+                    frames.push(Frame::ForIn {
+                        stmt,
+                        iterator,
+                        environment,
+                    });
+                    Step::Next
+                }
+                Err(err) => Step::Abrupt(err.into_completion()),
+            }
+        }
+        Stmt::Match(stmt) => match interpreter.match_arm(&stmt, environment) {
+            Ok(Some((body, scope))) => {
+                frames.push(block(vec![body], scope));
+                Step::Next
+            }
+            Ok(None) => Step::Next,
+            Err(err) => Step::Abrupt(err.into_completion()),
+        },
+        Stmt::Try(stmt) => {
+            let scope = Environment::with_parent(environment.clone());
+            let body = stmt.body();
+            frames.push(Frame::Try {
+                stmt,
+                stage: Stage::Body,
+                environment,
+            });
+            frames.push(block(body, Rc::new(RefCell::new(scope))));
+            Step::Next
+        }
+        _ => unreachable!("only statements that hold other statements can yield"),
+    }
+}
+
+/// Hands a completion other than a normal one, or an error, to the
+/// frames from the top down until one of them handles it
+///
+/// Returns the outcome back if none of the frames handles it,
+/// once every frame has been taken off
+fn unwind(
+    frames: &mut Vec<Frame>,
+    mut outcome: Result<Completion, Error>,
+) -> Option<Result<Completion, Error>> {
+    while let Some(frame) = frames.pop() {
+        match frame {
+            Frame::Block { .. } => {}
+            Frame::While {
+                stmt, environment, ..
+            } => match &outcome {
+                Ok(Completion::Break(label)) if Interpreter::targets_loop(label, stmt.label()) => {
+                    return None
+                }
+                // the increment still runs before the next iteration
+                Ok(Completion::Continue(label))
+                    if Interpreter::targets_loop(label, stmt.label()) =>
+                {
+                    frames.push(Frame::While {
+                        stmt,
+                        environment,
+                        looping: true,
+                    });
+                    return None;
+                }
+                _ => {}
+            },
+            Frame::ForIn {
+                stmt,
+                iterator,
+                environment,
+            } => match &outcome {
+                Ok(Completion::Break(label)) if Interpreter::targets_loop(label, stmt.label()) => {
+                    return None
+                }
+                Ok(Completion::Continue(label))
+                    if Interpreter::targets_loop(label, stmt.label()) =>
+                {
+                    frames.push(Frame::ForIn {
+                        stmt,
+                        iterator,
+                        environment,
+                    });
+                    return None;
+                }
+                _ => {}
+            },
+            Frame::Try {
+                stmt,
+                stage,
+                environment,
+            } => {
+                let thrown = matches!(outcome, Ok(Completion::Throw(_)) | Err(_));
+                if let (Stage::Body, Some(catch), true) = (&stage, stmt.catch(), thrown) {
+                    let caught = match outcome {
+                        Ok(Completion::Throw(thrown)) => Ok(thrown.value()),
+                        Err(err) => ErrorObject::catch(err),
+                        Ok(_) => unreachable!("only exceptions and errors are caught"),
+                    };
+                    match caught {
+                        Ok(value) => {
+                            let mut scope = Environment::with_parent(environment.clone());
+                            scope.define(catch.name().lexeme(), value);
+                            frames.push(Frame::Try {
+                                stmt,
+                                stage: Stage::Catch,
+                                environment,
+                            });
+                            frames.push(block(catch.body(), Rc::new(RefCell::new(scope))));
+                            return None;
+                        }
+                        // errors that cannot be caught still run the finally block
+                        Err(err) => outcome = Err(err),
+                    }
+                }
+                // an outcome of the finally block itself replaces the one it was handed
+                if let (false, Some(finally)) = (matches!(stage, Stage::Finally(_)), stmt.finally())
+                {
+                    let scope = Environment::with_parent(environment.clone());
+                    frames.push(Frame::Try {
+                        stmt,
+                        stage: Stage::Finally(Some(outcome)),
+                        environment,
+                    });
+                    frames.push(block(finally, Rc::new(RefCell::new(scope))));
+                    return None;
+                }
+            }
+        }
+    }
+    Some(outcome)
+}
+
+/// Builds the frame for statements run one after another in the scope
+fn block(statements: Vec<Stmt>, environment: Rc<RefCell<Environment>>) -> Frame {
+    Frame::Block {
+        statements,
+        next: 0,
+        environment,
+    }
+}
+
+/// Returns true if a `yield` is among the statement or the statements it
+/// holds, leaving out the bodies of the functions and classes declared in it
+fn yields(stmt: &Stmt) -> bool {
+    match stmt {
+        Stmt::Yield(_) => true,
+        Stmt::Block(stmt) => stmt.statements().iter().any(yields),
+        Stmt::If(stmt) => {
+            yields(&stmt.then_branch()) || stmt.else_branch().is_some_and(|branch| yields(&branch))
+        }
+        Stmt::While(stmt) => yields(&stmt.body()),
+        Stmt::ForIn(stmt) => yields(&stmt.body()),
+        Stmt::Match(stmt) => stmt.arms().iter().any(|arm| yields(&arm.body())),
+        Stmt::Try(stmt) => {
+            stmt.body().iter().any(yields)
+                || stmt
+                    .catch()
+                    .is_some_and(|catch| catch.body().iter().any(yields))
+                || stmt
+                    .finally()
+                    .is_some_and(|finally| finally.iter().any(yields))
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,98 @@
+use std::{cell::RefCell, collections::HashMap, fmt::Display, rc::Rc};
+
+use crate::{
+    common::Error,
+    rlox::{Token, Value},
+    stmt::{RloxClass, RloxFunction},
+};
+
+/// Represents an instance of a class at runtime
+///
+/// Cloning an instance clones the reference, so every copy
+/// sees the fields set through the others
+#[derive(Debug, Clone)]
+pub struct RloxInstance {
+    class: RloxClass,
+    fields: Rc<RefCell<HashMap<String, Value>>>,
+    /// The class that declares each private field, which is the
+    /// one whose code set the field first
+    private_fields: Rc<RefCell<HashMap<String, RloxClass>>>,
+}
+
+impl RloxInstance {
+    /// Constructs a new RloxInstance with no fields
+    pub fn new(class: RloxClass) -> RloxInstance {
+        RloxInstance {
+            class,
+            fields: Rc::new(RefCell::new(HashMap::new())),
+            private_fields: Rc::new(RefCell::new(HashMap::new())),
+        }
+    }
+
+    /// Returns the class the instance was made from
+    pub fn class(&self) -> &RloxClass {
+        &self.class
+    }
+
+    /// Returns the value of a property
+    ///
+    /// Fields shadow methods, and methods are bound to the instance
+    pub fn get(&self, name: &Token) -> Result<Value, Error> {
+        if let Some(value) = self.fields.borrow().get(&name.lexeme()) {
+            return Ok(value.clone());
+        }
+        if let Some(method) = self.class.find_method(&name.lexeme()) {
+            return Ok(Value::new(method.bind(self.clone())));
+        }
+        Err(Error::runtime(
+            name.clone(),
+            &format!("Undefined property '{}'.", name.lexeme()),
+        ))
+    }
+
+    /// Returns the getter the class defines for a property, bound to
+    /// the instance, unless a field of the instance shadows it
+    pub fn getter(&self, name: &Token) -> Option<RloxFunction> {
+        if self.fields.borrow().contains_key(&name.lexeme()) {
+            return None;
+        }
+        self.class
+            .find_method(&name.lexeme())
+            .filter(RloxFunction::is_getter)
+            .map(|getter| getter.bind(self.clone()))
+    }
+
+    /// Sets the value of a field, creating the field if needed
+    pub fn set(&self, name: &Token, value: Value) {
+        self.fields.borrow_mut().insert(name.lexeme(), value);
+    }
+
+    /// Records the class whose code set a private field, unless
+    /// another class declared the field before
+    pub fn declare_private(&self, name: &str, class: RloxClass) {
+        self.private_fields
+            .borrow_mut()
+            .entry(name.to_string())
+            .or_insert(class);
+    }
+
+    /// Returns the class that declares a property, which is the class
+    /// that set the field first or the one that defines the method
+    pub fn declaring_class(&self, name: &str) -> Option<RloxClass> {
+        if let Some(class) = self.private_fields.borrow().get(name) {
+            return Some(class.clone());
+        }
+        self.class.method_class(name)
+    }
+
+    /// Returns true if both values are the same instance
+    pub fn same(&self, other: &RloxInstance) -> bool {
+        Rc::ptr_eq(&self.fields, &other.fields)
+    }
+}
+
+impl Display for RloxInstance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<{} instance>", self.class.name())
+    }
+}
@@ -1,61 +1,151 @@
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::rc::Rc;
 
-use crate::common::errors::Error;
-use crate::common::MAX_FUNCTION_ARGUMENTS_SIZE;
+use crate::common::errors::{Error, ThrownValue};
+use crate::common::{NodeId, SideTable, MAX_FUNCTION_ARGUMENTS_SIZE};
 use crate::expressions::expr::{Expr, Visitor as ExprVisitor};
 
 use crate::rlox::environment::Environment;
-use crate::rlox::token::{TokenLiteral, TokenType};
-use crate::rlox::RloxCallable;
+use crate::rlox::fiber::FiberLink;
+use crate::rlox::resolver::Resolver;
+use crate::rlox::token::{Token, TokenLiteral, TokenType};
 use crate::rlox::Value;
-use crate::rlox::{ClockFunction, NativeCallable};
+use crate::rlox::{ClockFunction, ErrorObject, NativeCallable, RangeFunction, RloxList, RloxMap};
+use crate::rlox::{Completion, RloxCallable, RloxInstance, RloxIterator, RloxRange, Unwind};
+use crate::rlox::{FiberClass, RloxFiber};
+use crate::rlox::{RloxEnum, RloxGenerator, RloxVariant};
 use crate::stmt::stmt::Visitor as StmtVisitor;
-use crate::stmt::{Class, Function, RloxFunction, Stmt};
+use crate::stmt::{CatchClause, RloxClass, RloxFunction, RloxTrait, Stmt};
 
-pub struct Interpreter {
+/// The name the class is bound to in the scope around its methods,
+/// which no variable can have, to tell which class code belongs to
+const HOME_CLASS: &str = "#class";
+
+/// The body of the match arm that was picked, with the scope of its bindings
+type MatchedArm = (Stmt, Rc<RefCell<Environment>>);
+
+pub struct Interpreter<'a> {
     environment: Rc<RefCell<Environment>>,
     globals: Rc<RefCell<Environment>>,
+    /// The number of scopes between every resolved local variable and
+    /// its declaration, keyed by the id of the node using the variable
+    locals: SideTable<usize>,
+    /// The link to whoever resumes the fiber this Interpreter runs,
+    /// which is None for the one running the program
+    fiber: Option<FiberLink<'a>>,
 }
 
-impl Interpreter {
+impl<'a> Interpreter<'a> {
     /// Construct a new Interpreter
-    pub fn new() -> Interpreter {
+    pub fn new() -> Interpreter<'a> {
         let globals = Rc::new(RefCell::new(Environment::new()));
 
         let clock_function = ClockFunction::new();
+        globals.borrow_mut().define(
+            "clock".to_string(),
+            Value::new(NativeCallable::ClockFunction(clock_function)),
+        );
+        globals.borrow_mut().define(
+            "range".to_string(),
+            Value::new(NativeCallable::RangeFunction(RangeFunction::new())),
+        );
         globals
             .borrow_mut()
-            .define("clock".to_string(), Value::new(clock_function));
+            .define("Fiber".to_string(), Value::new(FiberClass::new()));
 
         let environment = globals.clone();
         Interpreter {
             globals,
             environment,
+            locals: SideTable::new(),
+            fiber: None,
+        }
+    }
+
+    /// Construct a new Interpreter for running a fiber, sharing the
+    /// globals and resolved variables of the program that started it
+    pub(crate) fn for_fiber(
+        globals: Rc<RefCell<Environment>>,
+        locals: SideTable<usize>,
+        fiber: FiberLink<'a>,
+    ) -> Interpreter<'a> {
+        Interpreter {
+            environment: globals.clone(),
+            globals,
+            locals,
+            fiber: Some(fiber),
         }
     }
 
+    /// Returns the link of the fiber this Interpreter runs, if any
+    pub(crate) fn fiber(&self) -> Option<&FiberLink<'a>> {
+        self.fiber.as_ref()
+    }
+
     /// Returns the global environment
     pub fn globals(&self) -> Rc<RefCell<Environment>> {
         self.globals.clone()
     }
 
     /// Begins the interpretation and evaluation process
+    ///
+    /// The statements are resolved before any of them runs.
+    /// Errors that are not caught by a try statement are reported here.
     pub fn interpret(&mut self, statements: Vec<Stmt>) -> Result<(), Error> {
+        Resolver::new(self).resolve(&statements)?;
+
         for statement in statements {
-            self.execute(statement)?;
+            match self.execute(statement) {
+                Ok(Completion::Normal) => {}
+                Ok(Completion::Throw(thrown)) => {
+                    // the value is printed as print would, unless that throws too
+                    let message = match self.stringify(&thrown.value()) {
+                        Ok(text) => format!("Uncaught exception: {}", text),
+                        Err(Unwind::Throw(_)) => "Uncaught exception".to_string(),
+                        Err(Unwind::Error(err)) => {
+                            err.report();
+                            return Err(err);
+                        }
+                    };
+                    let err = Error::runtime(thrown.keyword().clone(), &message);
+                    err.report();
+                    return Err(err);
+                }
+                // the resolver rejects return, break and continue
+                // outside of functions and loops
+                Ok(_) => break,
+                Err(err) => {
+                    err.report();
+                    return Err(err);
+                }
+            }
         }
         Ok(())
     }
 
     /// Executes a given statement
-    pub fn execute(&mut self, stmt: Stmt) -> Result<(), Error> {
-        stmt.accept(self)
+    pub fn execute(&mut self, stmt: Stmt) -> Result<Completion, Error> {
+        // an exception thrown inside a call is the completion
+        // of the statement that made the call
+        stmt.accept(self).or_else(Unwind::into_completion)
     }
 
     /// Evaluates a given expression to a literal
-    pub fn evaluate(&mut self, expr: Expr) -> Result<Value, Error> {
-        Ok(expr.accept(self)?)
+    pub fn evaluate(&mut self, expr: Expr) -> Result<Value, Unwind> {
+        expr.accept(self)
+    }
+
+    /// Evaluates an expression in the context of the given environment
+    pub fn evaluate_in(
+        &mut self,
+        expr: Expr,
+        environment: Rc<RefCell<Environment>>,
+    ) -> Result<Value, Unwind> {
+        let previous = std::mem::replace(&mut self.environment, environment);
+        let result = self.evaluate(expr);
+        self.environment = previous;
+        result
     }
 
     /// Executes a list of statements in the context of the given environment
@@ -63,56 +153,352 @@ impl Interpreter {
         &mut self,
         statements: Vec<Stmt>,
         environment: Rc<RefCell<Environment>>,
-    ) -> Result<(), Error> {
+    ) -> Result<Completion, Error> {
         // replace the interpreter's environment with the one from the context
         // this is so that the statements are executed with their scopes in view
         let previous = std::mem::replace(&mut self.environment, environment);
         for statement in statements {
-            // if there is an error executing a statement, switch to the original
-            // scope before terminating the execution pipeline
-            if let Err(err) = self.execute(statement) {
-                self.environment = previous;
-                return Err(err);
-            };
+            // if a statement fails or does not complete normally, switch to
+            // the original scope before handing the outcome up
+            match self.execute(statement) {
+                Ok(Completion::Normal) => {}
+                result => {
+                    self.environment = previous;
+                    return result;
+                }
+            }
         }
         // set it back to the original environment
         self.environment = previous;
-        Ok(())
+        Ok(Completion::Normal)
     }
 
-    /// Resolves something TODO
-    pub fn resolve(&self, expr: Expr, depth: usize) {
-        self.locals.insert(expr, depth);
+    /// Returns true if a break or continue with the given label
+    /// applies to the loop, which is the case for every unlabeled one
+    pub(crate) fn targets_loop(label: &Option<String>, loop_label: Option<Token>) -> bool {
+        match label {
+            Some(label) => loop_label.map(|name| name.lexeme()).as_ref() == Some(label),
+            None => true,
+        }
     }
-}
 
-impl ExprVisitor<Result<Value, Error>> for Interpreter {
-    fn visit_assign_expr(
+    /// Returns the body of the first arm of a match statement whose pattern
+    /// and guard accept the subject, along with the scope of the names the
+    /// pattern binds, evaluating the subject in the given environment
+    pub(crate) fn match_arm(
         &mut self,
-        expr: &crate::expressions::assign::Assign,
-    ) -> Result<Value, Error> {
-        let value = self.evaluate(expr.value())?;
-        self.environment
-            .borrow_mut()
-            .assign(expr.name(), value.clone())?;
-        Ok(value)
+        stmt: &crate::stmt::Match,
+        environment: Rc<RefCell<Environment>>,
+    ) -> Result<Option<MatchedArm>, Unwind> {
+        let subject = self.evaluate_in(stmt.subject(), environment.clone())?;
+        for arm in stmt.arms() {
+            let mut bindings = Vec::new();
+            if !arm.pattern().matches(&subject, &mut bindings)? {
+                continue;
+            }
+
+            // the names the pattern binds share a scope with the guard and the body
+            let mut scope = Environment::with_parent(environment.clone());
+            for (name, value) in bindings {
+                scope.define(name.lexeme(), value);
+            }
+            let scope = Rc::new(RefCell::new(scope));
+
+            if let Some(guard) = arm.guard() {
+                let value = self.evaluate_in(guard, scope.clone())?;
+                match value.borrowed::<TokenLiteral>() {
+                    Ok(TokenLiteral::Boolean(true)) => {}
+                    Ok(TokenLiteral::Boolean(false)) => continue,
+                    _ => {
+                        return Err(Error::runtime(
+                            stmt.keyword().clone(),
+                            "Guard in match arm must evaluate to 'true' or 'false'",
+                        )
+                        .into())
+                    }
+                }
+            }
+
+            return Ok(Some((arm.body(), scope)));
+        }
+        Ok(None)
     }
 
-    fn visit_binary_expr(
+    /// Executes the body of a catch clause with the caught value bound
+    fn execute_catch(&mut self, catch: CatchClause, value: Value) -> Result<Completion, Error> {
+        let mut environment = Environment::with_parent(self.environment.clone());
+        environment.define(catch.name().lexeme(), value);
+        self.execute_block(catch.body(), Rc::new(RefCell::new(environment)))
+    }
+
+    /// Returns the methods a class copies from its traits
+    ///
+    /// Two traits may only define the same method if the class overrides it
+    fn trait_methods(
         &mut self,
-        expr: &crate::expressions::binary::Binary,
-    ) -> Result<Value, Error> {
-        let left = self.evaluate(expr.left())?.owned::<TokenLiteral>()?;
-        let right = self.evaluate(expr.right())?.owned::<TokenLiteral>()?;
+        stmt: &crate::stmt::Class,
+    ) -> Result<HashMap<String, RloxFunction>, Unwind> {
+        let mut methods = HashMap::new();
+        let mut defined_by: HashMap<String, String> = HashMap::new();
+        let mut used: Vec<RloxTrait> = Vec::new();
+        for variable in stmt.traits() {
+            let trait_ = match self.evaluate(Expr::Variable(variable.clone()))? {
+                value if value.is::<RloxTrait>() => value.owned::<RloxTrait>()?,
+                _ => {
+                    return Err(Error::runtime(
+                        variable.name().clone(),
+                        "A class can only be declared with traits.",
+                    )
+                    .into())
+                }
+            };
+            // the same trait may be used under another name
+            if used.iter().any(|other| other.same(&trait_)) {
+                return Err(Error::runtime(
+                    variable.name().clone(),
+                    &format!("Trait {} is used more than once.", trait_.name()),
+                )
+                .into());
+            }
+            used.push(trait_.clone());
+            for (name, method) in trait_.methods() {
+                let overridden = stmt
+                    .methods()
+                    .iter()
+                    .any(|own| !own.is_class_method() && own.name().lexeme() == name);
+                if let Some(other) = defined_by.insert(name.clone(), trait_.name()) {
+                    if !overridden {
+                        return Err(Error::runtime(
+                            variable.name().clone(),
+                            &format!(
+                                "Traits {} and {} both define '{}', so the class must override it.",
+                                other,
+                                trait_.name(),
+                                name
+                            ),
+                        )
+                        .into());
+                    }
+                }
+                methods.insert(name, method);
+            }
+        }
+        Ok(methods)
+    }
+
+    /// Returns the text used to print a value
+    ///
+    /// Instances whose class defines `__str__` print as the string it returns
+    pub fn stringify(&mut self, value: &Value) -> Result<String, Unwind> {
+        self.stringify_nested(value, &mut Vec::new())
+    }
+
+    /// Returns the text used to print a value held by the given lists
+    /// and maps, the innermost last
+    ///
+    /// A list or map holding itself prints as `[...]` or `{...}` inside itself.
+    fn stringify_nested(
+        &mut self,
+        value: &Value,
+        containers: &mut Vec<Value>,
+    ) -> Result<String, Unwind> {
+        let text = if let Ok(literal) = value.borrowed::<TokenLiteral>() {
+            literal.to_string()
+        } else if let Ok(function) = value.borrowed::<RloxFunction>() {
+            function.to_string()
+        } else if let Ok(native) = value.borrowed::<NativeCallable>() {
+            native.to_string()
+        } else if let Ok(class) = value.borrowed::<RloxClass>() {
+            class.to_string()
+        } else if let Ok(trait_) = value.borrowed::<RloxTrait>() {
+            trait_.to_string()
+        } else if let Ok(generator) = value.borrowed::<RloxGenerator>() {
+            generator.to_string()
+        } else if let Ok(fiber) = value.borrowed::<RloxFiber>() {
+            fiber.to_string()
+        } else if let Ok(fiber_class) = value.borrowed::<FiberClass>() {
+            fiber_class.to_string()
+        } else if let Ok(instance) = value.borrowed::<RloxInstance>() {
+            match special_method(value, "__str__") {
+                Some(method) => match method.call(self, Vec::new())?.owned::<TokenLiteral>() {
+                    Ok(TokenLiteral::String(text)) => text,
+                    _ => {
+                        return Err(Error::generic("Method '__str__' must return a string.").into())
+                    }
+                },
+                None => instance.to_string(),
+            }
+        } else if let Ok(error) = value.borrowed::<ErrorObject>() {
+            error.to_string()
+        } else if let Ok(range) = value.borrowed::<RloxRange>() {
+            range.to_string()
+        } else if let Ok(enum_) = value.borrowed::<RloxEnum>() {
+            enum_.to_string()
+        } else if let Ok(variant) = value.borrowed::<RloxVariant>() {
+            let name = format!("{}.{}", variant.enum_name(), variant.name());
+            if variant.values().is_empty() {
+                return Ok(name);
+            }
+            let values = self.stringify_all(&variant.values(), containers)?;
+            format!("{}({})", name, values.join(", "))
+        } else if let Ok(list) = value.borrowed::<RloxList>() {
+            if containers
+                .iter()
+                .any(|container| values_equal(container, value))
+            {
+                return Ok("[...]".to_string());
+            }
+            containers.push(value.clone());
+            let elements = self.stringify_all(&list.elements(), containers)?;
+            containers.pop();
+            format!("[{}]", elements.join(", "))
+        } else if let Ok(map) = value.borrowed::<RloxMap>() {
+            if containers
+                .iter()
+                .any(|container| values_equal(container, value))
+            {
+                return Ok("{...}".to_string());
+            }
+            containers.push(value.clone());
+            let mut entries = Vec::with_capacity(map.len());
+            for (key, value) in map.entries() {
+                entries.push(format!(
+                    "{}: {}",
+                    self.stringify_nested(&key, containers)?,
+                    self.stringify_nested(&value, containers)?
+                ));
+            }
+            containers.pop();
+            format!("{{{}}}", entries.join(", "))
+        } else {
+            "<value>".to_string()
+        };
+        Ok(text)
+    }
+
+    /// Returns the text used to print each of the values
+    fn stringify_all(
+        &mut self,
+        values: &[Value],
+        containers: &mut Vec<Value>,
+    ) -> Result<Vec<String>, Unwind> {
+        let mut texts = Vec::with_capacity(values.len());
+        for value in values {
+            texts.push(self.stringify_nested(value, containers)?);
+        }
+        Ok(texts)
+    }
+
+    /// Calls a value with the given arguments, after checking that it can
+    /// be called and that it takes that many arguments
+    ///
+    /// The token is where errors from the call are reported.
+    pub fn call_value(
+        &mut self,
+        callee: Value,
+        arguments: Vec<Value>,
+        paren: &Token,
+    ) -> Result<Value, Unwind> {
+        self.call_with_named(callee, arguments, Vec::new(), paren)
+    }
+
+    /// Calls a value with positional arguments followed by named ones
+    ///
+    /// Callables that take named arguments match them to their
+    /// parameters themselves, so their arity is only checked here
+    /// when every argument is positional.
+    pub fn call_with_named(
+        &mut self,
+        callee: Value,
+        arguments: Vec<Value>,
+        named: Vec<(Token, Value)>,
+        paren: &Token,
+    ) -> Result<Value, Unwind> {
+        // naming the frame of a callable instance should not run its `__str__`
+        let name = match callee.borrowed::<RloxInstance>() {
+            Ok(instance) => instance.to_string(),
+            Err(_) => self.stringify(&callee)?,
+        };
+
+        // make type checks at runtime
+        let callable: Box<dyn RloxCallable> = if callee.is::<RloxFunction>() {
+            Box::new(callee.owned::<RloxFunction>()?)
+        } else if callee.is::<RloxClass>() {
+            Box::new(callee.owned::<RloxClass>()?)
+        } else if callee.is::<NativeCallable>() {
+            Box::new(callee.owned::<NativeCallable>()?)
+        } else if callee.is::<FiberClass>() {
+            Box::new(callee.owned::<FiberClass>()?)
+        } else if let Some(method) = special_method(&callee, "__call__") {
+            Box::new(method)
+        } else {
+            return Err(
+                Error::runtime(paren.clone(), "Can only call functions and classes.").into(),
+            );
+        };
+
+        let (arg_size, callable_arity) = (arguments.len(), callable.arity());
+        if named.is_empty() && !callable_arity.accepts(arg_size) {
+            return Err(Error::runtime(
+                paren.clone(),
+                &format!("Expected {} arguments but got {}", callable_arity, arg_size),
+            )
+            .into());
+        }
+
+        let frame = format!("{} called at line {}", name, paren.line());
+        callable
+            .call_with_named(self, arguments, named, paren)
+            .map_err(|err| err.with_frame(frame))
+    }
+
+    /// Applies a binary operator to two values that were already evaluated
+    ///
+    /// Compound assignments use this to combine the current value
+    /// of their target with the new one.
+    fn binary(&mut self, operator: &Token, left: Value, right: Value) -> Result<Value, Unwind> {
+        if let Some(result) = self.overloaded(operator, &left, &right)? {
+            return Ok(result);
+        }
+
+        // values other than literals can only be compared for equality
+        let comparing = matches!(
+            operator.kind(),
+            TokenType::EqualEqual | TokenType::BangEqual
+        );
+        if comparing && !(left.is::<TokenLiteral>() && right.is::<TokenLiteral>()) {
+            let equal = values_equal(&left, &right);
+            let expected = operator.kind() == TokenType::EqualEqual;
+            return Ok(Value::new(TokenLiteral::Boolean(equal == expected)));
+        }
+        Ok(Self::literal_binary(operator, left, right)?)
+    }
+
+    /// Applies a binary operator of literals to two values
+    fn literal_binary(operator: &Token, left: Value, right: Value) -> Result<Value, Error> {
+        // only literals have operators of their own
+        let (Ok(left), Ok(right)) = (left.owned::<TokenLiteral>(), right.owned::<TokenLiteral>())
+        else {
+            return Err(Error::runtime(
+                operator.clone(),
+                &format!("Unsupported operand type for '{}'.", operator.lexeme()),
+            ));
+        };
 
-        match expr.operator().kind() {
+        match operator.kind() {
             TokenType::Minus => match left {
                 TokenLiteral::Integer(l_val) => {
                     if let TokenLiteral::Integer(r_val) = right {
-                        return Ok(Value::new(TokenLiteral::Integer(l_val - r_val)));
+                        return match l_val.checked_sub(r_val) {
+                            Some(value) => Ok(Value::new(TokenLiteral::Integer(value))),
+                            None => Err(Error::runtime(
+                                operator.clone(),
+                                "MINUS result does not fit in an integer",
+                            )),
+                        };
                     }
-                    Err(Error::report_runtime(
-                        expr.operator().clone(),
+                    Err(Error::runtime(
+                        operator.clone(),
                         &get_runtime_err_msg("MINUS", None),
                     ))
                 }
@@ -120,53 +506,69 @@ impl ExprVisitor<Result<Value, Error>> for Interpreter {
                     if let TokenLiteral::Float(r_val) = right {
                         return Ok(Value::new(TokenLiteral::Float(l_val - r_val)));
                     }
-                    Err(Error::report_runtime(
-                        expr.operator().clone(),
+                    Err(Error::runtime(
+                        operator.clone(),
                         &get_runtime_err_msg("MINUS", None),
                     ))
                 }
-                _ => Err(Error::report_runtime(
-                    expr.operator().clone(),
+                _ => Err(Error::runtime(
+                    operator.clone(),
                     &get_runtime_err_msg("MINUS", Some("numeric")),
                 )),
             },
             TokenType::Slash => match left {
                 TokenLiteral::Integer(l_val) => {
                     if let TokenLiteral::Integer(r_val) = right {
-                        if r_val == 0 {
-                            panic!("division by zero not allowed!");
-                        }
-                        return Ok(Value::new(TokenLiteral::Integer(l_val / r_val)));
+                        return match l_val.checked_div(r_val) {
+                            Some(value) => Ok(Value::new(TokenLiteral::Integer(value))),
+                            None if r_val == 0 => Err(Error::runtime(
+                                operator.clone(),
+                                "DIVISION by zero is not allowed",
+                            )),
+                            None => Err(Error::runtime(
+                                operator.clone(),
+                                "DIVISION result does not fit in an integer",
+                            )),
+                        };
                     }
-                    Err(Error::report_runtime(
-                        expr.operator().clone(),
+                    Err(Error::runtime(
+                        operator.clone(),
                         &get_runtime_err_msg("DIVISION", None),
                     ))
                 }
                 TokenLiteral::Float(l_val) => {
                     if let TokenLiteral::Float(r_val) = right {
                         if r_val == 0f64 {
-                            panic!("division by zero not allowed!");
+                            return Err(Error::runtime(
+                                operator.clone(),
+                                "DIVISION by zero is not allowed",
+                            ));
                         }
                         return Ok(Value::new(TokenLiteral::Float(l_val / r_val)));
                     }
-                    Err(Error::report_runtime(
-                        expr.operator().clone(),
+                    Err(Error::runtime(
+                        operator.clone(),
                         &get_runtime_err_msg("DIVISION", None),
                     ))
                 }
-                _ => Err(Error::report_runtime(
-                    expr.operator().clone(),
+                _ => Err(Error::runtime(
+                    operator.clone(),
                     &get_runtime_err_msg("DIVISION", Some("numeric")),
                 )),
             },
             TokenType::Star => match left {
                 TokenLiteral::Integer(l_val) => {
                     if let TokenLiteral::Integer(r_val) = right {
-                        return Ok(Value::new(TokenLiteral::Integer(l_val * r_val)));
+                        return match l_val.checked_mul(r_val) {
+                            Some(value) => Ok(Value::new(TokenLiteral::Integer(value))),
+                            None => Err(Error::runtime(
+                                operator.clone(),
+                                "PRODUCT result does not fit in an integer",
+                            )),
+                        };
                     }
-                    Err(Error::report_runtime(
-                        expr.operator().clone(),
+                    Err(Error::runtime(
+                        operator.clone(),
                         &get_runtime_err_msg("PRODUCT", None),
                     ))
                 }
@@ -174,23 +576,29 @@ impl ExprVisitor<Result<Value, Error>> for Interpreter {
                     if let TokenLiteral::Float(r_val) = right {
                         return Ok(Value::new(TokenLiteral::Float(l_val * r_val)));
                     }
-                    Err(Error::report_runtime(
-                        expr.operator().clone(),
+                    Err(Error::runtime(
+                        operator.clone(),
                         &get_runtime_err_msg("PRODUCT", None),
                     ))
                 }
-                _ => Err(Error::report_runtime(
-                    expr.operator().clone(),
+                _ => Err(Error::runtime(
+                    operator.clone(),
                     &get_runtime_err_msg("PRODUCT", Some("numeric")),
                 )),
             },
             TokenType::Plus => match left {
                 TokenLiteral::Integer(l_val) => {
                     if let TokenLiteral::Integer(r_val) = right {
-                        return Ok(Value::new(TokenLiteral::Integer(l_val + r_val)));
+                        return match l_val.checked_add(r_val) {
+                            Some(value) => Ok(Value::new(TokenLiteral::Integer(value))),
+                            None => Err(Error::runtime(
+                                operator.clone(),
+                                "ADD result does not fit in an integer",
+                            )),
+                        };
                     }
-                    Err(Error::report_runtime(
-                        expr.operator().clone(),
+                    Err(Error::runtime(
+                        operator.clone(),
                         &get_runtime_err_msg("ADD", None),
                     ))
                 }
@@ -198,8 +606,8 @@ impl ExprVisitor<Result<Value, Error>> for Interpreter {
                     if let TokenLiteral::Float(r_val) = right {
                         return Ok(Value::new(TokenLiteral::Float(l_val + r_val)));
                     }
-                    Err(Error::report_runtime(
-                        expr.operator().clone(),
+                    Err(Error::runtime(
+                        operator.clone(),
                         &get_runtime_err_msg("ADD", None),
                     ))
                 }
@@ -208,13 +616,13 @@ impl ExprVisitor<Result<Value, Error>> for Interpreter {
                         l_val.push_str(&r_val);
                         return Ok(Value::new(TokenLiteral::String(l_val)));
                     }
-                    Err(Error::report_runtime(
-                        expr.operator().clone(),
+                    Err(Error::runtime(
+                        operator.clone(),
                         &get_runtime_err_msg("ADD", None),
                     ))
                 }
-                _ => Err(Error::report_runtime(
-                    expr.operator().clone(),
+                _ => Err(Error::runtime(
+                    operator.clone(),
                     &get_runtime_err_msg("ADD", Some("both numeric or both string")),
                 )),
             },
@@ -223,8 +631,8 @@ impl ExprVisitor<Result<Value, Error>> for Interpreter {
                     if let TokenLiteral::Integer(r_val) = right {
                         return Ok(Value::new(TokenLiteral::Boolean(l_val > r_val)));
                     }
-                    Err(Error::report_runtime(
-                        expr.operator().clone(),
+                    Err(Error::runtime(
+                        operator.clone(),
                         &get_runtime_err_msg("GREATER THAN", None),
                     ))
                 }
@@ -232,8 +640,8 @@ impl ExprVisitor<Result<Value, Error>> for Interpreter {
                     if let TokenLiteral::Float(r_val) = right {
                         return Ok(Value::new(TokenLiteral::Boolean(l_val > r_val)));
                     }
-                    Err(Error::report_runtime(
-                        expr.operator().clone(),
+                    Err(Error::runtime(
+                        operator.clone(),
                         &get_runtime_err_msg("GREATER THAN", None),
                     ))
                 }
@@ -241,13 +649,13 @@ impl ExprVisitor<Result<Value, Error>> for Interpreter {
                     if let TokenLiteral::String(r_val) = right {
                         return Ok(Value::new(TokenLiteral::Boolean(l_val > r_val)));
                     }
-                    Err(Error::report_runtime(
-                        expr.operator().clone(),
+                    Err(Error::runtime(
+                        operator.clone(),
                         &get_runtime_err_msg("GREATER THAN", None),
                     ))
                 }
-                _ => Err(Error::report_runtime(
-                    expr.operator().clone(),
+                _ => Err(Error::runtime(
+                    operator.clone(),
                     &get_runtime_err_msg("GREATER THAN", Some("valid")),
                 )),
             },
@@ -256,8 +664,8 @@ impl ExprVisitor<Result<Value, Error>> for Interpreter {
                     if let TokenLiteral::Integer(r_val) = right {
                         return Ok(Value::new(TokenLiteral::Boolean(l_val >= r_val)));
                     }
-                    Err(Error::report_runtime(
-                        expr.operator().clone(),
+                    Err(Error::runtime(
+                        operator.clone(),
                         &get_runtime_err_msg("GREATER THAN OR EQUAL", None),
                     ))
                 }
@@ -265,8 +673,8 @@ impl ExprVisitor<Result<Value, Error>> for Interpreter {
                     if let TokenLiteral::Float(r_val) = right {
                         return Ok(Value::new(TokenLiteral::Boolean(l_val >= r_val)));
                     }
-                    Err(Error::report_runtime(
-                        expr.operator().clone(),
+                    Err(Error::runtime(
+                        operator.clone(),
                         &get_runtime_err_msg("GREATER THAN OR EQUAL", None),
                     ))
                 }
@@ -274,13 +682,13 @@ impl ExprVisitor<Result<Value, Error>> for Interpreter {
                     if let TokenLiteral::String(r_val) = right {
                         return Ok(Value::new(TokenLiteral::Boolean(l_val >= r_val)));
                     }
-                    Err(Error::report_runtime(
-                        expr.operator().clone(),
+                    Err(Error::runtime(
+                        operator.clone(),
                         &get_runtime_err_msg("GREATER THAN OR EQUAL", None),
                     ))
                 }
-                _ => Err(Error::report_runtime(
-                    expr.operator().clone(),
+                _ => Err(Error::runtime(
+                    operator.clone(),
                     &get_runtime_err_msg("GREATER THAN OR EQUAL", Some("valid")),
                 )),
             },
@@ -289,8 +697,8 @@ impl ExprVisitor<Result<Value, Error>> for Interpreter {
                     if let TokenLiteral::Integer(r_val) = right {
                         return Ok(Value::new(TokenLiteral::Boolean(l_val < r_val)));
                     }
-                    Err(Error::report_runtime(
-                        expr.operator().clone(),
+                    Err(Error::runtime(
+                        operator.clone(),
                         &get_runtime_err_msg("LESS THAN", None),
                     ))
                 }
@@ -298,8 +706,8 @@ impl ExprVisitor<Result<Value, Error>> for Interpreter {
                     if let TokenLiteral::Float(r_val) = right {
                         return Ok(Value::new(TokenLiteral::Boolean(l_val < r_val)));
                     }
-                    Err(Error::report_runtime(
-                        expr.operator().clone(),
+                    Err(Error::runtime(
+                        operator.clone(),
                         &get_runtime_err_msg("LESS THAN", None),
                     ))
                 }
@@ -307,13 +715,13 @@ impl ExprVisitor<Result<Value, Error>> for Interpreter {
                     if let TokenLiteral::String(r_val) = right {
                         return Ok(Value::new(TokenLiteral::Boolean(l_val < r_val)));
                     }
-                    Err(Error::report_runtime(
-                        expr.operator().clone(),
+                    Err(Error::runtime(
+                        operator.clone(),
                         &get_runtime_err_msg("LESS THAN", None),
                     ))
                 }
-                _ => Err(Error::report_runtime(
-                    expr.operator().clone(),
+                _ => Err(Error::runtime(
+                    operator.clone(),
                     &get_runtime_err_msg("LESS THAN", Some("valid")),
                 )),
             },
@@ -322,8 +730,8 @@ impl ExprVisitor<Result<Value, Error>> for Interpreter {
                     if let TokenLiteral::Integer(r_val) = right {
                         return Ok(Value::new(TokenLiteral::Boolean(l_val <= r_val)));
                     }
-                    Err(Error::report_runtime(
-                        expr.operator().clone(),
+                    Err(Error::runtime(
+                        operator.clone(),
                         &get_runtime_err_msg("LESS THAN OR EQUAL", None),
                     ))
                 }
@@ -331,8 +739,8 @@ impl ExprVisitor<Result<Value, Error>> for Interpreter {
                     if let TokenLiteral::Float(r_val) = right {
                         return Ok(Value::new(TokenLiteral::Boolean(l_val <= r_val)));
                     }
-                    Err(Error::report_runtime(
-                        expr.operator().clone(),
+                    Err(Error::runtime(
+                        operator.clone(),
                         &get_runtime_err_msg("LESS THAN OR EQUAL", None),
                     ))
                 }
@@ -340,13 +748,13 @@ impl ExprVisitor<Result<Value, Error>> for Interpreter {
                     if let TokenLiteral::String(r_val) = right {
                         return Ok(Value::new(TokenLiteral::Boolean(l_val <= r_val)));
                     }
-                    Err(Error::report_runtime(
-                        expr.operator().clone(),
+                    Err(Error::runtime(
+                        operator.clone(),
                         &get_runtime_err_msg("LESS THAN OR EQUAL", None),
                     ))
                 }
-                _ => Err(Error::report_runtime(
-                    expr.operator().clone(),
+                _ => Err(Error::runtime(
+                    operator.clone(),
                     &get_runtime_err_msg("LESS THAN OR EQUAL", Some("valid")),
                 )),
             },
@@ -414,237 +822,1051 @@ impl ExprVisitor<Result<Value, Error>> for Interpreter {
                     _ => Ok(Value::new(TokenLiteral::Boolean(false))),
                 },
             },
+            TokenType::Percent => match (left, right) {
+                (TokenLiteral::Integer(l_val), TokenLiteral::Integer(r_val)) => {
+                    match l_val.checked_rem(r_val) {
+                        Some(value) => Ok(Value::new(TokenLiteral::Integer(value))),
+                        None if r_val == 0 => Err(Error::runtime(
+                            operator.clone(),
+                            "MODULO by zero is not allowed",
+                        )),
+                        None => Err(Error::runtime(
+                            operator.clone(),
+                            "MODULO result does not fit in an integer",
+                        )),
+                    }
+                }
+                (TokenLiteral::Float(l_val), TokenLiteral::Float(r_val)) => {
+                    if r_val == 0f64 {
+                        return Err(Error::runtime(
+                            operator.clone(),
+                            "MODULO by zero is not allowed",
+                        ));
+                    }
+                    Ok(Value::new(TokenLiteral::Float(l_val % r_val)))
+                }
+                (TokenLiteral::Integer(_), _) | (TokenLiteral::Float(_), _) => Err(Error::runtime(
+                    operator.clone(),
+                    &get_runtime_err_msg("MODULO", None),
+                )),
+                _ => Err(Error::runtime(
+                    operator.clone(),
+                    &get_runtime_err_msg("MODULO", Some("numeric")),
+                )),
+            },
+            TokenType::StarStar => match (left, right) {
+                // negative exponents give fractions, so they make a float
+                (TokenLiteral::Integer(l_val), TokenLiteral::Integer(r_val)) => {
+                    match u32::try_from(r_val) {
+                        Ok(exponent) => match l_val.checked_pow(exponent) {
+                            Some(value) => Ok(Value::new(TokenLiteral::Integer(value))),
+                            None => Err(Error::runtime(
+                                operator.clone(),
+                                "POWER result does not fit in an integer",
+                            )),
+                        },
+                        Err(_) => Ok(Value::new(TokenLiteral::Float((l_val as f64).powi(r_val)))),
+                    }
+                }
+                (TokenLiteral::Float(l_val), TokenLiteral::Float(r_val)) => {
+                    Ok(Value::new(TokenLiteral::Float(l_val.powf(r_val))))
+                }
+                (TokenLiteral::Integer(_), _) | (TokenLiteral::Float(_), _) => Err(Error::runtime(
+                    operator.clone(),
+                    &get_runtime_err_msg("POWER", None),
+                )),
+                _ => Err(Error::runtime(
+                    operator.clone(),
+                    &get_runtime_err_msg("POWER", Some("numeric")),
+                )),
+            },
+            TokenType::Ampersand => {
+                bitwise(operator, "BITWISE AND", left, right, |l, r| Some(l & r))
+            }
+            TokenType::Pipe => bitwise(operator, "BITWISE OR", left, right, |l, r| Some(l | r)),
+            TokenType::Caret => bitwise(operator, "BITWISE XOR", left, right, |l, r| Some(l ^ r)),
+            TokenType::LessLess => bitwise(operator, "SHIFT LEFT", left, right, |l, r| {
+                u32::try_from(r).ok().and_then(|r| l.checked_shl(r))
+            }),
+            TokenType::GreaterGreater => bitwise(operator, "SHIFT RIGHT", left, right, |l, r| {
+                u32::try_from(r).ok().and_then(|r| l.checked_shr(r))
+            }),
             _ => unreachable!(),
         }
     }
 
-    fn visit_call_expr(&mut self, expr: &crate::expressions::Call) -> Result<Value, Error> {
-        let callee = self.evaluate(expr.callee())?;
+    /// Applies a binary operator through the special method the class of
+    /// an instance operand defines for it, or returns None if the operator
+    /// has no special method or the operand is not an instance
+    ///
+    /// Comparisons all go through `__lt__`, so `a > b` is `b < a`,
+    /// `a <= b` is `!(b < a)` and `a >= b` is `!(a < b)`, and `a != b`
+    /// is `!(a == b)`. Instances without `__eq__` are compared by identity,
+    /// and comparing with nil never calls `__eq__`, so it can check for nil.
+    /// An instance on the side that does not receive the call, as in
+    /// `1 + a`, is an error.
+    fn overloaded(
+        &mut self,
+        operator: &Token,
+        left: &Value,
+        right: &Value,
+    ) -> Result<Option<Value>, Unwind> {
+        let (name, swapped, negated) = match operator.kind() {
+            TokenType::Plus => ("__add__", false, false),
+            TokenType::Minus => ("__sub__", false, false),
+            TokenType::Star => ("__mul__", false, false),
+            TokenType::Slash => ("__div__", false, false),
+            TokenType::Percent => ("__mod__", false, false),
+            TokenType::EqualEqual => ("__eq__", false, false),
+            TokenType::BangEqual => ("__eq__", false, true),
+            TokenType::Less => ("__lt__", false, false),
+            TokenType::Greater => ("__lt__", true, false),
+            TokenType::LessEqual => ("__lt__", true, true),
+            TokenType::GreaterEqual => ("__lt__", false, true),
+            _ => return Ok(None),
+        };
+        let (receiver, argument) = match swapped {
+            true => (right, left),
+            false => (left, right),
+        };
+        if name == "__eq__" && is_nil(argument) {
+            return Ok(None);
+        }
+        let instance = match (
+            receiver.borrowed::<RloxInstance>(),
+            argument.borrowed::<RloxInstance>(),
+        ) {
+            (Ok(instance), _) => instance,
+            // special methods are only looked up on the receiver, as in `1 + V()`
+            (Err(_), Ok(instance)) if name != "__eq__" => {
+                let side = if swapped { "right" } else { "left" };
+                return Err(Error::runtime(
+                    operator.clone(),
+                    &format!(
+                        "Unsupported operand for '{}', an instance of {} has to be on its {}.",
+                        operator.lexeme(),
+                        instance.class().name(),
+                        side
+                    ),
+                )
+                .into());
+            }
+            _ => return Ok(None),
+        };
+        let method = match special_method(receiver, name) {
+            Some(method) => method,
+            None if name == "__eq__" => return Ok(None),
+            None => {
+                return Err(Error::runtime(
+                    operator.clone(),
+                    &format!(
+                        "Class {} does not define '{}' for '{}'.",
+                        instance.class().name(),
+                        name,
+                        operator.lexeme()
+                    ),
+                )
+                .into())
+            }
+        };
+
+        let result = self.call_value(Value::new(method), vec![argument.clone()], operator)?;
+        if name != "__eq__" && name != "__lt__" {
+            return Ok(Some(result));
+        }
+        match result.owned::<TokenLiteral>() {
+            Ok(TokenLiteral::Boolean(value)) => {
+                Ok(Some(Value::new(TokenLiteral::Boolean(value != negated))))
+            }
+            _ => Err(Error::runtime(
+                operator.clone(),
+                &format!("Method '{}' must return 'true' or 'false'.", name),
+            )
+            .into()),
+        }
+    }
+
+    /// Returns the element of a list or map at an index, or what
+    /// the `__index__` method of an instance returns for it
+    pub fn get_index(
+        &mut self,
+        object: &Value,
+        index: Value,
+        bracket: &Token,
+    ) -> Result<Value, Unwind> {
+        if let Some(method) = special_method(object, "__index__") {
+            return self.call_value(Value::new(method), vec![index], bracket);
+        }
+        if let Ok(list) = object.borrowed::<RloxList>() {
+            return Ok(list.get(index, bracket)?);
+        }
+        if let Ok(map) = object.borrowed::<RloxMap>() {
+            return Ok(map.get(index, bracket)?);
+        }
+        Err(Error::runtime(bracket.clone(), "Only lists and maps can be indexed.").into())
+    }
+
+    /// Returns the value of a property of an object, which is a field,
+    /// a method bound to the object or what a getter returns
+    pub fn get_property(&mut self, object: &Value, name: &Token) -> Result<Value, Unwind> {
+        self.check_private(object, name)?;
+        if let Ok(instance) = object.borrowed::<RloxInstance>() {
+            if let Some(getter) = instance.getter(name) {
+                return self.call_value(Value::new(getter), Vec::new(), name);
+            }
+            return Ok(instance.get(name)?);
+        }
+        if let Ok(class) = object.borrowed::<RloxClass>() {
+            if let Some(getter) = class.getter(name) {
+                return self.call_value(Value::new(getter), Vec::new(), name);
+            }
+            return Ok(class.get(name)?);
+        }
+        if let Ok(error) = object.borrowed::<ErrorObject>() {
+            return Ok(error.get(name)?);
+        }
+        if let Ok(enum_) = object.borrowed::<RloxEnum>() {
+            return Ok(enum_.get(name)?);
+        }
+        if let Ok(variant) = object.borrowed::<RloxVariant>() {
+            return Ok(variant.get(name)?);
+        }
+        if let Ok(list) = object.borrowed::<RloxList>() {
+            return Ok(list.method(name)?);
+        }
+        if let Ok(map) = object.borrowed::<RloxMap>() {
+            return Ok(map.method(name)?);
+        }
+        if let Ok(generator) = object.borrowed::<RloxGenerator>() {
+            return Ok(generator.method(name)?);
+        }
+        if let Ok(fiber) = object.borrowed::<RloxFiber>() {
+            return Ok(fiber.method(name)?);
+        }
+        if let Ok(fiber_class) = object.borrowed::<FiberClass>() {
+            return Ok(fiber_class.get(name)?);
+        }
+        Err(Error::runtime(name.clone(), "Only instances have properties.").into())
+    }
+
+    /// Errors if a private property of an instance or a class is used by code
+    /// outside the class that declares it, or returns the class of the code
+    /// using a private property
+    ///
+    /// The class whose code sets a private field first declares it, and for
+    /// a field of an instance that has to be the class of the instance or
+    /// one of its superclasses
+    fn check_private(&self, object: &Value, name: &Token) -> Result<Option<RloxClass>, Error> {
+        if name.kind() != TokenType::PrivateIdentifier {
+            return Ok(None);
+        }
+        let (class, declaring) = if let Ok(instance) = object.borrowed::<RloxInstance>() {
+            (
+                instance.class().clone(),
+                instance.declaring_class(&name.lexeme()),
+            )
+        } else if let Ok(class) = object.borrowed::<RloxClass>() {
+            (class.clone(), class.declaring_class(&name.lexeme()))
+        } else {
+            // other values have no private properties to protect
+            return Ok(None);
+        };
+        let token = Token::new(
+            TokenType::Identifier,
+            HOME_CLASS,
+            TokenLiteral::Nil,
+            name.line(),
+        );
+        let home = match self.environment.borrow().get(&token) {
+            Ok(home) => home.owned::<RloxClass>().ok(),
+            Err(_) => None,
+        };
+        let inside = match (&home, declaring) {
+            (Some(home), Some(declaring)) => home.same(&declaring),
+            (Some(home), None) => class.inherits_from(home),
+            (None, _) => false,
+        };
+        if inside {
+            return Ok(home);
+        }
+        Err(Error::runtime(
+            name.clone(),
+            &format!(
+                "Cannot access private property '{}' outside of its class.",
+                name.lexeme()
+            ),
+        ))
+    }
+
+    /// Evaluates a link of a chain of property accesses, calls and
+    /// indexes, or any other expression, returning None when a `?.`
+    /// earlier in the chain found nil, so the rest of the chain is skipped
+    fn evaluate_chain(&mut self, expr: Expr) -> Result<Option<Value>, Unwind> {
+        match &expr {
+            Expr::Get(get) => self.get_link(get),
+            Expr::Call(call) => self.call_link(call),
+            Expr::Index(index) => self.index_link(index),
+            _ => self.evaluate(expr).map(Some),
+        }
+    }
+
+    /// Reads a property as a link of a chain, see [`Self::evaluate_chain`]
+    fn get_link(&mut self, expr: &crate::expressions::Get) -> Result<Option<Value>, Unwind> {
+        let object = match self.evaluate_chain(expr.object())? {
+            Some(object) if !(expr.is_optional() && is_nil(&object)) => object,
+            _ => return Ok(None),
+        };
+        self.get_property(&object, expr.name()).map(Some)
+    }
+
+    /// Calls a callee as a link of a chain, see [`Self::evaluate_chain`]
+    ///
+    /// The arguments are not evaluated when the call is skipped
+    fn call_link(&mut self, expr: &crate::expressions::Call) -> Result<Option<Value>, Unwind> {
+        let Some(callee) = self.evaluate_chain(expr.callee())? else {
+            return Ok(None);
+        };
 
         let mut arguments = Vec::with_capacity(MAX_FUNCTION_ARGUMENTS_SIZE);
         for argument in expr.arguments() {
             arguments.push(self.evaluate(argument.clone())?);
         }
+        let mut named = Vec::with_capacity(expr.named_arguments().len());
+        for (name, argument) in expr.named_arguments() {
+            named.push((name.clone(), self.evaluate(argument.clone())?));
+        }
 
-        // make type checks at runtime
-        let callable: Box<dyn RloxCallable> = if callee.is::<RloxFunction>() {
-            Box::new(callee.owned::<RloxFunction>()?)
-        } else if callee.is::<Class>() {
-            Box::new(callee.owned::<Class>()?)
-        } else if callee.is::<NativeCallable>() {
-            Box::new(callee.owned::<NativeCallable>()?)
-        } else {
-            return Err(Error::report_runtime(
-                expr.paren().clone(),
-                "Can only call functions and classes.",
-            ));
+        self.call_with_named(callee, arguments, named, expr.paren())
+            .map(Some)
+    }
+
+    /// Indexes an object as a link of a chain, see [`Self::evaluate_chain`]
+    fn index_link(&mut self, expr: &crate::expressions::Index) -> Result<Option<Value>, Unwind> {
+        let Some(object) = self.evaluate_chain(expr.object())? else {
+            return Ok(None);
         };
+        let index = self.evaluate(expr.index())?;
+        self.get_index(&object, index, expr.bracket()).map(Some)
+    }
 
-        let (arg_size, callable_arity) = (arguments.len(), callable.arity());
-        if arg_size != callable_arity {
-            return Err(Error::report_runtime(
-                expr.paren().clone(),
-                &format!("Expected {} arguments but got {}", callable_arity, arg_size),
-            ));
+    /// Records that the variable used by a node is declared
+    /// the given number of scopes above the one it is used in
+    pub fn resolve(&mut self, id: NodeId, depth: usize) {
+        self.locals.insert(id, depth);
+    }
+
+    /// Returns the scope distances recorded by the resolver
+    pub fn locals(&self) -> &SideTable<usize> {
+        &self.locals
+    }
+
+    /// Returns the value of a variable, looking it up in the scope the
+    /// resolver found for it or in the global scope if there was none
+    fn look_up_variable(&self, name: &Token, id: NodeId) -> Result<Value, Error> {
+        match self.locals.get(id) {
+            Some(distance) => self.environment.borrow().get_at(*distance, name),
+            None => self.globals.borrow().get(name),
         }
+    }
 
-        callable.call(self, arguments)
+    /// Assigns to the variable the node with the given id refers to
+    fn assign_variable(&mut self, name: Token, id: NodeId, value: Value) -> Result<(), Error> {
+        match self.locals.get(id) {
+            Some(distance) => self
+                .environment
+                .borrow_mut()
+                .assign_at(*distance, name, value),
+            None => self.globals.borrow_mut().assign(name, value),
+        }
+    }
+}
+
+impl ExprVisitor<Result<Value, Unwind>> for Interpreter<'_> {
+    fn visit_assign_expr(
+        &mut self,
+        expr: &crate::expressions::assign::Assign,
+    ) -> Result<Value, Unwind> {
+        // a compound assignment reads the variable before its operand
+        let current = match expr.operator() {
+            Some(_) => Some(self.look_up_variable(&expr.name(), expr.id())?),
+            None => None,
+        };
+        let mut value = self.evaluate(expr.value())?;
+        if let (Some(operator), Some(current)) = (expr.operator(), current) {
+            value = self.binary(&operator, current, value)?;
+        }
+        self.assign_variable(expr.name(), expr.id(), value.clone())?;
+        Ok(value)
+    }
+
+    fn visit_assign_pattern_expr(
+        &mut self,
+        expr: &crate::expressions::AssignPattern,
+    ) -> Result<Value, Unwind> {
+        // the whole value is destructured before any variable
+        // changes, so `[a, b] = [b, a]` swaps the two
+        let value = self.evaluate(expr.value())?;
+        let mut bindings = Vec::new();
+        expr.pattern().destructure(&value, &mut bindings)?;
+        for ((name, value), (_, id)) in bindings.into_iter().zip(expr.targets()) {
+            self.assign_variable(name, id, value)?;
+        }
+        Ok(value)
+    }
+
+    fn visit_binary_expr(
+        &mut self,
+        expr: &crate::expressions::binary::Binary,
+    ) -> Result<Value, Unwind> {
+        let left = self.evaluate(expr.left())?;
+        let right = self.evaluate(expr.right())?;
+        self.binary(expr.operator(), left, right)
+    }
+
+    fn visit_call_expr(&mut self, expr: &crate::expressions::Call) -> Result<Value, Unwind> {
+        // a chain skipped by `?.` is nil as a whole
+        let value = self.call_link(expr)?;
+        Ok(value.unwrap_or_else(|| Value::new(TokenLiteral::Nil)))
+    }
+
+    fn visit_get_expr(&mut self, expr: &crate::expressions::Get) -> Result<Value, Unwind> {
+        let value = self.get_link(expr)?;
+        Ok(value.unwrap_or_else(|| Value::new(TokenLiteral::Nil)))
     }
 
     fn visit_grouping_expr(
         &mut self,
         expr: &crate::expressions::grouping::Grouping,
-    ) -> Result<Value, Error> {
-        Ok(self.evaluate(expr.expression())?)
+    ) -> Result<Value, Unwind> {
+        self.evaluate(expr.expression())
+    }
+
+    fn visit_index_expr(&mut self, expr: &crate::expressions::Index) -> Result<Value, Unwind> {
+        let value = self.index_link(expr)?;
+        Ok(value.unwrap_or_else(|| Value::new(TokenLiteral::Nil)))
+    }
+
+    fn visit_lambda_expr(&mut self, expr: &crate::expressions::Lambda) -> Result<Value, Unwind> {
+        let function = RloxFunction::new(expr.declaration(), self.environment.clone(), false);
+        Ok(Value::new(function))
+    }
+
+    fn visit_list_expr(&mut self, expr: &crate::expressions::List) -> Result<Value, Unwind> {
+        let mut elements = Vec::with_capacity(expr.elements().len());
+        for element in expr.elements() {
+            elements.push(self.evaluate(element.clone())?);
+        }
+        Ok(Value::new(RloxList::new(elements)))
     }
 
     fn visit_literal_expr(
         &mut self,
         expr: &crate::expressions::literal::Literal,
-    ) -> Result<Value, Error> {
+    ) -> Result<Value, Unwind> {
         Ok(expr.value())
     }
 
-    fn visit_logical_expr(&mut self, expr: &crate::expressions::Logical) -> Result<Value, Error> {
-        let left = self.evaluate(expr.left())?.owned::<TokenLiteral>()?;
+    fn visit_logical_expr(&mut self, expr: &crate::expressions::Logical) -> Result<Value, Unwind> {
+        // nil coalescing works on values of any type, and only
+        // evaluates the right operand when the left one is nil
+        if expr.operator().kind() == TokenType::QuestionQuestion {
+            let left = self.evaluate(expr.left())?;
+            return match is_nil(&left) {
+                true => self.evaluate(expr.right()),
+                false => Ok(left),
+            };
+        }
 
-        // short circuit the boolean operation if its OR or AND
-        if expr.operator().kind() == TokenType::Or {
-            match left {
-                TokenLiteral::Boolean(is_true) => match is_true {
-                    true => return Ok(Value::new(left)), // return true if one is true
-                    false => {
-                        let right = self.evaluate(expr.right())?.owned::<TokenLiteral>()?;
-                        if matches!(right, TokenLiteral::Boolean(_)) {
-                            return Ok(Value::new(right));
-                        }
-                        return Err(Error::report_generic(
-                            "Right operand must be a boolean expresssion",
-                        ));
-                    }
-                },
-                _ => {
-                    return Err(Error::report_generic(
-                        "Left operand must be a boolean expresssion",
-                    ))
-                }
+        let left = match self.evaluate(expr.left())?.owned::<TokenLiteral>() {
+            Ok(TokenLiteral::Boolean(left)) => left,
+            _ => {
+                return Err(Error::runtime(
+                    expr.operator().clone(),
+                    "Left operand must be a boolean expression",
+                )
+                .into())
             }
-        } else if expr.operator().kind() == TokenType::And {
-            match left {
-                TokenLiteral::Boolean(is_true) => match is_true {
-                    true => {
-                        // only return true if both are true
-                        let right = self.evaluate(expr.right())?.owned::<TokenLiteral>()?;
-                        if matches!(right, TokenLiteral::Boolean(_)) {
-                            return Ok(Value::new(right));
-                        }
-                        return Err(Error::report_generic(
-                            "Right operand must be a boolean expresssion",
-                        ));
-                    }
-                    false => return Ok(Value::new(left)),
-                },
-                _ => {
-                    return Err(Error::report_generic(
-                        "Left operand must be a boolean expresssion",
-                    ))
-                }
+        };
+
+        // short circuit the boolean operation: OR is true if the left
+        // operand is, and AND is false if the left operand is
+        let short_circuits = match expr.operator().kind() {
+            TokenType::Or => left,
+            TokenType::And => !left,
+            _ => unreachable!(),
+        };
+        if short_circuits {
+            return Ok(Value::new(TokenLiteral::Boolean(left)));
+        }
+
+        let right = self.evaluate(expr.right())?;
+        match right.borrowed::<TokenLiteral>() {
+            Ok(TokenLiteral::Boolean(_)) => Ok(right),
+            _ => Err(Error::runtime(
+                expr.operator().clone(),
+                "Right operand must be a boolean expression",
+            )
+            .into()),
+        }
+    }
+
+    fn visit_map_expr(&mut self, expr: &crate::expressions::Map) -> Result<Value, Unwind> {
+        let map = RloxMap::new();
+        for (key, value) in expr.entries() {
+            let key = self.evaluate(key.clone())?;
+            let value = self.evaluate(value.clone())?;
+            map.set(key, value, expr.brace())?;
+        }
+        Ok(Value::new(map))
+    }
+
+    fn visit_set_index_expr(
+        &mut self,
+        expr: &crate::expressions::SetIndex,
+    ) -> Result<Value, Unwind> {
+        let object = self.evaluate(expr.object())?;
+        let index = self.evaluate(expr.index())?;
+        // the object and index are evaluated once, even when compounding
+        let current = match expr.operator() {
+            Some(_) => Some(self.get_index(&object, index.clone(), expr.bracket())?),
+            None => None,
+        };
+        let mut value = self.evaluate(expr.value())?;
+        if let (Some(operator), Some(current)) = (expr.operator(), current) {
+            value = self.binary(&operator, current, value)?;
+        }
+        if let Ok(list) = object.borrowed::<RloxList>() {
+            list.set(index, value.clone(), expr.bracket())?;
+        } else if let Ok(map) = object.borrowed::<RloxMap>() {
+            map.set(index, value.clone(), expr.bracket())?;
+        } else {
+            return Err(Error::runtime(
+                expr.bracket().clone(),
+                "Only lists and maps can be indexed.",
+            )
+            .into());
+        }
+        Ok(value)
+    }
+
+    fn visit_set_expr(&mut self, expr: &crate::expressions::Set) -> Result<Value, Unwind> {
+        let object = self.evaluate(expr.object())?;
+        if !object.is::<RloxInstance>() && !object.is::<RloxClass>() {
+            return Err(Error::runtime(
+                expr.name().clone(),
+                "Only instances and classes have fields.",
+            )
+            .into());
+        }
+        let home = self.check_private(&object, expr.name())?;
+        let current = match expr.operator() {
+            Some(_) => Some(self.get_property(&object, expr.name())?),
+            None => None,
+        };
+        let mut value = self.evaluate(expr.value())?;
+        if let (Some(operator), Some(current)) = (expr.operator(), current) {
+            value = self.binary(&operator, current, value)?;
+        }
+        if let Ok(instance) = object.borrowed::<RloxInstance>() {
+            instance.set(expr.name(), value.clone());
+            if let Some(home) = home {
+                instance.declare_private(&expr.name().lexeme(), home);
             }
+        } else if let Ok(class) = object.borrowed::<RloxClass>() {
+            class.set(expr.name(), value.clone());
         }
+        Ok(value)
+    }
 
-        unreachable!()
+    fn visit_super_expr(&mut self, expr: &crate::expressions::Super) -> Result<Value, Unwind> {
+        // the resolver puts `this` in the scope just inside the one holding `super`
+        let distance = *self
+            .locals
+            .get(expr.id())
+            .expect("super is always resolved inside a subclass");
+        let superclass = self
+            .environment
+            .borrow()
+            .get_at(distance, expr.keyword())?
+            .owned::<RloxClass>()?;
+        let this = Token::new(
+            TokenType::This,
+            "this",
+            TokenLiteral::Nil,
+            expr.keyword().line(),
+        );
+        let this = self.environment.borrow().get_at(distance - 1, &this)?;
+
+        // in a class method `this` is the class, which has its own methods
+        let name = expr.method().lexeme();
+        let method = match this.borrowed::<RloxClass>() {
+            Ok(class) => superclass
+                .find_class_method(&name)
+                .map(|method| method.bind_class(class.clone())),
+            Err(_) => {
+                let instance = this.owned::<RloxInstance>()?;
+                superclass
+                    .find_method(&name)
+                    .map(|method| method.bind(instance))
+            }
+        };
+        // a getter found through `super` is called, as it would be through `this`
+        match method {
+            Some(method) if method.is_getter() => {
+                self.call_value(Value::new(method), Vec::new(), expr.method())
+            }
+            Some(method) => Ok(Value::new(method)),
+            None => Err(Error::runtime(
+                expr.method().clone(),
+                &format!("Undefined property '{}'.", name),
+            )
+            .into()),
+        }
+    }
+
+    fn visit_ternary_expr(&mut self, expr: &crate::expressions::Ternary) -> Result<Value, Unwind> {
+        match self.evaluate(expr.condition())?.owned::<TokenLiteral>() {
+            Ok(TokenLiteral::Boolean(true)) => self.evaluate(expr.then_branch()),
+            Ok(TokenLiteral::Boolean(false)) => self.evaluate(expr.else_branch()),
+            _ => Err(Error::runtime(
+                expr.question().clone(),
+                "Condition in conditional expression must evaluate to 'true' or 'false'",
+            )
+            .into()),
+        }
+    }
+
+    fn visit_this_expr(&mut self, expr: &crate::expressions::This) -> Result<Value, Unwind> {
+        Ok(self.look_up_variable(expr.keyword(), expr.id())?)
     }
 
     fn visit_unary_expr(
         &mut self,
         expr: &crate::expressions::unary::Unary,
-    ) -> Result<Value, Error> {
-        let right = self.evaluate(expr.right())?.owned::<TokenLiteral>()?;
-
-        match expr.operator().kind() {
+    ) -> Result<Value, Unwind> {
+        // only literals have unary operators, so anything else falls
+        // through to the error of the operator
+        let right = self.evaluate(expr.right())?.owned::<TokenLiteral>().ok();
+        let result = match expr.operator().kind() {
             TokenType::Minus => match right {
-                TokenLiteral::Integer(v) => Ok(Value::new(TokenLiteral::Integer(-v))),
-                TokenLiteral::Float(v) => Ok(Value::new(TokenLiteral::Float(-v))),
-                _ => Err(Error::report_runtime(
+                Some(TokenLiteral::Integer(v)) => match v.checked_neg() {
+                    Some(v) => Ok(Value::new(TokenLiteral::Integer(v))),
+                    None => Err(Error::runtime(
+                        expr.operator().clone(),
+                        "MINUS result does not fit in an integer",
+                    )),
+                },
+                Some(TokenLiteral::Float(v)) => Ok(Value::new(TokenLiteral::Float(-v))),
+                _ => Err(Error::runtime(
                     expr.operator().clone(),
                     "MINUS must have a numeric operand",
                 )),
             },
             TokenType::Bang => match right {
-                TokenLiteral::Boolean(v) => Ok(Value::new(TokenLiteral::Boolean(!v))),
-                TokenLiteral::Nil => Ok(Value::new(TokenLiteral::Boolean(true))),
-                _ => Err(Error::report_runtime(
+                Some(TokenLiteral::Boolean(v)) => Ok(Value::new(TokenLiteral::Boolean(!v))),
+                Some(TokenLiteral::Nil) => Ok(Value::new(TokenLiteral::Boolean(true))),
+                _ => Err(Error::runtime(
                     expr.operator().clone(),
                     "NEGATION must have a valid operand",
                 )),
             },
-            // this part of the code is unreachable since MINUS and BANG
-            // are the only unary operators
+            TokenType::Tilde => match right {
+                Some(TokenLiteral::Integer(v)) => Ok(Value::new(TokenLiteral::Integer(!v))),
+                _ => Err(Error::runtime(
+                    expr.operator().clone(),
+                    "BITWISE NOT must have an integer operand",
+                )),
+            },
+            // this part of the code is unreachable since MINUS, BANG
+            // and TILDE are the only unary operators
             _ => unreachable!(),
-        }
+        };
+        Ok(result?)
     }
 
-    fn visit_variable_expr(&mut self, expr: &crate::expressions::Variable) -> Result<Value, Error> {
-        self.environment.borrow().get(&expr.name())
+    fn visit_variable_expr(
+        &mut self,
+        expr: &crate::expressions::Variable,
+    ) -> Result<Value, Unwind> {
+        Ok(self.look_up_variable(expr.name(), expr.id())?)
     }
 }
 
-impl StmtVisitor<Result<(), Error>> for Interpreter {
-    fn visit_block_stmt(&mut self, stmt: &crate::stmt::Block) -> Result<(), Error> {
+impl StmtVisitor<Result<Completion, Unwind>> for Interpreter<'_> {
+    fn visit_block_stmt(&mut self, stmt: &crate::stmt::Block) -> Result<Completion, Unwind> {
         let parent_env = Environment::with_parent(self.environment.clone());
-        self.execute_block(stmt.statements(), Rc::new(RefCell::new(parent_env)))?;
-        Ok(())
+        Ok(self.execute_block(stmt.statements(), Rc::new(RefCell::new(parent_env)))?)
+    }
+
+    fn visit_break_stmt(&mut self, stmt: &crate::stmt::Break) -> Result<Completion, Unwind> {
+        Ok(Completion::Break(stmt.label().map(|label| label.lexeme())))
+    }
+
+    fn visit_class_stmt(&mut self, stmt: &crate::stmt::Class) -> Result<Completion, Unwind> {
+        let superclass = match stmt.superclass() {
+            Some(superclass) => match self.evaluate(Expr::Variable(superclass.clone()))? {
+                value if value.is::<RloxClass>() => Some(value.owned::<RloxClass>()?),
+                _ => {
+                    return Err(Error::runtime(
+                        superclass.name().clone(),
+                        "Superclass must be a class.",
+                    )
+                    .into())
+                }
+            },
+            None => None,
+        };
+
+        // methods see the class, and `super` in a subclass, in a scope of their own
+        let mut environment = Environment::with_parent(self.environment.clone());
+        if let Some(superclass) = &superclass {
+            environment.define("super".to_string(), Value::new(superclass.clone()));
+        }
+        let closure = Rc::new(RefCell::new(environment));
+
+        // the methods of the class are added last, so they override those of its traits
+        let mut methods = self.trait_methods(stmt)?;
+        let mut class_methods = HashMap::new();
+        for method in stmt.methods() {
+            let is_class_method = method.is_class_method();
+            let is_initializer = !is_class_method && method.name().lexeme() == "init";
+            let name = method.name().lexeme();
+            let function = RloxFunction::new(method, closure.clone(), is_initializer);
+            match is_class_method {
+                true => class_methods.insert(name, function),
+                false => methods.insert(name, function),
+            };
+        }
+
+        let class = RloxClass::new(stmt.name().lexeme(), superclass, methods, class_methods);
+        closure
+            .borrow_mut()
+            .define(HOME_CLASS.to_string(), Value::new(class.clone()));
+        self.environment
+            .borrow_mut()
+            .declare(&stmt.name(), Value::new(class.clone()), false)?;
+
+        // fields are initialized once the class exists, so they can make instances of it
+        for field in stmt.fields() {
+            let value = self.evaluate(field.initializer())?;
+            class.set(&field.name(), value);
+        }
+        Ok(Completion::Normal)
     }
 
-    fn visit_class_stmt(&mut self, stmt: &crate::stmt::Class) -> Result<(), Error> {
-        todo!()
+    fn visit_enum_stmt(&mut self, stmt: &crate::stmt::Enum) -> Result<Completion, Unwind> {
+        let variants = stmt
+            .variants()
+            .iter()
+            .map(|variant| {
+                let fields = variant.fields().iter().map(Token::lexeme).collect();
+                (variant.name().lexeme(), fields)
+            })
+            .collect();
+        let enum_ = RloxEnum::new(stmt.name().lexeme(), variants);
+        self.environment
+            .borrow_mut()
+            .declare(&stmt.name(), Value::new(enum_), false)?;
+        Ok(Completion::Normal)
     }
 
-    fn visit_expression_stmt(&mut self, stmt: &crate::stmt::Expression) -> Result<(), Error> {
+    fn visit_continue_stmt(&mut self, stmt: &crate::stmt::Continue) -> Result<Completion, Unwind> {
+        Ok(Completion::Continue(
+            stmt.label().map(|label| label.lexeme()),
+        ))
+    }
+
+    fn visit_expression_stmt(
+        &mut self,
+        stmt: &crate::stmt::Expression,
+    ) -> Result<Completion, Unwind> {
         self.evaluate(stmt.expression())?;
-        Ok(())
+        Ok(Completion::Normal)
     }
 
-    fn visit_function_stmt(&mut self, stmt: &crate::stmt::Function) -> Result<(), Error> {
-        let function = RloxFunction::new(stmt.clone(), self.environment.clone());
+    fn visit_for_in_stmt(&mut self, stmt: &crate::stmt::ForIn) -> Result<Completion, Unwind> {
+        let iterable = self.evaluate(stmt.iterable())?;
+        let mut iterator = RloxIterator::new(self, iterable, &stmt.keyword())?;
+
+        while let Some(value) = iterator.next(self)? {
+            // every iteration gets a fresh variable, so closures
+            // made in the body each see their own element
+            let mut environment = Environment::with_parent(self.environment.clone());
+            environment.define(stmt.name().lexeme(), value);
+            let body = vec![*stmt.body()];
+            match self.execute_block(body, Rc::new(RefCell::new(environment)))? {
+                Completion::Break(label) if Self::targets_loop(&label, stmt.label()) => break,
+                Completion::Continue(label) if Self::targets_loop(&label, stmt.label()) => {}
+                Completion::Normal => {}
+                completion => return Ok(completion),
+            }
+        }
+
+        Ok(Completion::Normal)
+    }
+
+    fn visit_function_stmt(&mut self, stmt: &crate::stmt::Function) -> Result<Completion, Unwind> {
+        let function = RloxFunction::new(stmt.clone(), self.environment.clone(), false);
         self.environment
             .borrow_mut()
-            .define(stmt.name().lexeme(), Value::new(function));
-        Ok(())
+            .declare(&stmt.name(), Value::new(function), false)?;
+        Ok(Completion::Normal)
     }
 
-    fn visit_if_stmt(&mut self, stmt: &crate::stmt::If) -> Result<(), Error> {
-        let value = self.evaluate(stmt.condition())?.owned::<TokenLiteral>()?;
-        if let TokenLiteral::Boolean(cond_is_true) = value {
+    fn visit_if_stmt(&mut self, stmt: &crate::stmt::If) -> Result<Completion, Unwind> {
+        let value = self.evaluate(stmt.condition())?;
+        if let Ok(TokenLiteral::Boolean(cond_is_true)) = value.owned::<TokenLiteral>() {
             if cond_is_true {
-                self.execute(stmt.then_branch())?;
-                return Ok(());
+                return Ok(self.execute(stmt.then_branch())?);
             } else if stmt.else_branch().is_some() {
-                self.execute(stmt.else_branch().unwrap())?;
-                return Ok(());
+                return Ok(self.execute(stmt.else_branch().unwrap())?);
             }
-            return Ok(());
+            return Ok(Completion::Normal);
         }
-        Err(Error::report_generic(
+        Err(Error::runtime(
+            stmt.keyword(),
             "Condition in if statement must evaluate to 'true' or 'false'",
-        ))
+        )
+        .into())
     }
 
-    fn visit_print_stmt(&mut self, stmt: &crate::stmt::Print) -> Result<(), Error> {
-        let value = self
-            .evaluate(stmt.expression())?
-            .owned::<TokenLiteral>()
-            .map_err(|_| Error::report_generic("Cannot print non-literals"))?;
-        value.print();
-        Ok(())
+    fn visit_match_stmt(&mut self, stmt: &crate::stmt::Match) -> Result<Completion, Unwind> {
+        match self.match_arm(stmt, self.environment.clone())? {
+            Some((body, environment)) => Ok(self.execute_block(vec![body], environment)?),
+            None => Ok(Completion::Normal),
+        }
+    }
+
+    fn visit_print_stmt(&mut self, stmt: &crate::stmt::Print) -> Result<Completion, Unwind> {
+        let value = self.evaluate(stmt.expression())?;
+        println!("{}", self.stringify(&value)?);
+        Ok(Completion::Normal)
     }
 
-    fn visit_return_stmt(&mut self, stmt: &crate::stmt::Return) -> Result<(), Error> {
+    fn visit_return_stmt(&mut self, stmt: &crate::stmt::Return) -> Result<Completion, Unwind> {
         if let Some(expr) = stmt.value() {
             let value = self.evaluate(expr)?;
-            return Err(Error::Return(value));
+            return Ok(Completion::Return(value));
+        }
+        Ok(Completion::Return(Value::new(TokenLiteral::Nil)))
+    }
+
+    fn visit_throw_stmt(&mut self, stmt: &crate::stmt::Throw) -> Result<Completion, Unwind> {
+        let value = self.evaluate(stmt.value())?;
+        Ok(Completion::Throw(ThrownValue::new(stmt.keyword(), value)))
+    }
+
+    fn visit_trait_stmt(&mut self, stmt: &crate::stmt::Trait) -> Result<Completion, Unwind> {
+        let mut methods = HashMap::new();
+        for method in stmt.methods() {
+            let name = method.name().lexeme();
+            methods.insert(
+                name,
+                RloxFunction::new(method, self.environment.clone(), false),
+            );
         }
-        Err(Error::Return(Value::new(TokenLiteral::Nil)))
+        let trait_ = RloxTrait::new(stmt.name().lexeme(), methods);
+        self.environment
+            .borrow_mut()
+            .declare(&stmt.name(), Value::new(trait_), false)?;
+        Ok(Completion::Normal)
     }
 
-    fn visit_var_stmt(&mut self, stmt: &crate::stmt::Var) -> Result<(), Error> {
+    fn visit_try_stmt(&mut self, stmt: &crate::stmt::Try) -> Result<Completion, Unwind> {
+        let environment = Environment::with_parent(self.environment.clone());
+        let mut result = self.execute_block(stmt.body(), Rc::new(RefCell::new(environment)));
+
+        if let Some(catch) = stmt.catch() {
+            result = match result {
+                Ok(Completion::Throw(thrown)) => self.execute_catch(catch, thrown.value()),
+                Err(err) => match ErrorObject::catch(err) {
+                    Ok(value) => self.execute_catch(catch, value),
+                    Err(err) => Err(err),
+                },
+                result => result,
+            };
+        }
+
+        if let Some(finally) = stmt.finally() {
+            // an error, return or throw in the finally block
+            // replaces the outcome of the rest
+            let environment = Environment::with_parent(self.environment.clone());
+            match self.execute_block(finally, Rc::new(RefCell::new(environment)))? {
+                Completion::Normal => {}
+                completion => return Ok(completion),
+            }
+        }
+
+        Ok(result?)
+    }
+
+    fn visit_var_stmt(&mut self, stmt: &crate::stmt::Var) -> Result<Completion, Unwind> {
         let value = self.evaluate(stmt.initializer())?;
         self.environment
             .borrow_mut()
-            .define(stmt.name().lexeme(), value);
-        Ok(())
+            .declare(&stmt.name(), value, stmt.is_const())?;
+        Ok(Completion::Normal)
+    }
+
+    fn visit_var_pattern_stmt(
+        &mut self,
+        stmt: &crate::stmt::VarPattern,
+    ) -> Result<Completion, Unwind> {
+        let value = self.evaluate(stmt.initializer())?;
+        let mut bindings = Vec::new();
+        stmt.pattern().destructure(&value, &mut bindings)?;
+        let mut environment = self.environment.borrow_mut();
+        for (name, value) in bindings {
+            environment.declare(&name, value, stmt.is_const())?;
+        }
+        Ok(Completion::Normal)
     }
 
-    fn visit_while_stmt(&mut self, stmt: &crate::stmt::While) -> Result<(), Error> {
+    fn visit_while_stmt(&mut self, stmt: &crate::stmt::While) -> Result<Completion, Unwind> {
         loop {
             let value = self
                 .evaluate(stmt.condition())?
                 .owned::<TokenLiteral>()
                 .map_err(|_| {
-                    Error::report_generic(
+                    Error::generic(
                         "Condition in while statement must evaluate to 'true' or 'false'",
                     )
                 })?;
             if let TokenLiteral::Boolean(bool_value) = value {
                 if bool_value {
-                    self.execute(*stmt.body())?;
+                    match self.execute(*stmt.body())? {
+                        Completion::Break(label) if Self::targets_loop(&label, stmt.label()) => {
+                            break
+                        }
+                        // the increment still runs before the next iteration
+                        Completion::Continue(label) if Self::targets_loop(&label, stmt.label()) => {
+                        }
+                        Completion::Normal => {}
+                        completion => return Ok(completion),
+                    }
+                    if let Some(increment) = stmt.increment() {
+                        self.evaluate(increment)?;
+                    }
                 } else {
                     break;
                 }
             } else {
-                return Err(Error::report_generic(
+                return Err(Error::generic(
                     "Condition in while statement must evaluate to 'true' or 'false'",
-                ));
+                )
+                .into());
             }
         }
 
-        Ok(())
+        Ok(Completion::Normal)
+    }
+
+    fn visit_yield_stmt(&mut self, stmt: &crate::stmt::Yield) -> Result<Completion, Unwind> {
+        // generators run the statements holding a yield themselves,
+        // and the resolver rejects a yield anywhere else
+        Err(Error::runtime(stmt.keyword(), "Cannot use 'yield' outside of a generator.").into())
+    }
+}
+
+/// Returns true if both values are equal, where enum values are
+/// compared by their variant and fields, and every other object,
+/// like a list or a function, is only equal to itself
+fn values_equal(left: &Value, right: &Value) -> bool {
+    if let (Ok(left), Ok(right)) = (
+        left.borrowed::<TokenLiteral>(),
+        right.borrowed::<TokenLiteral>(),
+    ) {
+        return left == right;
+    }
+    if let (Ok(left), Ok(right)) = (
+        left.borrowed::<RloxVariant>(),
+        right.borrowed::<RloxVariant>(),
+    ) {
+        return left.equals(right, values_equal);
+    }
+    if let (Ok(left), Ok(right)) = (
+        left.borrowed::<RloxInstance>(),
+        right.borrowed::<RloxInstance>(),
+    ) {
+        return left.same(right);
+    }
+    if let (Ok(left), Ok(right)) = (left.borrowed::<RloxClass>(), right.borrowed::<RloxClass>()) {
+        return left.same(right);
+    }
+    if let (Ok(left), Ok(right)) = (left.borrowed::<RloxFiber>(), right.borrowed::<RloxFiber>()) {
+        return left.same(right);
+    }
+    if let (Ok(left), Ok(right)) = (left.borrowed::<RloxList>(), right.borrowed::<RloxList>()) {
+        return left.same(right);
+    }
+    if let (Ok(left), Ok(right)) = (left.borrowed::<RloxMap>(), right.borrowed::<RloxMap>()) {
+        return left.same(right);
+    }
+    if let (Ok(left), Ok(right)) = (
+        left.borrowed::<RloxFunction>(),
+        right.borrowed::<RloxFunction>(),
+    ) {
+        return left.same(right);
+    }
+    if let (Ok(left), Ok(right)) = (
+        left.borrowed::<RloxGenerator>(),
+        right.borrowed::<RloxGenerator>(),
+    ) {
+        return left.same(right);
+    }
+    if let (Ok(left), Ok(right)) = (
+        left.borrowed::<NativeCallable>(),
+        right.borrowed::<NativeCallable>(),
+    ) {
+        return left.same(right);
+    }
+    false
+}
+
+/// Returns the method with the given name that the class of an
+/// instance defines, bound to the instance, or None for other values
+fn special_method(value: &Value, name: &str) -> Option<RloxFunction> {
+    let instance = value.borrowed::<RloxInstance>().ok()?;
+    let method = instance.class().find_method(name)?;
+    Some(method.bind(instance.clone()))
+}
+
+/// Returns true if the value is nil
+fn is_nil(value: &Value) -> bool {
+    matches!(value.borrowed::<TokenLiteral>(), Ok(TokenLiteral::Nil))
+}
+
+/// Applies a bitwise operator to two integers
+///
+/// The operation returns None when it has no result, which only
+/// happens for shifts by a negative amount or by 32 bits or more.
+fn bitwise(
+    operator: &Token,
+    name: &str,
+    left: TokenLiteral,
+    right: TokenLiteral,
+    operation: fn(i32, i32) -> Option<i32>,
+) -> Result<Value, Error> {
+    match (left, right) {
+        (TokenLiteral::Integer(l_val), TokenLiteral::Integer(r_val)) => {
+            match operation(l_val, r_val) {
+                Some(value) => Ok(Value::new(TokenLiteral::Integer(value))),
+                None => Err(Error::runtime(
+                    operator.clone(),
+                    &format!("{} amount must be between 0 and 31", name),
+                )),
+            }
+        }
+        _ => Err(Error::runtime(
+            operator.clone(),
+            &get_runtime_err_msg(name, Some("integer")),
+        )),
     }
 }
 
 fn get_runtime_err_msg(operator: &str, actor: Option<&str>) -> String {
-    if actor.is_some() {
-        format!("{} must have {} operands", operator, actor.unwrap())
+    if let Some(actor) = actor {
+        format!("{} must have {} operands", operator, actor)
     } else {
         format!("Cannot use {} on two distinct types", operator)
     }
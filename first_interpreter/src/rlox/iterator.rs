@@ -0,0 +1,166 @@
+use crate::{
+    common::Error,
+    rlox::{
+        token::TokenType, Interpreter, RloxGenerator, RloxInstance, RloxList, RloxMap, RloxRange,
+        Token, TokenLiteral, Unwind, Value,
+    },
+};
+
+/// Represents the progress of a for-in loop through a value
+///
+/// Lists are read live, so elements pushed during the loop are visited.
+/// Maps are iterated over the keys they had when the loop started.
+/// Generators are resumed until their body returns. Objects follow the
+/// iterator protocol: the loop calls `iterator()` once, then `next()` on
+/// the result, which returns a map like `{"done": false, "value": 1}`,
+/// until the map it returns has a `done` of true.
+pub enum RloxIterator {
+    List {
+        list: RloxList,
+        position: usize,
+    },
+    Values(std::vec::IntoIter<Value>),
+    Range {
+        range: RloxRange,
+        next: Option<i32>,
+    },
+    Generator {
+        generator: RloxGenerator,
+        keyword: Token,
+    },
+    Object {
+        next: Value,
+        keyword: Token,
+    },
+}
+
+impl RloxIterator {
+    /// Starts iterating over a value
+    ///
+    /// The keyword is where errors from the iteration are reported
+    pub fn new(
+        interpreter: &mut Interpreter,
+        iterable: Value,
+        keyword: &Token,
+    ) -> Result<RloxIterator, Unwind> {
+        if iterable.is::<RloxInstance>() {
+            let method = interpreter.get_property(&iterable, &method_name("iterator", keyword))?;
+            let iterator = interpreter.call_value(method, vec![], keyword)?;
+            // an iterator method may hand back a built-in iterable
+            return match RloxIterator::native(iterator.clone(), keyword) {
+                Some(native) => Ok(native),
+                None => Ok(RloxIterator::Object {
+                    next: interpreter.get_property(&iterator, &method_name("next", keyword))?,
+                    keyword: keyword.clone(),
+                }),
+            };
+        }
+        RloxIterator::native(iterable, keyword).ok_or_else(|| {
+            Error::runtime(
+                keyword.clone(),
+                "Can only iterate over lists, maps, strings, ranges and objects with an 'iterator' method.",
+            )
+            .into()
+        })
+    }
+
+    /// Starts iterating over a built-in iterable, or returns None
+    /// if the value is not one
+    fn native(iterable: Value, keyword: &Token) -> Option<RloxIterator> {
+        if let Ok(list) = iterable.borrowed::<RloxList>() {
+            return Some(RloxIterator::List {
+                list: list.clone(),
+                position: 0,
+            });
+        }
+        if let Ok(map) = iterable.borrowed::<RloxMap>() {
+            let keys = map
+                .entries()
+                .into_iter()
+                .map(|(key, _)| key)
+                .collect::<Vec<Value>>();
+            return Some(RloxIterator::Values(keys.into_iter()));
+        }
+        if let Ok(generator) = iterable.borrowed::<RloxGenerator>() {
+            return Some(RloxIterator::Generator {
+                generator: generator.clone(),
+                keyword: keyword.clone(),
+            });
+        }
+        if let Ok(range) = iterable.borrowed::<RloxRange>() {
+            return Some(RloxIterator::Range {
+                range: *range,
+                next: Some(range.start()),
+            });
+        }
+        if let Ok(TokenLiteral::String(string)) = iterable.borrowed::<TokenLiteral>() {
+            let characters = string
+                .chars()
+                .map(|c| Value::new(TokenLiteral::String(c.to_string())))
+                .collect::<Vec<Value>>();
+            return Some(RloxIterator::Values(characters.into_iter()));
+        }
+        None
+    }
+
+    /// Returns the next value, or None once the iteration is over
+    pub fn next(&mut self, interpreter: &mut Interpreter) -> Result<Option<Value>, Unwind> {
+        match self {
+            RloxIterator::List { list, position } => {
+                let element = list.element(*position);
+                *position += 1;
+                Ok(element)
+            }
+            RloxIterator::Values(values) => Ok(values.next()),
+            RloxIterator::Range { range, next } => match next.filter(|n| range.contains(*n)) {
+                Some(number) => {
+                    *next = range.after(number);
+                    Ok(Some(Value::new(TokenLiteral::Integer(number))))
+                }
+                None => Ok(None),
+            },
+            RloxIterator::Generator { generator, keyword } => {
+                generator.resume(interpreter, keyword)
+            }
+            RloxIterator::Object { next, keyword } => {
+                let result = interpreter.call_value(next.clone(), vec![], keyword)?;
+                let map = result.owned::<RloxMap>().ok();
+                let done = match &map {
+                    Some(map) => map
+                        .get(string("done"), keyword)?
+                        .owned::<TokenLiteral>()
+                        .ok(),
+                    None => None,
+                };
+                match (done, map) {
+                    (Some(TokenLiteral::Boolean(true)), _) => Ok(None),
+                    // a result without a value has nil as its value
+                    (Some(TokenLiteral::Boolean(false)), Some(map)) => {
+                        Ok(Some(map.get(string("value"), keyword)?))
+                    }
+                    _ => Err(Error::runtime(
+                        keyword.clone(),
+                        "Method 'next' must return a map with a 'done' of 'true' or 'false'.",
+                    )
+                    .into()),
+                }
+            }
+        }
+    }
+}
+
+/// Builds a string value, used for the keys of the result of `next()`
+fn string(text: &str) -> Value {
+    Value::new(TokenLiteral::String(text.to_string()))
+}
+
+/// Builds the token a protocol method is looked up with,
+/// on the line of the loop using it
+fn method_name(name: &str, keyword: &Token) -> Token {
+    Token::new(
+        TokenType::Identifier,
+        name,
+        TokenLiteral::Nil,
+        keyword.line(),
+    )
+}
@@ -0,0 +1,71 @@
+//! Contains methods for encoding the syntax tree as JSON and
+//! rebuilding it from JSON.
+//!
+//! The encoding mirrors the `Stmt` and `Expr` types one-to-one, so every
+//! token keeps its lexeme, literal and line. Node ids are written out but
+//! never read back, since the ids in a document could repeat or be missing,
+//! which would mix up what the resolver records about the nodes. The nodes
+//! of a decoded tree get fresh ids instead.
+
+use crate::{common::Error, stmt::Stmt};
+
+/// Encodes a list of statements as a JSON document
+pub fn to_json(statements: &[Stmt]) -> Result<String, Error> {
+    serde_json::to_string_pretty(statements)
+        .map_err(|err| Error::report_generic(&format!("Failed to encode syntax tree: {}", err)))
+}
+
+/// Rebuilds a list of statements from a JSON document
+pub fn from_json(json: &str) -> Result<Vec<Stmt>, Error> {
+    serde_json::from_str(json)
+        .map_err(|err| Error::report_generic(&format!("Failed to decode syntax tree: {}", err)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rlox::{parser::Parser, scanner::Scanner},
+        visitors::ast_printer::AstPrinter,
+    };
+
+    #[test]
+    fn test_json_round_trip() {
+        let source = r#"
+fun greet(name) {
+    if (name == nil) return "nobody";
+    return "Hi, " + name;
+}
+var total = 0;
+for (var i = 0; i < 3; i = i + 1) total = total + 1.5;
+print greet("Bob");
+"#;
+        let tokens = Scanner::new(source.to_string()).scan_tokens().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+
+        let json = to_json(&statements).unwrap();
+        let decoded = from_json(&json).unwrap();
+
+        let mut printer = AstPrinter::new();
+        assert_eq!(
+            printer.print_program(statements),
+            printer.print_program(decoded)
+        );
+    }
+
+    #[test]
+    fn test_json_keeps_token_positions() {
+        let tokens = Scanner::new("\n\nprint x;".to_string())
+            .scan_tokens()
+            .unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+
+        let json = to_json(&statements).unwrap();
+        assert!(json.contains("\"line\": 3"));
+    }
+
+    #[test]
+    fn test_invalid_json() {
+        assert!(from_json("[{\"Print\": 1}]").is_err());
+    }
+}
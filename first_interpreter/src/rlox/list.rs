@@ -0,0 +1,341 @@
+use std::{cell::RefCell, cmp::Ordering, fmt::Display, rc::Rc};
+
+use crate::{
+    common::Error,
+    rlox::{Arity, Interpreter, NativeCallable, RloxCallable, Token, TokenLiteral, Unwind, Value},
+};
+
+/// The native methods of a list, with the number of arguments they take
+const METHODS: [(&str, Arity); 9] = [
+    ("filter", Arity::exact(1)),
+    ("insert", Arity::exact(2)),
+    ("len", Arity::exact(0)),
+    ("map", Arity::exact(1)),
+    ("pop", Arity::exact(0)),
+    ("push", Arity::exact(1)),
+    ("remove", Arity::exact(1)),
+    ("slice", Arity::range(1, 2)),
+    ("sort", Arity::exact(0)),
+];
+
+/// Represents a list at runtime
+///
+/// Cloning a list clones the reference, so every copy
+/// sees the changes made through the others
+#[derive(Debug, Clone)]
+pub struct RloxList {
+    elements: Rc<RefCell<Vec<Value>>>,
+}
+
+impl RloxList {
+    /// Constructs a new RloxList holding the given elements
+    pub fn new(elements: Vec<Value>) -> RloxList {
+        RloxList {
+            elements: Rc::new(RefCell::new(elements)),
+        }
+    }
+
+    /// Returns true if both values are the same list
+    pub fn same(&self, other: &RloxList) -> bool {
+        Rc::ptr_eq(&self.elements, &other.elements)
+    }
+
+    /// Returns the number of elements
+    pub fn len(&self) -> usize {
+        self.elements.borrow().len()
+    }
+
+    /// Returns true if the list has no elements
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a copy of the elements as they are now
+    pub fn elements(&self) -> Vec<Value> {
+        self.elements.borrow().clone()
+    }
+
+    /// Returns the element at a position, or None past the end of the list
+    pub fn element(&self, position: usize) -> Option<Value> {
+        self.elements.borrow().get(position).cloned()
+    }
+
+    /// Returns the element at an index
+    pub fn get(&self, index: Value, bracket: &Token) -> Result<Value, Error> {
+        let index = self.position(index, self.len(), bracket)?;
+        Ok(self.elements.borrow()[index].clone())
+    }
+
+    /// Replaces the element at an index
+    pub fn set(&self, index: Value, value: Value, bracket: &Token) -> Result<(), Error> {
+        let index = self.position(index, self.len(), bracket)?;
+        self.elements.borrow_mut()[index] = value;
+        Ok(())
+    }
+
+    /// Returns the native method with the given name, bound to this list
+    pub fn method(&self, name: &Token) -> Result<Value, Error> {
+        match METHODS.iter().find(|(method, _)| *method == name.lexeme()) {
+            Some(_) => Ok(Value::new(NativeCallable::ListMethod(ListMethod {
+                list: self.clone(),
+                name: name.clone(),
+            }))),
+            None => Err(Error::runtime(
+                name.clone(),
+                &format!("Undefined list method '{}'.", name.lexeme()),
+            )),
+        }
+    }
+
+    /// Converts an index value into a position below the given bound
+    fn position(&self, index: Value, bound: usize, token: &Token) -> Result<usize, Error> {
+        let index = match index.owned::<TokenLiteral>() {
+            Ok(TokenLiteral::Integer(index)) => index,
+            _ => {
+                return Err(Error::runtime(
+                    token.clone(),
+                    "List index must be an integer.",
+                ))
+            }
+        };
+        if index < 0 || index as usize >= bound {
+            return Err(Error::runtime(
+                token.clone(),
+                &format!(
+                    "List index {} out of range for a list of length {}.",
+                    index,
+                    self.len()
+                ),
+            ));
+        }
+        Ok(index as usize)
+    }
+}
+
+/// Represents a native method read from a list, like `xs.push`
+#[derive(Debug, Clone)]
+pub struct ListMethod {
+    list: RloxList,
+    /// The name the method was read with, used to report errors
+    name: Token,
+}
+
+impl ListMethod {
+    /// Returns true if both are the same method of the same list
+    pub fn same(&self, other: &ListMethod) -> bool {
+        self.list.same(&other.list) && self.name.lexeme() == other.name.lexeme()
+    }
+}
+
+impl Display for ListMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<native fn {}>", self.name.lexeme())
+    }
+}
+
+impl RloxCallable for ListMethod {
+    fn arity(&self) -> Arity {
+        METHODS
+            .iter()
+            .find(|(method, _)| *method == self.name.lexeme())
+            .map(|(_, arity)| *arity)
+            .expect("list methods are only bound by RloxList::method")
+    }
+
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, Unwind> {
+        let list = &self.list;
+        let mut arguments = arguments.into_iter();
+        let mut argument = || arguments.next().expect("arity is checked by the caller");
+
+        let value = match self.name.lexeme().as_str() {
+            "push" => {
+                list.elements.borrow_mut().push(argument());
+                Value::new(TokenLiteral::Nil)
+            }
+            "pop" => match list.elements.borrow_mut().pop() {
+                Some(value) => value,
+                None => {
+                    return Err(
+                        Error::runtime(self.name.clone(), "Cannot pop from an empty list.").into(),
+                    )
+                }
+            },
+            "len" => Value::new(TokenLiteral::Integer(list.len() as i32)),
+            "insert" => {
+                // inserting right after the last element is allowed
+                let index = list.position(argument(), list.len() + 1, &self.name)?;
+                list.elements.borrow_mut().insert(index, argument());
+                Value::new(TokenLiteral::Nil)
+            }
+            "remove" => {
+                let index = list.position(argument(), list.len(), &self.name)?;
+                list.elements.borrow_mut().remove(index)
+            }
+            "slice" => {
+                let start = list.position(argument(), list.len() + 1, &self.name)?;
+                // the end defaults to the length of the list
+                let end = match arguments.next() {
+                    Some(end) => list.position(end, list.len() + 1, &self.name)?,
+                    None => list.len(),
+                };
+                if start > end {
+                    return Err(Error::runtime(
+                        self.name.clone(),
+                        "Slice start must not be after its end.",
+                    )
+                    .into());
+                }
+                Value::new(RloxList::new(list.elements.borrow()[start..end].to_vec()))
+            }
+            "map" => {
+                let function = argument();
+                let mut mapped = Vec::with_capacity(list.len());
+                for element in list.elements() {
+                    mapped.push(interpreter.call_value(
+                        function.clone(),
+                        vec![element],
+                        &self.name,
+                    )?);
+                }
+                Value::new(RloxList::new(mapped))
+            }
+            "filter" => {
+                let function = argument();
+                let mut kept = Vec::new();
+                for element in list.elements() {
+                    let keep = interpreter
+                        .call_value(function.clone(), vec![element.clone()], &self.name)?
+                        .owned::<TokenLiteral>();
+                    match keep {
+                        Ok(TokenLiteral::Boolean(true)) => kept.push(element),
+                        Ok(TokenLiteral::Boolean(false)) => {}
+                        _ => {
+                            return Err(Error::runtime(
+                                self.name.clone(),
+                                "Filter function must return 'true' or 'false'.",
+                            )
+                            .into())
+                        }
+                    }
+                }
+                Value::new(RloxList::new(kept))
+            }
+            "sort" => {
+                let mut elements = list.elements();
+                let mut comparable = true;
+                elements.sort_by(|a, b| {
+                    compare(a, b).unwrap_or_else(|| {
+                        comparable = false;
+                        Ordering::Equal
+                    })
+                });
+                if !comparable {
+                    return Err(Error::runtime(
+                        self.name.clone(),
+                        "Can only sort lists of numbers or lists of strings.",
+                    )
+                    .into());
+                }
+                *list.elements.borrow_mut() = elements;
+                Value::new(TokenLiteral::Nil)
+            }
+            _ => unreachable!("list methods are only bound by RloxList::method"),
+        };
+        Ok(value)
+    }
+}
+
+/// Orders two values of the same number or string type
+fn compare(a: &Value, b: &Value) -> Option<Ordering> {
+    match (
+        a.borrowed::<TokenLiteral>().ok()?,
+        b.borrowed::<TokenLiteral>().ok()?,
+    ) {
+        (TokenLiteral::Integer(a), TokenLiteral::Integer(b)) => Some(a.cmp(b)),
+        (TokenLiteral::Float(a), TokenLiteral::Float(b)) => a.partial_cmp(b),
+        (TokenLiteral::String(a), TokenLiteral::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rlox::token::TokenType;
+
+    fn token(lexeme: &str) -> Token {
+        Token::new(TokenType::Identifier, lexeme, TokenLiteral::Nil, 1)
+    }
+
+    fn integers(list: &RloxList) -> Vec<i32> {
+        list.elements()
+            .into_iter()
+            .map(|value| match value.owned::<TokenLiteral>() {
+                Ok(TokenLiteral::Integer(v)) => v,
+                _ => panic!("expected an integer"),
+            })
+            .collect()
+    }
+
+    fn call(list: &RloxList, name: &str, arguments: Vec<Value>) -> Result<Value, Unwind> {
+        let method = list.method(&token(name))?.owned::<NativeCallable>()?;
+        method.call(&mut Interpreter::new(), arguments)
+    }
+
+    #[test]
+    fn test_list_methods() {
+        let int = |v| Value::new(TokenLiteral::Integer(v));
+        let list = RloxList::new(vec![int(3), int(1)]);
+        let alias = list.clone();
+
+        call(&list, "push", vec![int(2)]).unwrap();
+        call(&list, "insert", vec![int(0), int(5)]).unwrap();
+        assert_eq!(integers(&alias), vec![5, 3, 1, 2]);
+
+        call(&list, "sort", vec![]).unwrap();
+        assert_eq!(integers(&list), vec![1, 2, 3, 5]);
+
+        let slice = call(&list, "slice", vec![int(1), int(3)]).unwrap();
+        assert_eq!(integers(&slice.owned::<RloxList>().unwrap()), vec![2, 3]);
+        let tail = call(&list, "slice", vec![int(2)]).unwrap();
+        assert_eq!(integers(&tail.owned::<RloxList>().unwrap()), vec![3, 5]);
+
+        let removed = call(&list, "remove", vec![int(0)]).unwrap();
+        assert_eq!(
+            removed.owned::<TokenLiteral>().unwrap(),
+            TokenLiteral::Integer(1)
+        );
+        let popped = call(&list, "pop", vec![]).unwrap();
+        assert_eq!(
+            popped.owned::<TokenLiteral>().unwrap(),
+            TokenLiteral::Integer(5)
+        );
+        assert_eq!(integers(&list), vec![2, 3]);
+    }
+
+    #[test]
+    fn test_list_errors() {
+        let list = RloxList::new(vec![Value::new(TokenLiteral::Integer(1))]);
+        let bracket = token("]");
+
+        assert!(list
+            .get(Value::new(TokenLiteral::Integer(1)), &bracket)
+            .is_err());
+        assert!(list
+            .get(Value::new(TokenLiteral::Integer(-1)), &bracket)
+            .is_err());
+        assert!(list
+            .get(Value::new(TokenLiteral::Float(0.0)), &bracket)
+            .is_err());
+        assert!(list.method(&token("length")).is_err());
+
+        call(&list, "pop", vec![]).unwrap();
+        assert!(call(&list, "pop", vec![]).is_err());
+
+        let mixed = RloxList::new(vec![
+            Value::new(TokenLiteral::Integer(1)),
+            Value::new(TokenLiteral::String("a".to_string())),
+        ]);
+        assert!(call(&mixed, "sort", vec![]).is_err());
+    }
+}
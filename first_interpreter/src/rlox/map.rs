@@ -0,0 +1,310 @@
+use std::{cell::RefCell, collections::HashMap, fmt::Display, rc::Rc};
+
+use crate::{
+    common::Error,
+    rlox::{
+        Arity, Interpreter, NativeCallable, RloxCallable, RloxList, Token, TokenLiteral, Unwind,
+        Value,
+    },
+};
+
+/// The native methods of a map, with the number of arguments they take
+const METHODS: [(&str, Arity); 5] = [
+    ("has", Arity::exact(1)),
+    ("keys", Arity::exact(0)),
+    ("len", Arity::exact(0)),
+    ("remove", Arity::exact(1)),
+    ("values", Arity::exact(0)),
+];
+
+/// Represents a map key, hashed by value
+///
+/// Integers and floats are distinct keys, like they are distinct values
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum MapKey {
+    String(String),
+    Integer(i32),
+    /// The bits of the float, with negative zero folded into zero
+    Float(u64),
+    Boolean(bool),
+    Nil,
+}
+
+impl MapKey {
+    /// Builds the key for a value, which must be a string, number, boolean or nil
+    fn new(value: &Value, token: &Token) -> Result<MapKey, Error> {
+        let key = match value.borrowed::<TokenLiteral>() {
+            Ok(TokenLiteral::String(v)) => MapKey::String(v.clone()),
+            Ok(TokenLiteral::Integer(v)) => MapKey::Integer(*v),
+            Ok(TokenLiteral::Float(v)) => MapKey::Float((v + 0.0).to_bits()),
+            Ok(TokenLiteral::Boolean(v)) => MapKey::Boolean(*v),
+            Ok(TokenLiteral::Nil) => MapKey::Nil,
+            Err(_) => {
+                return Err(Error::runtime(
+                    token.clone(),
+                    "Map keys must be strings, numbers, booleans or nil.",
+                ))
+            }
+        };
+        Ok(key)
+    }
+}
+
+/// Holds the entries of a map in insertion order
+#[derive(Debug, Default)]
+struct Entries {
+    /// The position of every key in the entries
+    positions: HashMap<MapKey, usize>,
+    entries: Vec<(Value, Value)>,
+}
+
+/// Represents a map at runtime
+///
+/// Cloning a map clones the reference, so every copy
+/// sees the changes made through the others
+#[derive(Debug, Clone)]
+pub struct RloxMap {
+    entries: Rc<RefCell<Entries>>,
+}
+
+impl RloxMap {
+    /// Constructs a new empty RloxMap
+    pub fn new() -> RloxMap {
+        RloxMap {
+            entries: Rc::new(RefCell::new(Entries::default())),
+        }
+    }
+
+    /// Returns true if both values are the same map
+    pub fn same(&self, other: &RloxMap) -> bool {
+        Rc::ptr_eq(&self.entries, &other.entries)
+    }
+
+    /// Returns the number of entries
+    pub fn len(&self) -> usize {
+        self.entries.borrow().entries.len()
+    }
+
+    /// Returns true if the map has no entries
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a copy of the entries as they are now, in insertion order
+    pub fn entries(&self) -> Vec<(Value, Value)> {
+        self.entries.borrow().entries.clone()
+    }
+
+    /// Returns the value of a key, or nil if the map does not have the key
+    pub fn get(&self, key: Value, bracket: &Token) -> Result<Value, Error> {
+        let key = MapKey::new(&key, bracket)?;
+        let entries = self.entries.borrow();
+        match entries.positions.get(&key) {
+            Some(position) => Ok(entries.entries[*position].1.clone()),
+            None => Ok(Value::new(TokenLiteral::Nil)),
+        }
+    }
+
+    /// Sets the value of a key, keeping the position of a key already in the map
+    pub fn set(&self, key: Value, value: Value, bracket: &Token) -> Result<(), Error> {
+        let hashed = MapKey::new(&key, bracket)?;
+        let mut entries = self.entries.borrow_mut();
+        match entries.positions.get(&hashed) {
+            Some(position) => {
+                let position = *position;
+                entries.entries[position].1 = value;
+            }
+            None => {
+                let position = entries.entries.len();
+                entries.positions.insert(hashed, position);
+                entries.entries.push((key, value));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns true if the map has the key
+    pub fn has(&self, key: Value, token: &Token) -> Result<bool, Error> {
+        let key = MapKey::new(&key, token)?;
+        Ok(self.entries.borrow().positions.contains_key(&key))
+    }
+
+    /// Removes a key, returning its value or nil if the map did not have it
+    pub fn remove(&self, key: Value, token: &Token) -> Result<Value, Error> {
+        let key = MapKey::new(&key, token)?;
+        let mut entries = self.entries.borrow_mut();
+        let position = match entries.positions.remove(&key) {
+            Some(position) => position,
+            None => return Ok(Value::new(TokenLiteral::Nil)),
+        };
+        // every entry after the removed one moves back by one
+        for other in entries.positions.values_mut() {
+            if *other > position {
+                *other -= 1;
+            }
+        }
+        Ok(entries.entries.remove(position).1)
+    }
+
+    /// Returns the native method with the given name, bound to this map
+    pub fn method(&self, name: &Token) -> Result<Value, Error> {
+        match METHODS.iter().find(|(method, _)| *method == name.lexeme()) {
+            Some(_) => Ok(Value::new(NativeCallable::MapMethod(MapMethod {
+                map: self.clone(),
+                name: name.clone(),
+            }))),
+            None => Err(Error::runtime(
+                name.clone(),
+                &format!("Undefined map method '{}'.", name.lexeme()),
+            )),
+        }
+    }
+}
+
+impl Default for RloxMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Represents a native method read from a map, like `m.keys`
+#[derive(Debug, Clone)]
+pub struct MapMethod {
+    map: RloxMap,
+    /// The name the method was read with, used to report errors
+    name: Token,
+}
+
+impl MapMethod {
+    /// Returns true if both are the same method of the same map
+    pub fn same(&self, other: &MapMethod) -> bool {
+        self.map.same(&other.map) && self.name.lexeme() == other.name.lexeme()
+    }
+}
+
+impl Display for MapMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<native fn {}>", self.name.lexeme())
+    }
+}
+
+impl RloxCallable for MapMethod {
+    fn arity(&self) -> Arity {
+        METHODS
+            .iter()
+            .find(|(method, _)| *method == self.name.lexeme())
+            .map(|(_, arity)| *arity)
+            .expect("map methods are only bound by RloxMap::method")
+    }
+
+    fn call(&self, _interpreter: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, Unwind> {
+        let map = &self.map;
+        let mut arguments = arguments.into_iter();
+        let mut argument = || arguments.next().expect("arity is checked by the caller");
+
+        let value = match self.name.lexeme().as_str() {
+            "has" => Value::new(TokenLiteral::Boolean(map.has(argument(), &self.name)?)),
+            "keys" => {
+                let keys = map.entries().into_iter().map(|(key, _)| key).collect();
+                Value::new(RloxList::new(keys))
+            }
+            "len" => Value::new(TokenLiteral::Integer(map.len() as i32)),
+            "remove" => map.remove(argument(), &self.name)?,
+            "values" => {
+                let values = map.entries().into_iter().map(|(_, value)| value).collect();
+                Value::new(RloxList::new(values))
+            }
+            _ => unreachable!("map methods are only bound by RloxMap::method"),
+        };
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rlox::token::TokenType;
+
+    fn token() -> Token {
+        Token::new(TokenType::RightBracket, "]", TokenLiteral::Nil, 1)
+    }
+
+    fn literal(value: Value) -> TokenLiteral {
+        value.owned::<TokenLiteral>().unwrap()
+    }
+
+    #[test]
+    fn test_map_keys() {
+        let map = RloxMap::new();
+        let string = |v: &str| Value::new(TokenLiteral::String(v.to_string()));
+
+        map.set(string("b"), Value::new(TokenLiteral::Integer(1)), &token())
+            .unwrap();
+        map.set(
+            Value::new(TokenLiteral::Integer(1)),
+            string("int"),
+            &token(),
+        )
+        .unwrap();
+        map.set(
+            Value::new(TokenLiteral::Float(1.0)),
+            string("float"),
+            &token(),
+        )
+        .unwrap();
+        map.set(Value::new(TokenLiteral::Nil), string("nil"), &token())
+            .unwrap();
+        // overwriting keeps the position of the key
+        map.set(string("b"), Value::new(TokenLiteral::Integer(2)), &token())
+            .unwrap();
+
+        assert_eq!(map.len(), 4);
+        assert_eq!(
+            literal(map.get(string("b"), &token()).unwrap()),
+            TokenLiteral::Integer(2)
+        );
+        assert_eq!(
+            literal(
+                map.get(Value::new(TokenLiteral::Float(1.0)), &token())
+                    .unwrap()
+            ),
+            TokenLiteral::String("float".to_string())
+        );
+        assert_eq!(
+            literal(map.get(string("missing"), &token()).unwrap()),
+            TokenLiteral::Nil
+        );
+
+        map.remove(Value::new(TokenLiteral::Integer(1)), &token())
+            .unwrap();
+        let keys = map
+            .entries()
+            .into_iter()
+            .map(|(key, _)| literal(key))
+            .collect::<Vec<TokenLiteral>>();
+        assert_eq!(
+            keys,
+            vec![
+                TokenLiteral::String("b".to_string()),
+                TokenLiteral::Float(1.0),
+                TokenLiteral::Nil
+            ]
+        );
+        assert!(!map
+            .has(Value::new(TokenLiteral::Integer(1)), &token())
+            .unwrap());
+        assert!(map.has(Value::new(TokenLiteral::Nil), &token()).unwrap());
+    }
+
+    #[test]
+    fn test_map_errors() {
+        let map = RloxMap::new();
+        let list = Value::new(RloxList::new(vec![]));
+
+        assert!(map.get(list.clone(), &token()).is_err());
+        assert!(map
+            .set(list, Value::new(TokenLiteral::Nil), &token())
+            .is_err());
+        assert!(map.method(&token()).is_err());
+    }
+}
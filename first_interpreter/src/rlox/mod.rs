@@ -1,22 +1,46 @@
 pub mod callable;
+pub mod completion;
 pub mod environment;
+pub mod error_object;
+pub mod fiber;
+pub mod generator;
+pub mod instance;
 pub mod interpreter;
+pub mod iterator;
+pub mod json;
+pub mod list;
+pub mod map;
 pub mod native;
 pub mod parser;
+pub mod range;
 pub mod scanner;
 pub mod token;
 pub mod types;
+pub mod variant;
 pub mod resolver;
 
-use crate::{common::errors::Error, rlox::interpreter::Interpreter};
+use crate::{
+    common::errors::Error, rlox::interpreter::Interpreter, stmt::Stmt,
+    visitors::{ast_printer::AstPrinter, cfg, dot_printer::DotPrinter, formatter::Formatter},
+};
 use parser::Parser;
 use scanner::Scanner;
 
 pub use {
-    callable::RloxCallable,
+    callable::{Arity, RloxCallable},
+    completion::{Completion, Unwind},
+    error_object::ErrorObject,
+    fiber::{FiberClass, FiberFunction, FiberMethod, RloxFiber},
+    generator::{GeneratorMethod, RloxGenerator},
+    instance::RloxInstance,
+    iterator::RloxIterator,
+    list::{ListMethod, RloxList},
+    map::{MapMethod, RloxMap},
     native::{ClockFunction, NativeCallable},
+    range::{RangeFunction, RloxRange},
     token::{Token, TokenLiteral},
     types::Value,
+    variant::{RloxEnum, RloxVariant, VariantConstructor},
 };
 
 /// This is a wrapper for running the source code
@@ -52,31 +76,143 @@ pub fn run_prompt() -> Result<(), &'static str> {
 ///
 /// It starts the interpreter process after reading the source file
 pub fn run_file(file_path: &str) -> Result<(), Error> {
-    let prog = match std::fs::read_to_string(file_path) {
-        Ok(prog) => prog,
-        Err(err) => {
-            return Err(Error::report_io(&format!(
-                "Failed to read source file: {:?}",
-                err
-            )));
-        }
-    };
+    let prog = read_source(file_path)?;
 
     run(prog)?;
 
     Ok(())
 }
 
-/// This starts the compilation process for the source code
-fn run(source: String) -> Result<(), Error> {
-    println!("Running program...");
+/// Prints every token the scanner produces for the source file
+pub fn dump_tokens(file_path: &str) -> Result<(), Error> {
+    let prog = read_source(file_path)?;
+
+    let mut scanner = Scanner::new(prog);
+    for token in scanner.scan_tokens()? {
+        println!("[line {}] {}", token.line(), token);
+    }
+
+    Ok(())
+}
+
+/// Prints the syntax tree the parser produces for the source file
+pub fn dump_ast(file_path: &str) -> Result<(), Error> {
+    let prog = read_source(file_path)?;
+    let statements = parse_source(prog)?;
+
+    println!("{}", AstPrinter::new().print_program(statements));
+
+    Ok(())
+}
+
+/// Prints the syntax tree the parser produces for the source file as JSON
+pub fn dump_json(file_path: &str) -> Result<(), Error> {
+    let prog = read_source(file_path)?;
+    let statements = parse_source(prog)?;
+
+    println!("{}", json::to_json(&statements)?);
+
+    Ok(())
+}
+
+/// Prints the syntax tree the parser produces for the source file
+/// as a Graphviz DOT graph
+pub fn dump_dot(file_path: &str) -> Result<(), Error> {
+    let prog = read_source(file_path)?;
+    let statements = parse_source(prog)?;
+
+    println!("{}", DotPrinter::new().print_program(statements));
+
+    Ok(())
+}
+
+/// Prints the control-flow graph of every function in the source file
+/// as a Graphviz DOT graph
+pub fn dump_cfg(file_path: &str) -> Result<(), Error> {
+    let prog = read_source(file_path)?;
+    let statements = parse_source(prog)?;
+
+    println!("{}", cfg::to_dot(&cfg::build(statements)));
+
+    Ok(())
+}
 
+/// This is a wrapper for running a syntax tree encoded as JSON
+///
+/// It rebuilds the statements from the file and interprets them
+/// without going through the scanner and parser.
+pub fn run_json_file(file_path: &str) -> Result<(), Error> {
+    let json = read_source(file_path)?;
+    let statements = json::from_json(&json)?;
+
+    let mut interpreter = Interpreter::new();
+    interpreter.interpret(statements)?;
+
+    Ok(())
+}
+
+/// Formats the source file in place
+///
+/// With `check` set the file is left untouched, and an error is
+/// returned if formatting would change it.
+pub fn format_file(file_path: &str, check: bool) -> Result<(), Error> {
+    let prog = read_source(file_path)?;
+    let formatted = format_source(prog.clone())?;
+
+    if formatted == prog {
+        return Ok(());
+    }
+    if check {
+        println!("Would reformat {}", file_path);
+        return Err(Error::report_generic(&format!(
+            "{} is not formatted",
+            file_path
+        )));
+    }
+
+    std::fs::write(file_path, formatted).map_err(|err| {
+        Error::report_io(&format!("Failed to write source file: {:?}", err))
+    })
+}
+
+/// Formats the source code, keeping its comments and blank lines
+pub fn format_source(source: String) -> Result<String, Error> {
     let mut scanner = Scanner::new(source);
     let tokens = scanner.scan_tokens()?;
 
     let mut parser = Parser::new(tokens);
     let statements = parser.parse()?;
 
+    let mut formatter = Formatter::new(parser.statement_trivia().clone());
+    Ok(formatter.format(statements))
+}
+
+/// Reads the source file into a string
+fn read_source(file_path: &str) -> Result<String, Error> {
+    match std::fs::read_to_string(file_path) {
+        Ok(prog) => Ok(prog),
+        Err(err) => Err(Error::report_io(&format!(
+            "Failed to read source file: {:?}",
+            err
+        ))),
+    }
+}
+
+/// Scans and parses the source code into a list of statements
+fn parse_source(source: String) -> Result<Vec<Stmt>, Error> {
+    let mut scanner = Scanner::new(source);
+    let tokens = scanner.scan_tokens()?;
+
+    let mut parser = Parser::new(tokens);
+    parser.parse()
+}
+
+/// This starts the compilation process for the source code
+fn run(source: String) -> Result<(), Error> {
+    println!("Running program...");
+
+    let statements = parse_source(source)?;
+
     let mut interpreter = Interpreter::new();
     interpreter.interpret(statements)?;
 
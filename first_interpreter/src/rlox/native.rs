@@ -5,18 +5,55 @@ use std::{
 
 use crate::{
     common::Error,
-    rlox::{RloxCallable, Value},
+    rlox::{
+        Arity, FiberFunction, FiberMethod, GeneratorMethod, ListMethod, MapMethod, RangeFunction,
+        RloxCallable, Token, Unwind, Value, VariantConstructor,
+    },
 };
 
 #[derive(Debug, Clone)]
 pub enum NativeCallable {
     ClockFunction(ClockFunction),
+    FiberFunction(FiberFunction),
+    FiberMethod(FiberMethod),
+    GeneratorMethod(GeneratorMethod),
+    ListMethod(ListMethod),
+    MapMethod(MapMethod),
+    RangeFunction(RangeFunction),
+    VariantConstructor(VariantConstructor),
+}
+
+impl NativeCallable {
+    /// Returns true if both are the same native function, or
+    /// the same method of the same value
+    pub fn same(&self, other: &NativeCallable) -> bool {
+        match (self, other) {
+            (NativeCallable::ClockFunction(_), NativeCallable::ClockFunction(_)) => true,
+            (NativeCallable::FiberFunction(a), NativeCallable::FiberFunction(b)) => a.same(b),
+            (NativeCallable::FiberMethod(a), NativeCallable::FiberMethod(b)) => a.same(b),
+            (NativeCallable::GeneratorMethod(a), NativeCallable::GeneratorMethod(b)) => a.same(b),
+            (NativeCallable::ListMethod(a), NativeCallable::ListMethod(b)) => a.same(b),
+            (NativeCallable::MapMethod(a), NativeCallable::MapMethod(b)) => a.same(b),
+            (NativeCallable::RangeFunction(_), NativeCallable::RangeFunction(_)) => true,
+            (NativeCallable::VariantConstructor(a), NativeCallable::VariantConstructor(b)) => {
+                a.same(b)
+            }
+            _ => false,
+        }
+    }
 }
 
 impl RloxCallable for NativeCallable {
-    fn arity(&self) -> usize {
+    fn arity(&self) -> Arity {
         match self {
             NativeCallable::ClockFunction(c) => c.arity(),
+            NativeCallable::FiberFunction(f) => f.arity(),
+            NativeCallable::FiberMethod(m) => m.arity(),
+            NativeCallable::GeneratorMethod(m) => m.arity(),
+            NativeCallable::ListMethod(m) => m.arity(),
+            NativeCallable::MapMethod(m) => m.arity(),
+            NativeCallable::RangeFunction(r) => r.arity(),
+            NativeCallable::VariantConstructor(v) => v.arity(),
         }
     }
 
@@ -24,9 +61,50 @@ impl RloxCallable for NativeCallable {
         &self,
         interpreter: &mut super::interpreter::Interpreter,
         arguments: Vec<Value>,
-    ) -> Result<Value, Error> {
+    ) -> Result<Value, Unwind> {
         match self {
             NativeCallable::ClockFunction(c) => c.call(interpreter, arguments),
+            NativeCallable::FiberFunction(f) => f.call(interpreter, arguments),
+            NativeCallable::FiberMethod(m) => m.call(interpreter, arguments),
+            NativeCallable::GeneratorMethod(m) => m.call(interpreter, arguments),
+            NativeCallable::ListMethod(m) => m.call(interpreter, arguments),
+            NativeCallable::MapMethod(m) => m.call(interpreter, arguments),
+            NativeCallable::RangeFunction(r) => r.call(interpreter, arguments),
+            NativeCallable::VariantConstructor(v) => v.call(interpreter, arguments),
+        }
+    }
+
+    fn call_with_named(
+        &self,
+        interpreter: &mut super::interpreter::Interpreter,
+        arguments: Vec<Value>,
+        named: Vec<(Token, Value)>,
+        paren: &Token,
+    ) -> Result<Value, Unwind> {
+        match (self, named.first()) {
+            (_, Some((name, _))) => Err(Error::runtime(
+                name.clone(),
+                "Native functions do not take named arguments.",
+            )
+            .into()),
+            // range checks its arguments itself, so it reports them at the call
+            (NativeCallable::RangeFunction(r), None) => Ok(r.call_at(arguments, paren)?),
+            (_, None) => self.call(interpreter, arguments),
+        }
+    }
+}
+
+impl Display for NativeCallable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NativeCallable::ClockFunction(c) => c.fmt(f),
+            NativeCallable::FiberFunction(function) => function.fmt(f),
+            NativeCallable::FiberMethod(m) => m.fmt(f),
+            NativeCallable::GeneratorMethod(m) => m.fmt(f),
+            NativeCallable::ListMethod(m) => m.fmt(f),
+            NativeCallable::MapMethod(m) => m.fmt(f),
+            NativeCallable::RangeFunction(r) => r.fmt(f),
+            NativeCallable::VariantConstructor(v) => v.fmt(f),
         }
     }
 }
@@ -47,15 +125,15 @@ impl Display for ClockFunction {
 }
 
 impl RloxCallable for ClockFunction {
-    fn arity(&self) -> usize {
-        0
+    fn arity(&self) -> Arity {
+        Arity::exact(0)
     }
 
     fn call(
         &self,
         _interpreter: &mut super::interpreter::Interpreter,
         _arguments: Vec<super::Value>,
-    ) -> Result<Value, Error> {
+    ) -> Result<Value, Unwind> {
         let now = SystemTime::now();
         Ok(Value::new(
             now.duration_since(UNIX_EPOCH)
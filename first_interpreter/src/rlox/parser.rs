@@ -1,15 +1,20 @@
 use crate::{
-    common::{errors::Error, MAX_FUNCTION_ARGUMENTS_SIZE},
+    common::{errors::Error, NodeId, MAX_FUNCTION_ARGUMENTS_SIZE},
     expressions::{
-        assign::Assign, binary::Binary, expr::Expr, grouping::Grouping, literal::Literal,
-        unary::Unary, Call, Logical, Variable,
+        assign::Assign, assign_pattern::AssignPattern, binary::Binary, expr::Expr,
+        grouping::Grouping, literal::Literal, unary::Unary, Call, Get, Index, Lambda, List,
+        Logical, Map, Set, SetIndex, Super, Ternary, This, Variable,
     },
     rlox::token::Token,
-    stmt::{Block, Expression, Function, If, Print, Return, Stmt, Var, While},
+    stmt::{
+        Block, Break, CatchClause, Class, Continue, Enum, EnumVariant, Expression, ForIn, Function,
+        If, Match, MatchArm, Param, Pattern, Print, Return, Stmt, Throw, Trait, Try, Var,
+        VarPattern, While, Yield,
+    },
 };
 
 use super::{
-    token::{TokenLiteral, TokenType},
+    token::{TokenLiteral, TokenType, Trivia},
     Value,
 };
 
@@ -22,16 +27,41 @@ enum CallableKind {
     Method,
 }
 
+/// Holds the trivia surrounding a statement in a statement list
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatementTrivia {
+    /// Trivia found before the statement
+    pub leading: Vec<Trivia>,
+    /// Trivia found inside the statement but not in a nested statement
+    pub trailing: Vec<Trivia>,
+    /// The tokens of the statement, kept only if it has a comment inside
+    /// it, so the comment can be printed next to the token it came before
+    pub tokens: Vec<Token>,
+    /// The number of entries after this one that belong to statements
+    /// nested in it, which are only filled in along with the tokens
+    pub nested: usize,
+}
+
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
+    /// Index of the first token whose trivia has not been collected yet
+    collected: usize,
+    /// The trivia of every declaration and of every end of a statement
+    /// list, in the order the parser reached them
+    statement_trivia: Vec<StatementTrivia>,
 }
 
 /// Methods for the Parser
 impl Parser {
     /// Constructs a new Parser
     pub fn new(tokens: Vec<Token>) -> Parser {
-        Parser { tokens, current: 0 }
+        Parser {
+            tokens,
+            current: 0,
+            collected: 0,
+            statement_trivia: Vec::new(),
+        }
     }
 
     /// Parses a series of statements, as many as it
@@ -41,9 +71,19 @@ impl Parser {
         while !self.is_at_end() {
             statements.push(self.declaration()?)
         }
+        self.collect_list_end();
         Ok(statements)
     }
 
+    /// Returns the trivia of every statement in a statement list, in the
+    /// order a pre-order walk of the parsed statements reaches them.
+    ///
+    /// Every statement list (the program, a block, a function body) yields
+    /// one entry per statement followed by one entry for its end.
+    pub fn statement_trivia(&self) -> &Vec<StatementTrivia> {
+        &self.statement_trivia
+    }
+
     /// Returns true if any of the token types match the current token
     fn match_token(&mut self, token_types: Vec<TokenType>) -> bool {
         for token_type in token_types {
@@ -77,6 +117,20 @@ impl Parser {
         self.tokens[self.current].clone()
     }
 
+    /// Returns true if the token after the current one is of the given type
+    fn check_next(&self, token_type: TokenType) -> bool {
+        self.check_ahead(1, token_type)
+    }
+
+    /// Returns true if the token the given distance past
+    /// the current one is of the given type
+    fn check_ahead(&self, distance: usize, token_type: TokenType) -> bool {
+        match self.tokens.get(self.current + distance) {
+            Some(token) => token.kind() == token_type,
+            None => false,
+        }
+    }
+
     /// Returns true if at the last token
     fn is_at_end(&self) -> bool {
         self.peek().kind() == TokenType::EOF
@@ -86,17 +140,92 @@ impl Parser {
     fn previous(&self) -> Token {
         self.tokens[self.current - 1].clone()
     }
+
+    /// Returns a fresh id for the next node built
+    fn node_id(&mut self) -> NodeId {
+        NodeId::fresh()
+    }
+
+    /// Takes the trivia of every uncollected token before the given index
+    fn collect_trivia(&mut self, end: usize) -> Vec<Trivia> {
+        let end = end.min(self.tokens.len());
+        let mut trivia = Vec::new();
+        while self.collected < end {
+            trivia.extend(self.tokens[self.collected].trivia().clone());
+            self.collected += 1;
+        }
+        trivia
+    }
+
+    /// Records the trivia found before the token that ends a statement list
+    fn collect_list_end(&mut self) {
+        let leading = self.collect_trivia(self.current + 1);
+        self.statement_trivia.push(StatementTrivia {
+            leading,
+            ..StatementTrivia::default()
+        });
+    }
+
+    /// Records the trivia found before the statement about to be parsed,
+    /// returning the slot its other trivia goes in and its first token
+    fn open_trivia(&mut self) -> (usize, usize) {
+        let leading = self.collect_trivia(self.current + 1);
+        self.statement_trivia.push(StatementTrivia {
+            leading,
+            ..StatementTrivia::default()
+        });
+        (self.statement_trivia.len() - 1, self.current)
+    }
+
+    /// Records the trivia found inside the statement just parsed, along
+    /// with its tokens if the trivia has a comment
+    fn close_trivia(&mut self, slot: usize, start: usize) {
+        let trailing = self.collect_trivia(self.current);
+        if trailing
+            .iter()
+            .any(|item| !matches!(item, Trivia::BlankLine))
+        {
+            self.statement_trivia[slot].tokens = self.tokens[start..self.current].to_vec();
+            self.statement_trivia[slot].nested = self.statement_trivia.len() - slot - 1;
+        }
+        self.statement_trivia[slot].trailing = trailing;
+    }
 }
 
 /// Private methods for handling statements
 impl Parser {
     /// Parses a series of statements when called repeatedly
+    ///
+    /// Records the trivia around the statement for tools that rewrite the source
     fn declaration(&mut self) -> Result<Stmt, Error> {
-        if self.match_token(vec![TokenType::Fun]) {
+        let (slot, start) = self.open_trivia();
+
+        let stmt = self.bare_declaration();
+
+        self.close_trivia(slot, start);
+        stmt
+    }
+
+    /// Parses a single declaration without recording its trivia
+    fn bare_declaration(&mut self) -> Result<Stmt, Error> {
+        if self.match_token(vec![TokenType::Class]) {
+            return self.class_declaration();
+        }
+        if self.match_token(vec![TokenType::Enum]) {
+            return self.enum_declaration();
+        }
+        if self.match_token(vec![TokenType::Trait]) {
+            return self.trait_declaration();
+        }
+        // `fun (` starts an anonymous function in an expression statement
+        let is_named = self.check_next(TokenType::Identifier)
+            || (self.check_next(TokenType::Star) && self.check_ahead(2, TokenType::Identifier));
+        if self.check(TokenType::Fun) && is_named {
+            self.advance();
             return self.function(CallableKind::Function);
         }
 
-        if self.match_token(vec![TokenType::Var]) {
+        if self.match_token(vec![TokenType::Var, TokenType::Const]) {
             match self.var_declaration() {
                 Ok(v) => return Ok(v),
                 Err(err) => {
@@ -114,13 +243,16 @@ impl Parser {
                 // skip further tokens in this statement and
                 // consume the next tokens
                 self.synchronize();
-                return Err(err);
+                Err(err)
             }
         }
     }
 
     /// Parses a single statement
     fn statement(&mut self) -> Result<Stmt, Error> {
+        if self.check(TokenType::Identifier) && self.check_next(TokenType::Colon) {
+            return self.labeled_statement();
+        }
         if self.match_token(vec![TokenType::Print]) {
             return self.print_statement();
         }
@@ -134,10 +266,28 @@ impl Parser {
             return self.return_statement();
         }
         if self.match_token(vec![TokenType::While]) {
-            return self.while_statement();
+            return self.while_statement(None);
         }
         if self.match_token(vec![TokenType::For]) {
-            return self.for_statement();
+            return self.for_statement(None);
+        }
+        if self.match_token(vec![TokenType::Break]) {
+            return self.break_statement();
+        }
+        if self.match_token(vec![TokenType::Continue]) {
+            return self.continue_statement();
+        }
+        if self.match_token(vec![TokenType::Throw]) {
+            return self.throw_statement();
+        }
+        if self.match_token(vec![TokenType::Yield]) {
+            return self.yield_statement();
+        }
+        if self.match_token(vec![TokenType::Try]) {
+            return self.try_statement();
+        }
+        if self.match_token(vec![TokenType::Match]) {
+            return self.match_statement();
         }
         self.expression_statement()
     }
@@ -146,7 +296,7 @@ impl Parser {
     fn print_statement(&mut self) -> Result<Stmt, Error> {
         let value = self.expression()?;
         self.consume(TokenType::Semicolon, "Expected ';' after value.")?;
-        Ok(Stmt::Print(Print::new(value)))
+        Ok(Stmt::Print(Print::new(self.node_id(), value)))
     }
 
     /// Parses the return statement
@@ -157,32 +307,379 @@ impl Parser {
             value = Some(self.expression()?);
         }
         self.consume(TokenType::Semicolon, "Expected ';' after return value")?;
-        Ok(Stmt::Return(Return::new(keyword, value)))
+        Ok(Stmt::Return(Return::new(self.node_id(), keyword, value)))
+    }
+
+    /// Parses the break statement
+    fn break_statement(&mut self) -> Result<Stmt, Error> {
+        let keyword = self.previous();
+        let label = self.loop_label()?;
+        self.consume(TokenType::Semicolon, "Expected ';' after 'break'.")?;
+        Ok(Stmt::Break(Break::new(self.node_id(), keyword, label)))
+    }
+
+    /// Parses the continue statement
+    fn continue_statement(&mut self) -> Result<Stmt, Error> {
+        let keyword = self.previous();
+        let label = self.loop_label()?;
+        self.consume(TokenType::Semicolon, "Expected ';' after 'continue'.")?;
+        Ok(Stmt::Continue(Continue::new(
+            self.node_id(),
+            keyword,
+            label,
+        )))
+    }
+
+    /// Parses the optional label naming the loop a break
+    /// or continue statement applies to
+    fn loop_label(&mut self) -> Result<Option<Token>, Error> {
+        if self.check(TokenType::Identifier) {
+            return Ok(Some(self.advance()));
+        }
+        Ok(None)
+    }
+
+    /// Parses a loop preceded by a label, like `outer: while (...)`
+    fn labeled_statement(&mut self) -> Result<Stmt, Error> {
+        let label = self.advance();
+        self.consume(TokenType::Colon, "Expected ':' after label.")?;
+        if self.match_token(vec![TokenType::While]) {
+            return self.while_statement(Some(label));
+        }
+        if self.match_token(vec![TokenType::For]) {
+            return self.for_statement(Some(label));
+        }
+        Err(Error::report_parse(
+            self.peek(),
+            "Expected a loop after label.",
+        ))
     }
 
     /// Parses a block of statements
     fn block_statement(&mut self) -> Result<Stmt, Error> {
+        let statements = self.block()?;
+        Ok(Stmt::Block(Block::new(self.node_id(), statements)))
+    }
+
+    /// Parses the statements of a block up to its closing brace
+    fn block(&mut self) -> Result<Vec<Stmt>, Error> {
         let mut statements = Vec::new();
         while !self.check(TokenType::RightBrace) && !self.is_at_end() {
             statements.push(self.declaration()?);
         }
+        self.collect_list_end();
         self.consume(TokenType::RightBrace, "Expected '}' after block.")?;
-        Ok(Stmt::Block(Block::new(statements)))
+        Ok(statements)
+    }
+
+    /// Parses the throw statement
+    fn throw_statement(&mut self) -> Result<Stmt, Error> {
+        let keyword = self.previous();
+        let value = self.expression()?;
+        self.consume(TokenType::Semicolon, "Expected ';' after thrown value.")?;
+        Ok(Stmt::Throw(Throw::new(self.node_id(), keyword, value)))
+    }
+
+    /// Parses the yield statement
+    fn yield_statement(&mut self) -> Result<Stmt, Error> {
+        let keyword = self.previous();
+        let value = self.expression()?;
+        self.consume(TokenType::Semicolon, "Expected ';' after yielded value.")?;
+        Ok(Stmt::Yield(Yield::new(self.node_id(), keyword, value)))
+    }
+
+    /// Parses a try statement with its catch clause and finally block
+    fn try_statement(&mut self) -> Result<Stmt, Error> {
+        self.consume(TokenType::LeftBrace, "Expected '{' after 'try'.")?;
+        let body = self.block()?;
+
+        let mut catch = None;
+        if self.match_token(vec![TokenType::Catch]) {
+            self.consume(TokenType::LeftParen, "Expected '(' after 'catch'.")?;
+            let name = self.consume(TokenType::Identifier, "Expected name of caught value.")?;
+            self.consume(TokenType::RightParen, "Expected ')' after caught value.")?;
+            self.consume(TokenType::LeftBrace, "Expected '{' before catch body.")?;
+            catch = Some(CatchClause::new(name, self.block()?));
+        }
+
+        let mut finally = None;
+        if self.match_token(vec![TokenType::Finally]) {
+            self.consume(TokenType::LeftBrace, "Expected '{' after 'finally'.")?;
+            finally = Some(self.block()?);
+        }
+
+        if catch.is_none() && finally.is_none() {
+            return Err(Error::report_parse(
+                self.peek(),
+                "Expected 'catch' or 'finally' after try block.",
+            ));
+        }
+
+        Ok(Stmt::Try(Try::new(self.node_id(), body, catch, finally)))
+    }
+
+    /// Parses a match statement and its arms
+    ///
+    /// The arms are recorded like the statements of a statement list,
+    /// so the trivia around them stays with them
+    fn match_statement(&mut self) -> Result<Stmt, Error> {
+        let keyword = self.previous();
+        self.consume(TokenType::LeftParen, "Expected '(' after 'match'.")?;
+        let subject = self.expression()?;
+        self.consume(TokenType::RightParen, "Expected ')' after match subject.")?;
+        self.consume(TokenType::LeftBrace, "Expected '{' before match arms.")?;
+
+        let mut arms = Vec::new();
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            let (slot, start) = self.open_trivia();
+
+            let pattern = self.pattern()?;
+            let mut guard = None;
+            if self.match_token(vec![TokenType::If]) {
+                guard = Some(self.guard()?);
+            }
+            self.consume(TokenType::Arrow, "Expected '=>' after match pattern.")?;
+            let body = self.statement()?;
+            // arms may be separated by commas
+            self.match_token(vec![TokenType::Comma]);
+            arms.push(MatchArm::new(pattern, guard, body));
+
+            self.close_trivia(slot, start);
+        }
+        self.collect_list_end();
+        self.consume(TokenType::RightBrace, "Expected '}' after match arms.")?;
+
+        Ok(Stmt::Match(Match::new(
+            self.node_id(),
+            keyword,
+            subject,
+            arms,
+        )))
+    }
+
+    /// Parses the guard of a match arm
+    ///
+    /// A guard wrapped in parentheses is followed by the `=>` of the arm,
+    /// so it has to be told apart from the start of an arrow function
+    fn guard(&mut self) -> Result<Expr, Error> {
+        if self.check(TokenType::LeftParen) && self.is_arrow_function() {
+            self.advance();
+            let expr = self.expression()?;
+            self.consume(TokenType::RightParen, "Expected ')' after guard.")?;
+            return Ok(Expr::Grouping(Grouping::new(self.node_id(), expr)));
+        }
+        self.expression()
+    }
+
+    /// Parses a pattern made of alternatives separated by `|`
+    fn pattern(&mut self) -> Result<Pattern, Error> {
+        let first = self.single_pattern()?;
+        if !self.check(TokenType::Pipe) {
+            return Ok(first);
+        }
+
+        let mut alternatives = vec![first];
+        while self.match_token(vec![TokenType::Pipe]) {
+            let pipe = self.previous();
+            let alternative = self.single_pattern()?;
+            // each alternative would have to bind the same names
+            // for the arm to know what it can use
+            if !alternative.bindings().is_empty() || !alternatives[0].bindings().is_empty() {
+                return Err(Error::report_parse(
+                    pipe,
+                    "Alternative patterns cannot bind names.",
+                ));
+            }
+            alternatives.push(alternative);
+        }
+        Ok(Pattern::Alternative(alternatives))
+    }
+
+    /// Parses a pattern that is not an alternative at its top level
+    fn single_pattern(&mut self) -> Result<Pattern, Error> {
+        if self.match_token(vec![
+            TokenType::False,
+            TokenType::True,
+            TokenType::Nil,
+            TokenType::Integer,
+            TokenType::Float,
+            TokenType::String,
+        ]) {
+            let token = self.previous();
+            let value = match token.kind() {
+                TokenType::False => TokenLiteral::Boolean(false),
+                TokenType::True => TokenLiteral::Boolean(true),
+                _ => token.literal(),
+            };
+            return Ok(Pattern::Literal { token, value });
+        }
+        if self.match_token(vec![TokenType::Minus]) {
+            let minus = self.previous();
+            if !self.match_token(vec![TokenType::Integer, TokenType::Float]) {
+                return Err(Error::report_parse(
+                    self.peek(),
+                    "Expected a number after '-' in pattern.",
+                ));
+            }
+            let number = self.previous();
+            let value = match number.literal() {
+                TokenLiteral::Integer(value) => TokenLiteral::Integer(-value),
+                TokenLiteral::Float(value) => TokenLiteral::Float(-value),
+                literal => literal,
+            };
+            let lexeme = format!("-{}", number.lexeme());
+            let token = Token::new(number.kind(), &lexeme, value.clone(), minus.line());
+            return Ok(Pattern::Literal { token, value });
+        }
+        if self.match_token(vec![TokenType::Identifier]) {
+            let name = self.previous();
+            if self.check(TokenType::Dot) || self.check(TokenType::LeftParen) {
+                return self.variant_pattern(name);
+            }
+            return match name.lexeme() == "_" {
+                true => Ok(Pattern::Wildcard(name)),
+                false => Ok(Pattern::Binding(name)),
+            };
+        }
+        if self.match_token(vec![TokenType::LeftBracket]) {
+            return self.list_pattern();
+        }
+        if self.match_token(vec![TokenType::LeftBrace]) {
+            return self.map_pattern();
+        }
+        Err(Error::report_parse(self.peek(), "Expected a pattern."))
+    }
+
+    /// Parses the elements of a list pattern after its opening bracket
+    fn list_pattern(&mut self) -> Result<Pattern, Error> {
+        let bracket = self.previous();
+        let mut elements = Vec::new();
+        let mut rest = None;
+        if !self.check(TokenType::RightBracket) {
+            loop {
+                if self.match_token(vec![TokenType::Ellipsis]) {
+                    rest = Some(self.consume(
+                        TokenType::Identifier,
+                        "Expected a name after '...' in pattern.",
+                    )?);
+                    if !self.check(TokenType::RightBracket) {
+                        return Err(Error::report_parse(
+                            self.peek(),
+                            "Rest pattern must be the last element.",
+                        ));
+                    }
+                    break;
+                }
+                elements.push(self.pattern()?);
+                if !self.match_token(vec![TokenType::Comma]) {
+                    break;
+                }
+            }
+        }
+        self.consume(TokenType::RightBracket, "Expected ']' after list pattern.")?;
+        Ok(Pattern::List {
+            bracket,
+            elements,
+            rest,
+        })
+    }
+
+    /// Parses a variant pattern after its first name, which is the name
+    /// of the enum when a `.` and the name of the variant follow
+    fn variant_pattern(&mut self, first: Token) -> Result<Pattern, Error> {
+        let (enum_name, name) = match self.match_token(vec![TokenType::Dot]) {
+            true => {
+                let name = self.consume(
+                    TokenType::Identifier,
+                    "Expected a variant name after '.' in pattern.",
+                )?;
+                (Some(first), name)
+            }
+            false => (None, first),
+        };
+
+        let mut fields = None;
+        if self.match_token(vec![TokenType::LeftParen]) {
+            let mut patterns = Vec::new();
+            if !self.check(TokenType::RightParen) {
+                loop {
+                    patterns.push(self.pattern()?);
+                    if !self.match_token(vec![TokenType::Comma]) {
+                        break;
+                    }
+                }
+            }
+            self.consume(
+                TokenType::RightParen,
+                "Expected ')' after variant fields in pattern.",
+            )?;
+            fields = Some(patterns);
+        }
+        Ok(Pattern::Variant {
+            enum_name,
+            name,
+            fields,
+        })
+    }
+
+    /// Parses the entries of a map pattern after its opening brace,
+    /// where a bare name is short for the name as a key bound to itself
+    fn map_pattern(&mut self) -> Result<Pattern, Error> {
+        let brace = self.previous();
+        let mut entries = Vec::new();
+        if !self.check(TokenType::RightBrace) {
+            loop {
+                if !self.match_token(vec![
+                    TokenType::Identifier,
+                    TokenType::String,
+                    TokenType::Integer,
+                ]) {
+                    return Err(Error::report_parse(
+                        self.peek(),
+                        "Expected a name, string or integer as map pattern key.",
+                    ));
+                }
+                let key = self.previous();
+                let pattern = match self.match_token(vec![TokenType::Colon]) {
+                    true => self.pattern()?,
+                    false if key.kind() == TokenType::Identifier => Pattern::Binding(key.clone()),
+                    false => {
+                        return Err(Error::report_parse(
+                            self.peek(),
+                            "Expected ':' after map pattern key.",
+                        ))
+                    }
+                };
+                entries.push((key, pattern));
+                if !self.match_token(vec![TokenType::Comma]) {
+                    break;
+                }
+            }
+        }
+        self.consume(TokenType::RightBrace, "Expected '}' after map pattern.")?;
+        Ok(Pattern::Map { brace, entries })
     }
 
     /// Parses a while statement
-    fn while_statement(&mut self) -> Result<Stmt, Error> {
+    fn while_statement(&mut self, label: Option<Token>) -> Result<Stmt, Error> {
         self.consume(TokenType::LeftParen, "Expected '(' after 'while'.")?;
         let condition = self.expression()?;
         self.consume(TokenType::RightParen, "Expected ')' after while condition.")?;
         let body = self.statement()?;
 
-        Ok(Stmt::While(While::new(condition, Box::new(body))))
+        Ok(Stmt::While(
+            While::new(self.node_id(), condition, Box::new(body)).labeled(label),
+        ))
     }
 
     /// Parses a for statement
-    fn for_statement(&mut self) -> Result<Stmt, Error> {
+    ///
+    /// The label, if any, names the while loop the statement desugars to
+    fn for_statement(&mut self, label: Option<Token>) -> Result<Stmt, Error> {
         self.consume(TokenType::LeftParen, "Expected '(' after 'for'")?;
+        if self.check(TokenType::Var) && self.check_ahead(2, TokenType::In) {
+            return self.for_in_statement(label);
+        }
 
         // get initializer
         let initializer;
@@ -207,30 +704,49 @@ impl Parser {
         }
         self.consume(TokenType::RightParen, "Expect ')' after 'for' clauses.")?;
 
-        let mut body = self.statement()?;
-        if let Some(increment) = increment {
-            body = Stmt::Block(Block::new(vec![
-                body,
-                Stmt::Expression(Expression::new(increment)),
-            ]));
-        }
+        let body = self.statement()?;
 
         if condition.is_none() {
-            condition = Some(Expr::Literal(Literal::new(Value::new(
-                TokenLiteral::Boolean(true),
-            ))));
+            condition = Some(Expr::Literal(Literal::new(
+                self.node_id(),
+                Value::new(TokenLiteral::Boolean(true)),
+            )));
         }
-        body = Stmt::While(While::new(condition.unwrap(), Box::new(body)));
 
+        let mut statements = Vec::new();
         if let Some(initializer) = initializer {
-            body = Stmt::Block(Block::new(vec![initializer, body]))
+            statements.push(initializer);
         }
+        statements.push(Stmt::While(
+            While::with_increment(
+                self.node_id(),
+                condition.unwrap(),
+                Box::new(body),
+                increment,
+            )
+            .labeled(label),
+        ));
+
+        Ok(Stmt::Block(Block::for_loop(self.node_id(), statements)))
+    }
 
-        Ok(body)
+    /// Parses the rest of a for-in statement, like `for (var x in xs) body`
+    fn for_in_statement(&mut self, label: Option<Token>) -> Result<Stmt, Error> {
+        self.consume(TokenType::Var, "Expected 'var' in for-in loop.")?;
+        let name = self.consume(TokenType::Identifier, "Expected variable name.")?;
+        let keyword = self.consume(TokenType::In, "Expected 'in' after loop variable.")?;
+        let iterable = self.expression()?;
+        self.consume(TokenType::RightParen, "Expect ')' after 'for' clauses.")?;
+        let body = self.statement()?;
+
+        Ok(Stmt::ForIn(
+            ForIn::new(self.node_id(), name, keyword, iterable, Box::new(body)).labeled(label),
+        ))
     }
 
     /// Parses an if statement
     fn if_statement(&mut self) -> Result<Stmt, Error> {
+        let keyword = self.previous();
         self.consume(TokenType::LeftParen, "Expected '(' after 'if'.")?;
         let condition = self.expression()?;
         self.consume(TokenType::RightParen, "Expected ')' after if condition.")?;
@@ -242,25 +758,227 @@ impl Parser {
             else_branch = Some(self.statement()?);
         }
 
-        Ok(Stmt::If(If::new(condition, then_branch, else_branch)))
+        Ok(Stmt::If(If::new(
+            self.node_id(),
+            keyword,
+            condition,
+            then_branch,
+            else_branch,
+        )))
     }
 
     /// Parses an expression statement
     fn expression_statement(&mut self) -> Result<Stmt, Error> {
         let expr = self.expression()?;
         self.consume(TokenType::Semicolon, "Expected ';' after expression.")?;
-        Ok(Stmt::Expression(Expression::new(expr)))
+        Ok(Stmt::Expression(Expression::new(self.node_id(), expr)))
+    }
+
+    /// Parses a class declaration with its superclass, fields and methods
+    ///
+    /// The fields and methods are recorded like the statements of a
+    /// statement list, so the trivia around them stays with them
+    fn class_declaration(&mut self) -> Result<Stmt, Error> {
+        let name = self.consume(TokenType::Identifier, "Expected class name.")?;
+
+        let mut superclass = None;
+        if self.match_token(vec![TokenType::Less]) {
+            let superclass_name =
+                self.consume(TokenType::Identifier, "Expected superclass name.")?;
+            superclass = Some(Variable::new(self.node_id(), superclass_name));
+        }
+
+        let mut traits = Vec::new();
+        if self.match_token(vec![TokenType::With]) {
+            loop {
+                let trait_name = self.consume(TokenType::Identifier, "Expected trait name.")?;
+                traits.push(Variable::new(self.node_id(), trait_name));
+                if !self.match_token(vec![TokenType::Comma]) {
+                    break;
+                }
+            }
+        }
+
+        self.consume(TokenType::LeftBrace, "Expected '{' before class body.")?;
+        let mut fields = Vec::new();
+        let mut methods = Vec::new();
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            let (slot, start) = self.open_trivia();
+            if self.is_class_field() {
+                let keyword = self.advance();
+                if !methods.is_empty() {
+                    return Err(Error::report_parse(
+                        keyword,
+                        "Class fields must come before methods.",
+                    ));
+                }
+                fields.push(self.class_field()?);
+            } else {
+                let is_class_method = self.match_token(vec![TokenType::Class]);
+                match self.function(CallableKind::Method)? {
+                    Stmt::Function(method) if is_class_method => {
+                        methods.push(method.class_method())
+                    }
+                    Stmt::Function(method) => methods.push(method),
+                    _ => panic!("This should not happen, a method should parse as a function"),
+                }
+            }
+            self.close_trivia(slot, start);
+        }
+        self.collect_list_end();
+        self.consume(TokenType::RightBrace, "Expected '}' after class body.")?;
+
+        Ok(Stmt::Class(
+            Class::new(self.node_id(), name, superclass, methods)
+                .with_traits(traits)
+                .with_fields(fields),
+        ))
+    }
+
+    /// Consumes the name of a property or of a class member, which may be private
+    ///
+    /// `yield` only starts a statement, so it can name a member, like `Fiber.yield`
+    fn member_name(&mut self, message: &str) -> Result<Token, Error> {
+        if self.match_token(vec![TokenType::PrivateIdentifier]) {
+            return Ok(self.previous());
+        }
+        if self.match_token(vec![TokenType::Yield]) {
+            let keyword = self.previous();
+            return Ok(Token::new(
+                TokenType::Identifier,
+                &keyword.lexeme(),
+                TokenLiteral::Nil,
+                keyword.line(),
+            ));
+        }
+        self.consume(TokenType::Identifier, message)
+    }
+
+    /// Parses a trait declaration, whose body holds only methods
+    fn trait_declaration(&mut self) -> Result<Stmt, Error> {
+        let name = self.consume(TokenType::Identifier, "Expected trait name.")?;
+        self.consume(TokenType::LeftBrace, "Expected '{' before trait body.")?;
+        let mut methods = Vec::new();
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            let (slot, start) = self.open_trivia();
+            match self.function(CallableKind::Method)? {
+                Stmt::Function(method) => methods.push(method),
+                _ => panic!("This should not happen, a method should parse as a function"),
+            }
+            self.close_trivia(slot, start);
+        }
+        self.collect_list_end();
+        self.consume(TokenType::RightBrace, "Expected '}' after trait body.")?;
+
+        Ok(Stmt::Trait(Trait::new(self.node_id(), name, methods)))
+    }
+
+    /// Returns true if the class body continues with a field of the class,
+    /// like `class count = 0;`, rather than a class method
+    fn is_class_field(&self) -> bool {
+        self.check(TokenType::Class)
+            && (self.check_next(TokenType::Identifier)
+                || self.check_next(TokenType::PrivateIdentifier))
+            && (self.check_ahead(2, TokenType::Equal) || self.check_ahead(2, TokenType::Semicolon))
+    }
+
+    /// Parses a field of a class after its `class` keyword
+    fn class_field(&mut self) -> Result<Var, Error> {
+        let name = self.member_name("Expected field name.")?;
+        let initializer = match self.match_token(vec![TokenType::Equal]) {
+            true => Some(self.expression()?),
+            false => None,
+        };
+        self.consume(
+            TokenType::Semicolon,
+            "Expected ';' after class field declaration.",
+        )?;
+        Ok(Var::new(self.node_id(), name, initializer))
+    }
+
+    /// Parses an enum declaration with its variants, which are
+    /// separated by commas and may list the names of their fields
+    fn enum_declaration(&mut self) -> Result<Stmt, Error> {
+        let name = self.consume(TokenType::Identifier, "Expected enum name.")?;
+        self.consume(TokenType::LeftBrace, "Expected '{' before enum body.")?;
+
+        let mut variants = Vec::new();
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            let variant = self.consume(TokenType::Identifier, "Expected variant name.")?;
+            let mut fields = Vec::new();
+            if self.match_token(vec![TokenType::LeftParen]) {
+                if !self.check(TokenType::RightParen) {
+                    loop {
+                        fields.push(self.consume(TokenType::Identifier, "Expected field name.")?);
+                        if !self.match_token(vec![TokenType::Comma]) {
+                            break;
+                        }
+                    }
+                }
+                self.consume(TokenType::RightParen, "Expected ')' after variant fields.")?;
+            }
+            variants.push(EnumVariant::new(variant, fields));
+            // the last variant may be followed by a comma too
+            if !self.match_token(vec![TokenType::Comma]) {
+                break;
+            }
+        }
+        self.consume(TokenType::RightBrace, "Expected '}' after enum body.")?;
+
+        Ok(Stmt::Enum(Enum::new(self.node_id(), name, variants)))
     }
 
     /// Parses a function declaration
     fn function(&mut self, kind: CallableKind) -> Result<Stmt, Error> {
-        let name = self.consume(TokenType::Identifier, &format!("Expected {:?} name.", kind))?;
+        // a star before the name makes a generator, like `fun* lines()` or `*items()`
+        let is_generator = self.match_token(vec![TokenType::Star]);
+        let message = format!("Expected {:?} name.", kind);
+        let name = match kind {
+            CallableKind::Method => self.member_name(&message)?,
+            _ => self.consume(TokenType::Identifier, &message)?,
+        };
+
+        // a method written without parentheses is a getter
+        let is_getter = matches!(kind, CallableKind::Method)
+            && name.lexeme() != "init"
+            && self.check(TokenType::LeftBrace);
+        let params = match is_getter {
+            true => Vec::new(),
+            false => {
+                self.consume(
+                    TokenType::LeftParen,
+                    &format!("Expected '(' after {:?} name.", kind),
+                )?;
+                self.parameters()?
+            }
+        };
+
+        // start parsing function body
         self.consume(
-            TokenType::LeftParen,
-            &format!("Expected '(' after {:?} name.", kind),
+            TokenType::LeftBrace,
+            &format!("Expected '{{' before {:?} body.", kind),
         )?;
+        match self.block_statement()? {
+            Stmt::Block(body) => {
+                let mut function = Function::new(self.node_id(), name, params, body.statements());
+                if is_getter {
+                    function = function.getter();
+                }
+                if is_generator {
+                    function = function.generator();
+                }
+                Ok(Stmt::Function(function))
+            }
+            _ => panic!("This should not happen, block statement should yield a body"),
+        }
+    }
 
-        let mut params = Vec::new();
+    /// Parses the parameters of a function up to the closing parenthesis
+    ///
+    /// Parameters with a default value come after the ones without,
+    /// and a rest parameter can only be the last one.
+    fn parameters(&mut self) -> Result<Vec<Param>, Error> {
+        let mut params: Vec<Param> = Vec::new();
         if !self.check(TokenType::RightParen) {
             loop {
                 if params.len() >= MAX_FUNCTION_ARGUMENTS_SIZE {
@@ -269,35 +987,56 @@ impl Parser {
                         "Cannot have equal to or more than 255 arguements",
                     ));
                 }
-                params.push(self.consume(TokenType::Identifier, "Expected parameter name")?);
+                if self.match_token(vec![TokenType::Ellipsis]) {
+                    let name =
+                        self.consume(TokenType::Identifier, "Expected rest parameter name")?;
+                    params.push(Param::rest(name));
+                    if self.check(TokenType::Comma) {
+                        return Err(Error::report_parse(
+                            self.peek(),
+                            "Rest parameter must be the last parameter.",
+                        ));
+                    }
+                    break;
+                }
+
+                let name = self.consume(TokenType::Identifier, "Expected parameter name")?;
+                let default = match self.match_token(vec![TokenType::Equal]) {
+                    true => Some(self.assignment()?),
+                    false => None,
+                };
+                let follows_default = params.iter().any(|param| param.default().is_some());
+                if default.is_none() && follows_default {
+                    return Err(Error::report_parse(
+                        name,
+                        "Parameters without a default cannot follow one with a default.",
+                    ));
+                }
+                params.push(Param::new(name, default));
                 if !self.match_token(vec![TokenType::Comma]) {
                     break;
                 }
             }
         }
         self.consume(TokenType::RightParen, "Expected ')' after parameters")?;
-
-        // start parsing function body
-        self.consume(
-            TokenType::LeftBrace,
-            &format!("Expected '{{' before {:?} body.", kind),
-        )?;
-        match self.block_statement()? {
-            Stmt::Block(body) => Ok(Stmt::Function(Function::new(
-                name,
-                params,
-                body.statements(),
-            ))),
-            _ => panic!("This should not happen, block statement should yield a body"),
-        }
+        Ok(params)
     }
 
-    /// Parses a variable declaration
+    /// Parses a variable declaration after its `var` or `const` keyword
+    ///
+    /// Constants must be given their value when they are declared
     fn var_declaration(&mut self) -> Result<Stmt, Error> {
+        let is_const = self.previous().kind() == TokenType::Const;
+        if self.match_token(vec![TokenType::LeftBracket, TokenType::LeftBrace]) {
+            return self.var_pattern_declaration(is_const);
+        }
         let name = self.consume(TokenType::Identifier, "Expected variable name.")?;
 
         let mut initializer = None;
-        if self.match_token(vec![TokenType::Equal]) {
+        if is_const {
+            self.consume(TokenType::Equal, "Expected '=' after constant name.")?;
+            initializer = Some(self.expression()?);
+        } else if self.match_token(vec![TokenType::Equal]) {
             initializer = Some(self.expression()?);
         }
 
@@ -305,7 +1044,35 @@ impl Parser {
             TokenType::Semicolon,
             "Expected ';' after variable declaration",
         )?;
-        Ok(Stmt::Var(Var::new(name, initializer)))
+        let var = Var::new(self.node_id(), name, initializer);
+        match is_const {
+            true => Ok(Stmt::Var(var.constant())),
+            false => Ok(Stmt::Var(var)),
+        }
+    }
+
+    /// Parses a declaration that destructures its initializer,
+    /// like `var [a, b] = pair;`, after the opening bracket or brace
+    fn var_pattern_declaration(&mut self, is_const: bool) -> Result<Stmt, Error> {
+        let pattern = match self.previous().kind() {
+            TokenType::LeftBracket => self.list_pattern()?,
+            _ => self.map_pattern()?,
+        };
+        self.consume(
+            TokenType::Equal,
+            "Expected '=' after destructuring pattern.",
+        )?;
+        let initializer = self.expression()?;
+        self.consume(
+            TokenType::Semicolon,
+            "Expected ';' after variable declaration",
+        )?;
+
+        let var = VarPattern::new(self.node_id(), pattern, initializer);
+        match is_const {
+            true => Ok(Stmt::VarPattern(var.constant())),
+            false => Ok(Stmt::VarPattern(var)),
+        }
     }
 }
 
@@ -320,20 +1087,122 @@ impl Parser {
     /// or an expression.
     /// Returns an expression of the specific type
     fn assignment(&mut self) -> Result<Expr, Error> {
-        let expr = self.or()?;
+        if self.check(TokenType::LeftBracket)
+            && self.closes_before(
+                TokenType::LeftBracket,
+                TokenType::RightBracket,
+                TokenType::Equal,
+            )
+        {
+            return self.pattern_assignment();
+        }
+        let expr = self.ternary()?;
 
-        if self.match_token(vec![TokenType::Equal]) {
+        if self.match_token(vec![
+            TokenType::Equal,
+            TokenType::PlusEqual,
+            TokenType::MinusEqual,
+            TokenType::StarEqual,
+            TokenType::SlashEqual,
+            TokenType::PercentEqual,
+        ]) {
             let equals = self.previous();
             let value = self.assignment()?;
+            let operator = compound_operator(&equals);
 
             if let Expr::Variable(v) = expr {
-                return Ok(Expr::Assign(Assign::new(v.name().clone(), value)));
+                let assign = Assign::new(self.node_id(), v.name().clone(), value);
+                return Ok(Expr::Assign(match operator {
+                    Some(operator) => assign.compound(operator),
+                    None => assign,
+                }));
+            }
+            if let Expr::Get(get) = expr {
+                if get.is_optional() {
+                    return Err(Error::report_parse(
+                        equals,
+                        "Cannot assign through an optional property access.",
+                    ));
+                }
+                let set = Set::new(self.node_id(), get.object(), get.name().clone(), value);
+                return Ok(Expr::Set(match operator {
+                    Some(operator) => set.compound(operator),
+                    None => set,
+                }));
+            }
+            if let Expr::Index(index) = expr {
+                let set_index = SetIndex::new(
+                    self.node_id(),
+                    index.object(),
+                    index.bracket().clone(),
+                    index.index(),
+                    value,
+                );
+                return Ok(Expr::SetIndex(match operator {
+                    Some(operator) => set_index.compound(operator),
+                    None => set_index,
+                }));
             }
 
             return Err(Error::report_parse(equals, "Invalid assignment target."));
         }
 
-        return Ok(expr);
+        Ok(expr)
+    }
+
+    /// Parses an assignment that destructures its value, like `[a, b] = [b, a]`
+    fn pattern_assignment(&mut self) -> Result<Expr, Error> {
+        self.advance();
+        let pattern = self.list_pattern()?;
+        self.consume(
+            TokenType::Equal,
+            "Expected '=' after destructuring pattern.",
+        )?;
+        let value = self.assignment()?;
+        let targets = pattern.bindings().iter().map(|_| self.node_id()).collect();
+        Ok(Expr::AssignPattern(AssignPattern::new(
+            self.node_id(),
+            pattern,
+            value,
+            targets,
+        )))
+    }
+
+    /// Parses a conditional expression, which groups to the right
+    /// so that `a ? b : c ? d : e` reads as `a ? b : (c ? d : e)`
+    fn ternary(&mut self) -> Result<Expr, Error> {
+        let condition = self.nil_coalesce()?;
+        if !self.match_token(vec![TokenType::Question]) {
+            return Ok(condition);
+        }
+        let question = self.previous();
+        let then_branch = self.assignment()?;
+        self.consume(
+            TokenType::Colon,
+            "Expected ':' after then branch of conditional expression.",
+        )?;
+        let else_branch = self.ternary()?;
+        Ok(Expr::Ternary(Ternary::new(
+            self.node_id(),
+            condition,
+            question,
+            then_branch,
+            else_branch,
+        )))
+    }
+
+    /// Parses a series of expressions that fall back to
+    /// their right operand when the left one is nil
+    fn nil_coalesce(&mut self) -> Result<Expr, Error> {
+        let mut expr = self.or()?;
+
+        while self.match_token(vec![TokenType::QuestionQuestion]) {
+            let operator = self.previous();
+            let right = self.or()?;
+            expr = Expr::Logical(Logical::new(self.node_id(), expr, operator, right))
+        }
+
+        Ok(expr)
     }
 
     /// Parses a series of expressions evaluating OR
@@ -343,7 +1212,7 @@ impl Parser {
         while self.match_token(vec![TokenType::Or]) {
             let operator = self.previous();
             let right = self.and()?;
-            expr = Expr::Logical(Logical::new(expr, operator, right))
+            expr = Expr::Logical(Logical::new(self.node_id(), expr, operator, right))
         }
 
         Ok(expr)
@@ -353,10 +1222,10 @@ impl Parser {
     fn and(&mut self) -> Result<Expr, Error> {
         let mut expr = self.equality()?;
 
-        while self.match_token(vec![TokenType::Or]) {
+        while self.match_token(vec![TokenType::And]) {
             let operator = self.previous();
             let right = self.equality()?;
-            expr = Expr::Logical(Logical::new(expr, operator, right))
+            expr = Expr::Logical(Logical::new(self.node_id(), expr, operator, right))
         }
 
         Ok(expr)
@@ -368,23 +1237,70 @@ impl Parser {
         while self.match_token(vec![TokenType::BangEqual, TokenType::EqualEqual]) {
             let operator = self.previous();
             let right = self.comparison()?;
-            expr = Expr::Binary(Binary::new(expr, operator, right));
+            expr = Expr::Binary(Binary::new(self.node_id(), expr, operator, right));
         }
         Ok(expr)
     }
 
     /// Returns the comparison expression
     fn comparison(&mut self) -> Result<Expr, Error> {
-        let mut expr = self.term()?;
+        let mut expr = self.bitwise_or()?;
         while self.match_token(vec![
             TokenType::Greater,
             TokenType::GreaterEqual,
             TokenType::Less,
             TokenType::LessEqual,
         ]) {
+            let operator = self.previous();
+            let right = self.bitwise_or()?;
+            expr = Expr::Binary(Binary::new(self.node_id(), expr, operator, right))
+        }
+        Ok(expr)
+    }
+
+    /// Returns a bitwise OR expression
+    ///
+    /// The bitwise operators bind tighter than comparisons,
+    /// so `a & 1 == 0` compares the result of the `&`
+    fn bitwise_or(&mut self) -> Result<Expr, Error> {
+        let mut expr = self.bitwise_xor()?;
+        while self.match_token(vec![TokenType::Pipe]) {
+            let operator = self.previous();
+            let right = self.bitwise_xor()?;
+            expr = Expr::Binary(Binary::new(self.node_id(), expr, operator, right))
+        }
+        Ok(expr)
+    }
+
+    /// Returns a bitwise XOR expression
+    fn bitwise_xor(&mut self) -> Result<Expr, Error> {
+        let mut expr = self.bitwise_and()?;
+        while self.match_token(vec![TokenType::Caret]) {
+            let operator = self.previous();
+            let right = self.bitwise_and()?;
+            expr = Expr::Binary(Binary::new(self.node_id(), expr, operator, right))
+        }
+        Ok(expr)
+    }
+
+    /// Returns a bitwise AND expression
+    fn bitwise_and(&mut self) -> Result<Expr, Error> {
+        let mut expr = self.shift()?;
+        while self.match_token(vec![TokenType::Ampersand]) {
+            let operator = self.previous();
+            let right = self.shift()?;
+            expr = Expr::Binary(Binary::new(self.node_id(), expr, operator, right))
+        }
+        Ok(expr)
+    }
+
+    /// Returns a bit shift expression
+    fn shift(&mut self) -> Result<Expr, Error> {
+        let mut expr = self.term()?;
+        while self.match_token(vec![TokenType::LessLess, TokenType::GreaterGreater]) {
             let operator = self.previous();
             let right = self.term()?;
-            expr = Expr::Binary(Binary::new(expr, operator, right))
+            expr = Expr::Binary(Binary::new(self.node_id(), expr, operator, right))
         }
         Ok(expr)
     }
@@ -395,7 +1311,7 @@ impl Parser {
         while self.match_token(vec![TokenType::Minus, TokenType::Plus]) {
             let operator = self.previous();
             let right = self.factor()?;
-            expr = Expr::Binary(Binary::new(expr, operator, right))
+            expr = Expr::Binary(Binary::new(self.node_id(), expr, operator, right))
         }
         Ok(expr)
     }
@@ -403,22 +1319,42 @@ impl Parser {
     /// Returns a factor expression
     fn factor(&mut self) -> Result<Expr, Error> {
         let mut expr = self.unary()?;
-        while self.match_token(vec![TokenType::Slash, TokenType::Star]) {
+        while self.match_token(vec![TokenType::Slash, TokenType::Star, TokenType::Percent]) {
             let operator = self.previous();
             let right = self.unary()?;
-            expr = Expr::Binary(Binary::new(expr, operator, right))
+            expr = Expr::Binary(Binary::new(self.node_id(), expr, operator, right))
         }
         Ok(expr)
     }
 
     /// Returns a unary expression
     fn unary(&mut self) -> Result<Expr, Error> {
-        if self.match_token(vec![TokenType::Bang, TokenType::Minus]) {
+        if self.match_token(vec![TokenType::Bang, TokenType::Minus, TokenType::Tilde]) {
+            let operator = self.previous();
+            let right = self.unary()?;
+            return Ok(Expr::Unary(Unary::new(self.node_id(), operator, right)));
+        }
+        self.power()
+    }
+
+    /// Returns a power expression
+    ///
+    /// Powers group to the right and bind tighter than a unary operator
+    /// on their left, so `-2 ** 2` is `-(2 ** 2)`, while the exponent
+    /// can still have one, like in `2 ** -1`.
+    fn power(&mut self) -> Result<Expr, Error> {
+        let expr = self.call()?;
+        if self.match_token(vec![TokenType::StarStar]) {
             let operator = self.previous();
             let right = self.unary()?;
-            return Ok(Expr::Unary(Unary::new(operator, right)));
+            return Ok(Expr::Binary(Binary::new(
+                self.node_id(),
+                expr,
+                operator,
+                right,
+            )));
         }
-        self.call()
+        Ok(expr)
     }
 
     // Returns the result of a call expression
@@ -427,6 +1363,16 @@ impl Parser {
         loop {
             if self.match_token(vec![TokenType::LeftParen]) {
                 expr = self.finish_call(expr)?;
+            } else if self.match_token(vec![TokenType::Dot]) {
+                let name = self.member_name("Expected property name after '.'.")?;
+                expr = Expr::Get(Get::new(self.node_id(), expr, name));
+            } else if self.match_token(vec![TokenType::QuestionDot]) {
+                let name = self.member_name("Expected property name after '?.'.")?;
+                expr = Expr::Get(Get::new(self.node_id(), expr, name).optional());
+            } else if self.match_token(vec![TokenType::LeftBracket]) {
+                let index = self.expression()?;
+                let bracket = self.consume(TokenType::RightBracket, "Expected ']' after index.")?;
+                expr = Expr::Index(Index::new(self.node_id(), expr, bracket, index));
             } else {
                 break;
             }
@@ -437,55 +1383,209 @@ impl Parser {
     /// Parse the argument list of a call
     fn finish_call(&mut self, callee: Expr) -> Result<Expr, Error> {
         let mut arguments = Vec::new();
+        let mut named_arguments = Vec::new();
         if !self.check(TokenType::RightParen) {
             loop {
-                if arguments.len() >= MAX_FUNCTION_ARGUMENTS_SIZE {
+                if arguments.len() + named_arguments.len() >= MAX_FUNCTION_ARGUMENTS_SIZE {
                     return Err(Error::report_parse(
                         self.peek(),
                         "Cannot have equal to or more than 255 arguements",
                     ));
                 }
-                arguments.push(self.expression()?);
+                // a named argument is a parameter name followed by a colon
+                if self.check(TokenType::Identifier) && self.check_next(TokenType::Colon) {
+                    let name = self.advance();
+                    self.advance();
+                    named_arguments.push((name, self.expression()?));
+                } else if !named_arguments.is_empty() {
+                    return Err(Error::report_parse(
+                        self.peek(),
+                        "Positional arguments cannot follow named arguments.",
+                    ));
+                } else {
+                    arguments.push(self.expression()?);
+                }
                 if !self.match_token(vec![TokenType::Comma]) {
                     break;
                 }
             }
         }
         let paren = self.consume(TokenType::RightParen, "Expected ')' after arguments.")?;
-        Ok(Expr::Call(Call::new(callee, paren, arguments)))
+        Ok(Expr::Call(Call::new(
+            self.node_id(),
+            callee,
+            paren,
+            arguments,
+            named_arguments,
+        )))
+    }
+
+    /// Parses an anonymous function after its `fun` keyword
+    fn lambda(&mut self) -> Result<Expr, Error> {
+        let keyword = self.previous();
+        let is_generator = self.match_token(vec![TokenType::Star]);
+        self.consume(TokenType::LeftParen, "Expected '(' after 'fun'.")?;
+        let params = self.parameters()?;
+        self.consume(TokenType::LeftBrace, "Expected '{' before function body.")?;
+        let body = self.block()?;
+        let lambda = Lambda::new(self.node_id(), keyword, params, body);
+        match is_generator {
+            true => Ok(Expr::Lambda(lambda.generator())),
+            false => Ok(Expr::Lambda(lambda)),
+        }
+    }
+
+    /// Returns true if the parenthesis at the current token
+    /// closes on a parameter list followed by `=>`
+    fn is_arrow_function(&self) -> bool {
+        self.closes_before(
+            TokenType::LeftParen,
+            TokenType::RightParen,
+            TokenType::Arrow,
+        )
+    }
+
+    /// Returns true if the bracket at the current token is
+    /// closed right before a token of the given kind
+    fn closes_before(&self, open: TokenType, close: TokenType, next: TokenType) -> bool {
+        let mut depth = 0;
+        for (i, token) in self.tokens.iter().enumerate().skip(self.current) {
+            match token.kind() {
+                kind if kind == open => depth += 1,
+                kind if kind == close => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return self.tokens.get(i + 1).map(|token| token.kind()) == Some(next);
+                    }
+                }
+                TokenType::EOF => return false,
+                _ => {}
+            }
+        }
+        false
+    }
+
+    /// Parses an arrow function, like `(a) => a * 2` or `(a) => { ... }`
+    fn arrow_function(&mut self) -> Result<Expr, Error> {
+        self.consume(TokenType::LeftParen, "Expected '(' before parameters.")?;
+        let params = self.parameters()?;
+        let arrow = self.consume(TokenType::Arrow, "Expected '=>' after parameters.")?;
+
+        let body = match self.match_token(vec![TokenType::LeftBrace]) {
+            true => self.block()?,
+            false => {
+                // an expression body returns its value
+                let value = self.assignment()?;
+                vec![Stmt::Return(Return::new(
+                    self.node_id(),
+                    arrow.clone(),
+                    Some(value),
+                ))]
+            }
+        };
+        Ok(Expr::Lambda(Lambda::new(
+            self.node_id(),
+            arrow,
+            params,
+            body,
+        )))
+    }
+
+    /// Parses the elements of a list literal, allowing a trailing comma
+    fn list(&mut self) -> Result<Expr, Error> {
+        let bracket = self.previous();
+        let mut elements = Vec::new();
+        while !self.check(TokenType::RightBracket) {
+            elements.push(self.expression()?);
+            if !self.match_token(vec![TokenType::Comma]) {
+                break;
+            }
+        }
+        self.consume(TokenType::RightBracket, "Expected ']' after list elements.")?;
+        Ok(Expr::List(List::new(self.node_id(), bracket, elements)))
+    }
+
+    /// Parses the entries of a map literal, allowing a trailing comma
+    ///
+    /// A brace at the start of a statement opens a block, so a map
+    /// literal is only parsed where an expression is expected
+    fn map(&mut self) -> Result<Expr, Error> {
+        let brace = self.previous();
+        let mut entries = Vec::new();
+        while !self.check(TokenType::RightBrace) {
+            let key = self.expression()?;
+            self.consume(TokenType::Colon, "Expected ':' after map key.")?;
+            let value = self.expression()?;
+            entries.push((key, value));
+            if !self.match_token(vec![TokenType::Comma]) {
+                break;
+            }
+        }
+        self.consume(TokenType::RightBrace, "Expected '}' after map entries.")?;
+        Ok(Expr::Map(Map::new(self.node_id(), brace, entries)))
     }
 
     /// Returns a primary expression
     fn primary(&mut self) -> Result<Expr, Error> {
         if self.match_token(vec![TokenType::False]) {
-            return Ok(Expr::Literal(Literal::new(Value::new(
-                TokenLiteral::Boolean(false),
-            ))));
+            return Ok(Expr::Literal(Literal::new(
+                self.node_id(),
+                Value::new(TokenLiteral::Boolean(false)),
+            )));
         }
         if self.match_token(vec![TokenType::True]) {
-            return Ok(Expr::Literal(Literal::new(Value::new(
-                TokenLiteral::Boolean(true),
-            ))));
+            return Ok(Expr::Literal(Literal::new(
+                self.node_id(),
+                Value::new(TokenLiteral::Boolean(true)),
+            )));
         }
         if self.match_token(vec![TokenType::Nil]) {
-            return Ok(Expr::Literal(Literal::new(Value::new(TokenLiteral::Nil))));
+            return Ok(Expr::Literal(Literal::new(
+                self.node_id(),
+                Value::new(TokenLiteral::Nil),
+            )));
         }
         if self.match_token(vec![
             TokenType::Integer,
             TokenType::Float,
             TokenType::String,
         ]) {
-            return Ok(Expr::Literal(Literal::new(Value::new(
-                self.previous().literal(),
-            ))));
+            return Ok(Expr::Literal(Literal::new(
+                self.node_id(),
+                Value::new(self.previous().literal()),
+            )));
+        }
+        if self.match_token(vec![TokenType::This]) {
+            return Ok(Expr::This(This::new(self.node_id(), self.previous())));
+        }
+        if self.match_token(vec![TokenType::Super]) {
+            let keyword = self.previous();
+            self.consume(TokenType::Dot, "Expected '.' after 'super'.")?;
+            let method = self.consume(TokenType::Identifier, "Expected superclass method name.")?;
+            return Ok(Expr::Super(Super::new(self.node_id(), keyword, method)));
         }
         if self.match_token(vec![TokenType::Identifier]) {
-            return Ok(Expr::Variable(Variable::new(self.previous())));
+            return Ok(Expr::Variable(Variable::new(
+                self.node_id(),
+                self.previous(),
+            )));
+        }
+        if self.match_token(vec![TokenType::Fun]) {
+            return self.lambda();
+        }
+        if self.check(TokenType::LeftParen) && self.is_arrow_function() {
+            return self.arrow_function();
         }
         if self.match_token(vec![TokenType::LeftParen]) {
             let expr = self.expression()?;
             self.consume(TokenType::RightParen, "Expected ')' after expression")?;
-            return Ok(Expr::Grouping(Grouping::new(expr)));
+            return Ok(Expr::Grouping(Grouping::new(self.node_id(), expr)));
+        }
+        if self.match_token(vec![TokenType::LeftBracket]) {
+            return self.list();
+        }
+        if self.match_token(vec![TokenType::LeftBrace]) {
+            return self.map();
         }
         Err(Error::report_parse(self.peek(), "Expected expression."))
     }
@@ -516,14 +1616,23 @@ impl Parser {
             // match only tokens that can start a valid statement
             // discarding the rest
             match self.peek().kind() {
-                TokenType::Class
+                TokenType::Break
+                | TokenType::Class
+                | TokenType::Const
+                | TokenType::Continue
+                | TokenType::Enum
                 | TokenType::Fun
                 | TokenType::For
                 | TokenType::If
+                | TokenType::Match
                 | TokenType::Print
                 | TokenType::Return
+                | TokenType::Throw
+                | TokenType::Trait
+                | TokenType::Try
                 | TokenType::Var
-                | TokenType::While => return,
+                | TokenType::While
+                | TokenType::Yield => return,
                 _ => {}
             }
 
@@ -531,3 +1640,23 @@ impl Parser {
         }
     }
 }
+
+/// Returns the binary operator a compound assignment applies,
+/// like the `+` of `+=`, or None for a plain `=`
+fn compound_operator(equals: &Token) -> Option<Token> {
+    let kind = match equals.kind() {
+        TokenType::PlusEqual => TokenType::Plus,
+        TokenType::MinusEqual => TokenType::Minus,
+        TokenType::StarEqual => TokenType::Star,
+        TokenType::SlashEqual => TokenType::Slash,
+        TokenType::PercentEqual => TokenType::Percent,
+        _ => return None,
+    };
+    let lexeme = equals.lexeme();
+    Some(Token::new(
+        kind,
+        &lexeme[..lexeme.len() - 1],
+        TokenLiteral::Nil,
+        equals.line(),
+    ))
+}
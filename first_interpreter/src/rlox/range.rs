@@ -0,0 +1,136 @@
+use std::fmt::Display;
+
+use crate::{
+    common::Error,
+    rlox::{Arity, Interpreter, RloxCallable, Token, TokenLiteral, Unwind, Value},
+};
+
+/// Represents a lazy run of integers from a start up to, but
+/// not including, an end, like the ones `range(0, 10, 2)` makes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RloxRange {
+    start: i32,
+    end: i32,
+    step: i32,
+}
+
+impl RloxRange {
+    /// Constructs a new RloxRange
+    ///
+    /// Errors if the step is zero, since the range would never end
+    pub fn new(start: i32, end: i32, step: i32) -> Result<RloxRange, &'static str> {
+        if step == 0 {
+            return Err("Range step cannot be zero.");
+        }
+        Ok(RloxRange { start, end, step })
+    }
+
+    /// Returns the first number of the range
+    pub fn start(&self) -> i32 {
+        self.start
+    }
+
+    /// Returns true if the number is before the end of the range,
+    /// in the direction of its step
+    pub fn contains(&self, number: i32) -> bool {
+        match self.step > 0 {
+            true => number < self.end,
+            false => number > self.end,
+        }
+    }
+
+    /// Returns the number after the given one, or None
+    /// if it would not fit in an integer
+    pub fn after(&self, number: i32) -> Option<i32> {
+        number.checked_add(self.step)
+    }
+}
+
+impl Display for RloxRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "range({}, {}, {})", self.start, self.end, self.step)
+    }
+}
+
+/// Represents the native `range(start, end, step)` function
+///
+/// `range(end)` starts at zero and the step defaults to one
+#[derive(Debug, Clone, Default)]
+pub struct RangeFunction;
+
+impl RangeFunction {
+    pub fn new() -> RangeFunction {
+        RangeFunction
+    }
+
+    /// Calls the function, reporting bad arguments at the paren of the call
+    pub fn call_at(&self, arguments: Vec<Value>, paren: &Token) -> Result<Value, Error> {
+        let range = range(arguments).map_err(|message| Error::runtime(paren.clone(), message))?;
+        Ok(Value::new(range))
+    }
+}
+
+/// Makes the range the arguments of a call describe
+fn range(arguments: Vec<Value>) -> Result<RloxRange, &'static str> {
+    let mut bounds = Vec::with_capacity(arguments.len());
+    for argument in arguments {
+        match argument.owned::<TokenLiteral>() {
+            Ok(TokenLiteral::Integer(v)) => bounds.push(v),
+            _ => return Err("Range arguments must be integers."),
+        }
+    }
+    match bounds.as_slice() {
+        [end] => RloxRange::new(0, *end, 1),
+        [start, end] => RloxRange::new(*start, *end, 1),
+        [start, end, step] => RloxRange::new(*start, *end, *step),
+        _ => unreachable!("arity is checked by the caller"),
+    }
+}
+
+impl Display for RangeFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<native fn range>")
+    }
+}
+
+impl RloxCallable for RangeFunction {
+    fn arity(&self) -> Arity {
+        Arity::range(1, 3)
+    }
+
+    fn call(&self, _interpreter: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, Unwind> {
+        let range = range(arguments).map_err(Error::generic)?;
+        Ok(Value::new(range))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(range: RloxRange) -> Vec<i32> {
+        let mut numbers = Vec::new();
+        let mut next = Some(range.start());
+        while let Some(number) = next.filter(|number| range.contains(*number)) {
+            numbers.push(number);
+            next = range.after(number);
+        }
+        numbers
+    }
+
+    #[test]
+    fn test_range() {
+        assert_eq!(collect(RloxRange::new(0, 5, 2).unwrap()), vec![0, 2, 4]);
+        assert_eq!(collect(RloxRange::new(3, 0, -1).unwrap()), vec![3, 2, 1]);
+        assert_eq!(collect(RloxRange::new(2, 2, 1).unwrap()), Vec::<i32>::new());
+        assert_eq!(
+            collect(RloxRange::new(0, 5, -1).unwrap()),
+            Vec::<i32>::new()
+        );
+        assert_eq!(
+            collect(RloxRange::new(i32::MAX - 1, i32::MAX, 5).unwrap()),
+            vec![i32::MAX - 1]
+        );
+        assert!(RloxRange::new(0, 5, 0).is_err());
+    }
+}
@@ -2,89 +2,328 @@
 //! This module runs after the Parser has produced the AST
 //! and before the Interpreter begins execution.
 //!
-//! There are no side effects produced from this module.
-//! All states remain the same after this module runs.
+//! The syntax tree is never modified: the scope distance of every local
+//! variable is handed to the Interpreter, keyed by the id of the node
+//! that refers to the variable.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
-    common::{Error, Stack},
-    expressions::{expr::Visitor as ExprVisitor, Expr, Literal},
-    rlox::{Interpreter, Token, TokenLiteral},
-    stmt::{stmt::Visitor as StmtVisitor, Stmt},
+    common::{Error, NodeId, Stack},
+    expressions::{
+        expr::Visitor as ExprVisitor, Assign, AssignPattern, Binary, Call, Expr, Get, Grouping,
+        Index, Lambda, List, Literal, Logical, Map, Set, SetIndex, Super, Ternary, This, Unary,
+        Variable,
+    },
+    rlox::{token::TokenType, Interpreter, Token},
+    stmt::{
+        stmt::Visitor as StmtVisitor, Block, Break, Class, Continue, Enum, Expression, ForIn,
+        Function, If, Match, Print, Return, Stmt, Throw, Trait, Try, Var, VarPattern, While, Yield,
+    },
 };
 
+/// Determines what kind of function body is being resolved
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FunctionKind {
+    /// Identifier for code outside of any function
+    None,
+    /// Identifier for a function
+    Function,
+    /// Identifier for a class method
+    Method,
+    /// Identifier for the `init` method of a class
+    Initializer,
+    /// Identifier for a function or method declared with `fun*` or `*`
+    Generator,
+}
+
+/// Determines what kind of class body is being resolved
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ClassKind {
+    /// Identifier for code outside of any class
+    None,
+    /// Identifier for a class without a superclass
+    Class,
+    /// Identifier for a class with a superclass
+    Subclass,
+    /// Identifier for a trait
+    Trait,
+}
+
 /// Represents a structure for handling variable resolution
-struct Resolver {
-    interpreter: Interpreter,
+pub struct Resolver<'a, 'b> {
+    interpreter: &'a mut Interpreter<'b>,
     /// The boolean value represents whether or not the variable
     /// initializer has been resolved
     scopes: Stack<HashMap<String, bool>>,
+    /// The names declared with `const` in the global scope and then
+    /// in each of the scopes, in the same order
+    constants: Vec<HashSet<String>>,
+    /// The kind of function whose body is being resolved
+    current_function: FunctionKind,
+    /// The kind of class whose body is being resolved
+    current_class: ClassKind,
+    /// The labels of the loops around the code being resolved, innermost last
+    loops: Vec<Option<String>>,
+    /// The names of the methods of the traits declared in the global scope
+    /// and then in each of the scopes, by the name of the trait, to find
+    /// the methods that the traits of a class share
+    traits: Vec<HashMap<String, Vec<String>>>,
 }
 
-impl Resolver {
+impl<'a, 'b> Resolver<'a, 'b> {
     /// Constructs a new Resolver
-    pub fn new(interpreter: Interpreter) -> Resolver {
+    pub fn new(interpreter: &'a mut Interpreter<'b>) -> Resolver<'a, 'b> {
         Resolver {
             interpreter,
             scopes: Stack::new(),
+            constants: vec![HashSet::new()],
+            current_function: FunctionKind::None,
+            current_class: ClassKind::None,
+            loops: Vec::new(),
+            traits: vec![HashMap::new()],
         }
     }
 
-    /// Resolves a list of statements
-    fn resolve_statements(&mut self, statements: Vec<Stmt>) {
+    /// Resolves every variable used in a program
+    ///
+    /// Stops at the first error found, after reporting it
+    pub fn resolve(&mut self, statements: &[Stmt]) -> Result<(), Error> {
         for statement in statements {
-            self.resolve_statement(statement)
+            self.resolve_statement(statement)?;
         }
+        Ok(())
+    }
+
+    /// Resolves a list of statements
+    fn resolve_statements(&mut self, statements: Vec<Stmt>) -> Result<(), Error> {
+        self.resolve(&statements)
     }
 
     /// Resolves a statement
-    fn resolve_statement(&mut self, stmt: Stmt) {
-        stmt.accept(self);
+    fn resolve_statement(&mut self, stmt: &Stmt) -> Result<(), Error> {
+        stmt.accept(self)
     }
 
     /// Resolves an expression
-    fn resolve_expression(&mut self, expr: Expr) {
-        expr.accept(self);
+    fn resolve_expression(&mut self, expr: &Expr) -> Result<(), Error> {
+        expr.accept(self)
+    }
+
+    /// Resolves the parameters and body of a function in a new scope
+    fn resolve_function(&mut self, function: &Function, kind: FunctionKind) -> Result<(), Error> {
+        let kind = match (function.is_generator(), kind) {
+            (false, kind) => kind,
+            (true, FunctionKind::Initializer) => {
+                return Err(Error::report_parse(
+                    function.name(),
+                    "An initializer cannot be a generator.",
+                ))
+            }
+            (true, _) => FunctionKind::Generator,
+        };
+        let enclosing = std::mem::replace(&mut self.current_function, kind);
+        // loops outside the function cannot be broken out of from inside it
+        let enclosing_loops = std::mem::take(&mut self.loops);
+        self.begin_scope();
+
+        for param in function.params() {
+            // a default can refer to the parameters before its own
+            if let Some(default) = param.default() {
+                self.resolve_expression(&default)?;
+            }
+            self.declare(param.name())?;
+            self.define(param.name());
+        }
+        self.resolve_statements(function.body())?;
+
+        self.end_scope();
+        self.loops = enclosing_loops;
+        self.current_function = enclosing;
+        Ok(())
+    }
+
+    /// Resolves a list of statements in a new block scope
+    fn resolve_block(&mut self, statements: Vec<Stmt>) -> Result<(), Error> {
+        self.begin_scope();
+        self.resolve_statements(statements)?;
+        self.end_scope();
+        Ok(())
+    }
+
+    /// Resolves the traits of a class and checks that no two of them
+    /// define the same method, unless the class overrides the method
+    ///
+    /// Traits the resolver has not seen declared are left to the Interpreter
+    fn check_traits(&mut self, stmt: &Class) -> Result<(), Error> {
+        let overridden: HashSet<String> = stmt
+            .methods()
+            .iter()
+            .filter(|method| !method.is_class_method())
+            .map(|method| method.name().lexeme())
+            .collect();
+        let mut defined_by: HashMap<String, String> = HashMap::new();
+        let mut used = HashSet::new();
+        for trait_ in stmt.traits() {
+            self.visit_variable_expr(&trait_)?;
+            let name = trait_.name().lexeme();
+            if !used.insert(name.clone()) {
+                return Err(Error::report_parse(
+                    trait_.name().clone(),
+                    &format!("Trait {} is used more than once.", name),
+                ));
+            }
+            let methods = self.traits[self.declaring_scope(&name)]
+                .get(&name)
+                .cloned()
+                .unwrap_or_default();
+            for method in methods {
+                if overridden.contains(&method) {
+                    continue;
+                }
+                if let Some(other) = defined_by.insert(method.clone(), name.clone()) {
+                    return Err(Error::report_parse(
+                        trait_.name().clone(),
+                        &format!(
+                            "Traits {} and {} both define '{}', so the class must override it.",
+                            other, name, method
+                        ),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that a private property is only used inside a class body
+    ///
+    /// Whether it is used on an object of that class is checked at runtime
+    fn check_private(&self, name: &Token) -> Result<(), Error> {
+        let in_class = matches!(self.current_class, ClassKind::Class | ClassKind::Subclass);
+        if name.kind() == TokenType::PrivateIdentifier && !in_class {
+            return Err(Error::report_parse(
+                name.clone(),
+                &format!(
+                    "Cannot access private property '{}' outside of its class.",
+                    name.lexeme()
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks that a break or continue statement is inside
+    /// a loop, and inside the loop it names if it has a label
+    fn check_loop(&self, keyword: Token, label: Option<Token>) -> Result<(), Error> {
+        if self.loops.is_empty() {
+            return Err(Error::report_parse(
+                keyword.clone(),
+                &format!("Cannot use '{}' outside of a loop.", keyword.lexeme()),
+            ));
+        }
+        if let Some(label) = label {
+            if !self.loops.contains(&Some(label.lexeme())) {
+                return Err(Error::report_parse(
+                    label.clone(),
+                    &format!("No enclosing loop is labeled '{}'.", label.lexeme()),
+                ));
+            }
+        }
+        Ok(())
     }
 
     /// Creates a new block scope
     fn begin_scope(&mut self) {
         self.scopes.push(HashMap::new());
+        self.constants.push(HashSet::new());
+        self.traits.push(HashMap::new());
     }
 
     /// Exits a block scope
     fn end_scope(&mut self) {
         self.scopes.pop();
+        self.constants.pop();
+        self.traits.pop();
     }
 
     /// Adds the variable to the innermost scope so that
     /// it shadows any outer scope.
-    fn declare(&mut self, name: Token) {
-        if self.scopes.is_empty() {
-            return;
-        }
-        let scope = self
-            .scopes
-            .peek_mut()
-            .expect("should not happen, scope should exist.");
+    fn declare(&mut self, name: Token) -> Result<(), Error> {
+        // the name no longer refers to a trait declared before, if it did
+        self.traits
+            .last_mut()
+            .expect("the global traits are never popped")
+            .remove(&name.lexeme());
+        let scope = match self.scopes.peek_mut() {
+            Some(scope) => scope,
+            // globals may be redeclared, unless they are constants
+            None if self.constants[0].contains(&name.lexeme()) => {
+                let message = format!("Cannot redeclare constant '{}'.", name.lexeme());
+                return Err(Error::report_parse(name, &message));
+            }
+            None => return Ok(()),
+        };
+        if scope.contains_key(&name.lexeme()) {
+            return Err(Error::report_parse(
+                name,
+                "Already a variable with this name in this scope.",
+            ));
+        }
         scope.insert(name.lexeme(), false);
+        Ok(())
     }
 
     /// Resolve the initializer expression of a variable by defining it
     fn define(&mut self, name: Token) {
-        if self.scopes.is_empty() {
-            return;
+        if let Some(scope) = self.scopes.peek_mut() {
+            scope.insert(name.lexeme(), true);
+        }
+    }
+
+    /// Errors if the variable the name refers to was declared with `const`
+    ///
+    /// Globals are only known here once their declaration is resolved,
+    /// so the environment checks them again when the program runs
+    fn check_assignable(&self, name: &Token) -> Result<(), Error> {
+        let lexeme = name.lexeme();
+        match self.constants[self.declaring_scope(&lexeme)].contains(&lexeme) {
+            true => Err(Error::report_parse(
+                name.clone(),
+                &format!("Cannot assign to constant '{}'.", lexeme),
+            )),
+            false => Ok(()),
         }
-        let scope = self
-            .scopes
-            .peek_mut()
-            .expect("should not happen, scope should exist.");
-        scope.entry(name.lexeme()).and_modify(|v| *v = true);
     }
 
-    /// Resolves a local variable
-    fn resolve_local(&self, expr: Expr, name: Token) {
+    /// Returns the index of the scope declaring the name in the sets kept
+    /// for every scope, where the global one comes before the others
+    fn declaring_scope(&self, name: &str) -> usize {
+        (0..self.scopes.len())
+            .rev()
+            .find(|i| {
+                self.scopes
+                    .get(*i)
+                    .expect("index i to retrieve for scope should be valid")
+                    .contains_key(name)
+            })
+            .map_or(0, |i| i + 1)
+    }
+
+    /// Records whether the variable just declared in the innermost scope is a constant
+    fn record_constant(&mut self, name: &Token, is_const: bool) {
+        if is_const {
+            self.constants
+                .last_mut()
+                .expect("the global set of constants is never popped")
+                .insert(name.lexeme());
+        }
+    }
+
+    /// Resolves a local variable by recording how many scopes
+    /// separate the node using it from the scope declaring it
+    ///
+    /// Variables that are not found are assumed to be global
+    fn resolve_local(&mut self, id: NodeId, name: &Token) {
         for i in (0..self.scopes.len()).rev() {
             if self
                 .scopes
@@ -92,103 +331,594 @@ impl Resolver {
                 .expect("index i to retrieve for scope should be valid")
                 .contains_key(&name.lexeme())
             {
-                self.interpreter.resolve(expr, self.scopes.len() - 1 - i);
+                self.interpreter.resolve(id, self.scopes.len() - 1 - i);
+                return;
             }
         }
     }
 }
 
-impl ExprVisitor<Result<(), Error>> for Resolver {
-    fn visit_assign_expr(&mut self, expr: &crate::expressions::Assign) -> Result<(), Error> {
-        unimplemented!()
+impl ExprVisitor<Result<(), Error>> for Resolver<'_, '_> {
+    fn visit_assign_expr(&mut self, expr: &Assign) -> Result<(), Error> {
+        self.check_assignable(&expr.name())?;
+        self.resolve_expression(&expr.value())?;
+        self.resolve_local(expr.id(), &expr.name());
+        Ok(())
     }
 
-    fn visit_binary_expr(&mut self, expr: &crate::expressions::Binary) -> Result<(), Error> {
-        unimplemented!()
+    fn visit_assign_pattern_expr(&mut self, expr: &AssignPattern) -> Result<(), Error> {
+        for (name, _) in expr.targets() {
+            self.check_assignable(&name)?;
+        }
+        self.resolve_expression(&expr.value())?;
+        for (name, id) in expr.targets() {
+            self.resolve_local(id, &name);
+        }
+        Ok(())
     }
 
-    fn visit_call_expr(&mut self, expr: &crate::expressions::Call) -> Result<(), Error> {
-        unimplemented!()
+    fn visit_binary_expr(&mut self, expr: &Binary) -> Result<(), Error> {
+        self.resolve_expression(&expr.left())?;
+        self.resolve_expression(&expr.right())
     }
 
-    fn visit_grouping_expr(&mut self, expr: &crate::expressions::Grouping) -> Result<(), Error> {
-        unimplemented!()
+    fn visit_call_expr(&mut self, expr: &Call) -> Result<(), Error> {
+        self.resolve_expression(&expr.callee())?;
+        for argument in expr.arguments() {
+            self.resolve_expression(argument)?;
+        }
+        for (_, argument) in expr.named_arguments() {
+            self.resolve_expression(argument)?;
+        }
+        Ok(())
     }
 
-    fn visit_literal_expr(&mut self, expr: &crate::expressions::Literal) -> Result<(), Error> {
-        unimplemented!()
+    fn visit_get_expr(&mut self, expr: &Get) -> Result<(), Error> {
+        // properties are looked up dynamically, only the object is resolved
+        self.check_private(expr.name())?;
+        self.resolve_expression(&expr.object())
     }
 
-    fn visit_logical_expr(&mut self, expr: &crate::expressions::Logical) -> Result<(), Error> {
-        unimplemented!()
+    fn visit_grouping_expr(&mut self, expr: &Grouping) -> Result<(), Error> {
+        self.resolve_expression(&expr.expression())
     }
 
-    fn visit_unary_expr(&mut self, expr: &crate::expressions::Unary) -> Result<(), Error> {
-        unimplemented!()
+    fn visit_index_expr(&mut self, expr: &Index) -> Result<(), Error> {
+        self.resolve_expression(&expr.object())?;
+        self.resolve_expression(&expr.index())
     }
 
-    fn visit_variable_expr(&mut self, expr: &crate::expressions::Variable) -> Result<(), Error> {
-        if !self.scopes.is_empty()
-            && matches!(
-                self.scopes
-                    .peek()
-                    .expect("should not happen, scope should exist.")
-                    .get(&expr.name().lexeme()),
-                Some(&false)
-            )
-        {
+    fn visit_lambda_expr(&mut self, expr: &Lambda) -> Result<(), Error> {
+        self.resolve_function(&expr.declaration(), FunctionKind::Function)
+    }
+
+    fn visit_list_expr(&mut self, expr: &List) -> Result<(), Error> {
+        for element in expr.elements() {
+            self.resolve_expression(element)?;
+        }
+        Ok(())
+    }
+
+    fn visit_literal_expr(&mut self, _expr: &Literal) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn visit_logical_expr(&mut self, expr: &Logical) -> Result<(), Error> {
+        self.resolve_expression(&expr.left())?;
+        self.resolve_expression(&expr.right())
+    }
+
+    fn visit_map_expr(&mut self, expr: &Map) -> Result<(), Error> {
+        for (key, value) in expr.entries() {
+            self.resolve_expression(key)?;
+            self.resolve_expression(value)?;
+        }
+        Ok(())
+    }
+
+    fn visit_set_expr(&mut self, expr: &Set) -> Result<(), Error> {
+        self.check_private(expr.name())?;
+        self.resolve_expression(&expr.value())?;
+        self.resolve_expression(&expr.object())
+    }
+
+    fn visit_super_expr(&mut self, expr: &Super) -> Result<(), Error> {
+        match self.current_class {
+            ClassKind::None => Err(Error::report_parse(
+                expr.keyword().clone(),
+                "Cannot use 'super' outside of a class.",
+            )),
+            ClassKind::Class => Err(Error::report_parse(
+                expr.keyword().clone(),
+                "Cannot use 'super' in a class with no superclass.",
+            )),
+            ClassKind::Subclass => {
+                self.resolve_local(expr.id(), expr.keyword());
+                Ok(())
+            }
+            ClassKind::Trait => Err(Error::report_parse(
+                expr.keyword().clone(),
+                "Cannot use 'super' in a trait.",
+            )),
+        }
+    }
+
+    fn visit_ternary_expr(&mut self, expr: &Ternary) -> Result<(), Error> {
+        self.resolve_expression(&expr.condition())?;
+        self.resolve_expression(&expr.then_branch())?;
+        self.resolve_expression(&expr.else_branch())
+    }
+
+    fn visit_this_expr(&mut self, expr: &This) -> Result<(), Error> {
+        if self.current_class == ClassKind::None {
+            return Err(Error::report_parse(
+                expr.keyword().clone(),
+                "Cannot use 'this' outside of a class.",
+            ));
+        }
+        self.resolve_local(expr.id(), expr.keyword());
+        Ok(())
+    }
+
+    fn visit_set_index_expr(&mut self, expr: &SetIndex) -> Result<(), Error> {
+        self.resolve_expression(&expr.value())?;
+        self.resolve_expression(&expr.object())?;
+        self.resolve_expression(&expr.index())
+    }
+
+    fn visit_unary_expr(&mut self, expr: &Unary) -> Result<(), Error> {
+        self.resolve_expression(&expr.right())
+    }
+
+    fn visit_variable_expr(&mut self, expr: &Variable) -> Result<(), Error> {
+        if matches!(
+            self.scopes
+                .peek()
+                .and_then(|scope| scope.get(&expr.name().lexeme())),
+            Some(&false)
+        ) {
             return Err(Error::report_parse(
                 expr.name().clone(),
                 "Cannot read local variable in its own initializer",
             ));
         }
-        self.resolve_local(expr, expr.name());
+        self.resolve_local(expr.id(), expr.name());
         Ok(())
     }
 }
 
-impl StmtVisitor<Result<(), Error>> for Resolver {
-    fn visit_block_stmt(&mut self, stmt: &crate::stmt::Block) -> Result<(), Error> {
+impl StmtVisitor<Result<(), Error>> for Resolver<'_, '_> {
+    fn visit_block_stmt(&mut self, stmt: &Block) -> Result<(), Error> {
+        self.resolve_block(stmt.statements())
+    }
+
+    fn visit_break_stmt(&mut self, stmt: &Break) -> Result<(), Error> {
+        self.check_loop(stmt.keyword(), stmt.label())
+    }
+
+    fn visit_class_stmt(&mut self, stmt: &Class) -> Result<(), Error> {
+        self.declare(stmt.name())?;
+        self.define(stmt.name());
+
+        // fields are initialized outside of the methods, where there is no `this`
+        let mut fields = HashSet::new();
+        for field in stmt.fields() {
+            if !fields.insert(field.name().lexeme()) {
+                return Err(Error::report_parse(
+                    field.name(),
+                    "Already a field with this name in this class.",
+                ));
+            }
+            self.resolve_expression(&field.initializer())?;
+        }
+
+        self.check_traits(stmt)?;
+
+        let enclosing = std::mem::replace(&mut self.current_class, ClassKind::Class);
+
+        if let Some(superclass) = stmt.superclass() {
+            if superclass.name().lexeme() == stmt.name().lexeme() {
+                return Err(Error::report_parse(
+                    superclass.name().clone(),
+                    "A class cannot inherit from itself.",
+                ));
+            }
+            self.current_class = ClassKind::Subclass;
+            self.visit_variable_expr(&superclass)?;
+        }
+
+        // the methods see the class, and `super` in a subclass, in a scope around them
+        self.begin_scope();
+        if let (Some(scope), Some(_)) = (self.scopes.peek_mut(), stmt.superclass()) {
+            scope.insert("super".to_string(), true);
+        }
+
+        // `this` lives in a scope between the methods and the class
+        self.begin_scope();
+        if let Some(scope) = self.scopes.peek_mut() {
+            scope.insert("this".to_string(), true);
+        }
+        for method in stmt.methods() {
+            let kind = match method.name().lexeme() == "init" && !method.is_class_method() {
+                true => FunctionKind::Initializer,
+                false => FunctionKind::Method,
+            };
+            self.resolve_function(&method, kind)?;
+        }
+        self.end_scope();
+        self.end_scope();
+        self.current_class = enclosing;
+        Ok(())
+    }
+
+    fn visit_continue_stmt(&mut self, stmt: &Continue) -> Result<(), Error> {
+        self.check_loop(stmt.keyword(), stmt.label())
+    }
+
+    fn visit_enum_stmt(&mut self, stmt: &Enum) -> Result<(), Error> {
+        self.declare(stmt.name())?;
+        self.define(stmt.name());
+
+        let mut variants = HashSet::new();
+        for variant in stmt.variants() {
+            if !variants.insert(variant.name().lexeme()) {
+                return Err(Error::report_parse(
+                    variant.name().clone(),
+                    "Already a variant with this name in this enum.",
+                ));
+            }
+            let mut fields = HashSet::new();
+            for field in variant.fields() {
+                if !fields.insert(field.lexeme()) {
+                    return Err(Error::report_parse(
+                        field,
+                        "Already a field with this name in this variant.",
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn visit_expression_stmt(&mut self, stmt: &Expression) -> Result<(), Error> {
+        self.resolve_expression(&stmt.expression())
+    }
+
+    fn visit_for_in_stmt(&mut self, stmt: &ForIn) -> Result<(), Error> {
+        self.resolve_expression(&stmt.iterable())?;
+        // the loop variable shares a scope with the body
         self.begin_scope();
-        self.resolve_statements(stmt.statements());
+        self.declare(stmt.name())?;
+        self.define(stmt.name());
+        self.loops.push(stmt.label().map(|label| label.lexeme()));
+        let body = self.resolve_statement(&stmt.body());
+        self.loops.pop();
         self.end_scope();
+        body
+    }
+
+    fn visit_function_stmt(&mut self, stmt: &Function) -> Result<(), Error> {
+        // define the name first so the function can refer to itself
+        self.declare(stmt.name())?;
+        self.define(stmt.name());
+        self.resolve_function(stmt, FunctionKind::Function)
+    }
+
+    fn visit_if_stmt(&mut self, stmt: &If) -> Result<(), Error> {
+        self.resolve_expression(&stmt.condition())?;
+        self.resolve_statement(&stmt.then_branch())?;
+        if let Some(else_branch) = stmt.else_branch() {
+            self.resolve_statement(&else_branch)?;
+        }
         Ok(())
     }
 
-    fn visit_class_stmt(&mut self, stmt: &crate::stmt::Class) -> Result<(), Error> {
-        unimplemented!()
+    fn visit_match_stmt(&mut self, stmt: &Match) -> Result<(), Error> {
+        self.resolve_expression(&stmt.subject())?;
+        for arm in stmt.arms() {
+            // the names the pattern binds share a scope with the guard and the body
+            self.begin_scope();
+            for name in arm.pattern().bindings() {
+                self.declare(name.clone())?;
+                self.define(name);
+            }
+            if let Some(guard) = arm.guard() {
+                self.resolve_expression(&guard)?;
+            }
+            self.resolve_statement(&arm.body())?;
+            self.end_scope();
+        }
+        Ok(())
     }
 
-    fn visit_expression_stmt(&mut self, stmt: &crate::stmt::Expression) -> Result<(), Error> {
-        unimplemented!()
+    fn visit_print_stmt(&mut self, stmt: &Print) -> Result<(), Error> {
+        self.resolve_expression(&stmt.expression())
     }
 
-    fn visit_function_stmt(&mut self, stmt: &crate::stmt::Function) -> Result<(), Error> {
-        unimplemented!()
+    fn visit_return_stmt(&mut self, stmt: &Return) -> Result<(), Error> {
+        if self.current_function == FunctionKind::None {
+            return Err(Error::report_parse(
+                stmt.keyword(),
+                "Cannot return from top-level code.",
+            ));
+        }
+        match stmt.value() {
+            Some(_) if self.current_function == FunctionKind::Initializer => Err(
+                Error::report_parse(stmt.keyword(), "Cannot return a value from an initializer."),
+            ),
+            // the values of a generator are the ones it yields
+            Some(_) if self.current_function == FunctionKind::Generator => Err(
+                Error::report_parse(stmt.keyword(), "Cannot return a value from a generator."),
+            ),
+            Some(value) => self.resolve_expression(&value),
+            None => Ok(()),
+        }
     }
 
-    fn visit_if_stmt(&mut self, stmt: &crate::stmt::If) -> Result<(), Error> {
-        unimplemented!()
+    fn visit_throw_stmt(&mut self, stmt: &Throw) -> Result<(), Error> {
+        self.resolve_expression(&stmt.value())
     }
 
-    fn visit_print_stmt(&mut self, stmt: &crate::stmt::Print) -> Result<(), Error> {
-        unimplemented!()
+    fn visit_trait_stmt(&mut self, stmt: &Trait) -> Result<(), Error> {
+        self.declare(stmt.name())?;
+        self.define(stmt.name());
+
+        let enclosing = std::mem::replace(&mut self.current_class, ClassKind::Trait);
+        // `this` lives in a scope between the methods and the trait,
+        // the same as it does for the methods of a class
+        self.begin_scope();
+        if let Some(scope) = self.scopes.peek_mut() {
+            scope.insert("this".to_string(), true);
+        }
+        for method in stmt.methods() {
+            if method.name().lexeme() == "init" {
+                return Err(Error::report_parse(
+                    method.name(),
+                    "A trait cannot define an initializer.",
+                ));
+            }
+            if method.name().kind() == TokenType::PrivateIdentifier {
+                return Err(Error::report_parse(
+                    method.name(),
+                    "A trait cannot define private methods.",
+                ));
+            }
+            self.resolve_function(&method, FunctionKind::Method)?;
+        }
+        self.end_scope();
+        self.current_class = enclosing;
+
+        let methods = stmt
+            .methods()
+            .into_iter()
+            .map(|method| method.name().lexeme());
+        self.traits
+            .last_mut()
+            .expect("the global traits are never popped")
+            .insert(stmt.name().lexeme(), methods.collect());
+        Ok(())
     }
 
-    fn visit_return_stmt(&mut self, stmt: &crate::stmt::Return) -> Result<(), Error> {
-        unimplemented!()
+    fn visit_try_stmt(&mut self, stmt: &Try) -> Result<(), Error> {
+        self.resolve_block(stmt.body())?;
+        if let Some(catch) = stmt.catch() {
+            // the caught value shares a scope with the catch body
+            self.begin_scope();
+            self.declare(catch.name())?;
+            self.define(catch.name());
+            self.resolve_statements(catch.body())?;
+            self.end_scope();
+        }
+        if let Some(finally) = stmt.finally() {
+            self.resolve_block(finally)?;
+        }
+        Ok(())
     }
 
-    fn visit_var_stmt(&mut self, stmt: &crate::stmt::Var) -> Result<(), Error> {
-        self.declare(stmt.name());
+    fn visit_var_stmt(&mut self, stmt: &Var) -> Result<(), Error> {
+        self.declare(stmt.name())?;
         if stmt.is_initialized() {
-            self.resolve_expression(stmt.initializer())
+            self.resolve_expression(&stmt.initializer())?;
         }
         self.define(stmt.name());
+        self.record_constant(&stmt.name(), stmt.is_const());
+        Ok(())
+    }
+
+    fn visit_var_pattern_stmt(&mut self, stmt: &VarPattern) -> Result<(), Error> {
+        let names = stmt.pattern().bindings();
+        for name in &names {
+            self.declare(name.clone())?;
+        }
+        self.resolve_expression(&stmt.initializer())?;
+        for name in names {
+            self.define(name.clone());
+            self.record_constant(&name, stmt.is_const());
+        }
         Ok(())
     }
 
-    fn visit_while_stmt(&mut self, stmt: &crate::stmt::While) -> Result<(), Error> {
-        unimplemented!()
+    fn visit_while_stmt(&mut self, stmt: &While) -> Result<(), Error> {
+        self.resolve_expression(&stmt.condition())?;
+        self.loops.push(stmt.label().map(|label| label.lexeme()));
+        let body = self.resolve_statement(&stmt.body());
+        self.loops.pop();
+        body?;
+        if let Some(increment) = stmt.increment() {
+            self.resolve_expression(&increment)?;
+        }
+        Ok(())
+    }
+
+    fn visit_yield_stmt(&mut self, stmt: &Yield) -> Result<(), Error> {
+        if self.current_function != FunctionKind::Generator {
+            return Err(Error::report_parse(
+                stmt.keyword(),
+                "Cannot use 'yield' outside of a generator.",
+            ));
+        }
+        self.resolve_expression(&stmt.value())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rlox::{parser::Parser, scanner::Scanner};
+
+    fn resolve_source(source: &str) -> (Interpreter<'static>, Result<Vec<Stmt>, Error>) {
+        let tokens = Scanner::new(source.to_string()).scan_tokens().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+
+        let mut interpreter = Interpreter::new();
+        let result = Resolver::new(&mut interpreter).resolve(&statements);
+        (interpreter, result.map(|_| statements))
+    }
+
+    #[test]
+    fn test_resolve_depths() {
+        let (interpreter, statements) = resolve_source(
+            r#"
+var a = 1;
+fun outer(b) {
+    fun inner() { return a + b; }
+    return inner;
+}
+"#,
+        );
+        let statements = statements.unwrap();
+
+        // `a + b` inside `inner`: `a` is global, `b` is one function out
+        let inner = match &statements[1] {
+            Stmt::Function(outer) => match &outer.body()[0] {
+                Stmt::Function(inner) => inner.body()[0].clone(),
+                _ => panic!("expected the inner function"),
+            },
+            _ => panic!("expected the outer function"),
+        };
+        let sum = match inner {
+            Stmt::Return(ret) => match ret.value() {
+                Some(Expr::Binary(sum)) => sum,
+                _ => panic!("expected a sum"),
+            },
+            _ => panic!("expected a return statement"),
+        };
+
+        let id = |expr: Expr| match expr {
+            Expr::Variable(variable) => variable.id(),
+            _ => panic!("expected a variable"),
+        };
+        assert_eq!(interpreter.locals().get(id(sum.left())), None);
+        assert_eq!(interpreter.locals().get(id(sum.right())), Some(&1));
+    }
+
+    #[test]
+    fn test_resolve_errors() {
+        let test_cases = [
+            "{ var a = 1; var a = 2; }",
+            "{ var a = a; }",
+            "return 1;",
+            "fun f(a, a) {}",
+            "break;",
+            "if (true) continue;",
+            "while (true) { fun f() { break; } }",
+            "inner: while (true) {} while (true) { break inner; }",
+            "print this;",
+            "fun f() { return this; }",
+            "class A { f() { return super.f(); } }",
+            "class A < A {}",
+            "class A { init() { return 1; } }",
+            "for (var x in xs) {} break;",
+            "var f = (a, a) => a;",
+            "while (true) { var f = fun () { break; }; }",
+            "const a = 1; a = 2;",
+            "{ const a = 1; fun f() { a = 2; } }",
+            "const a = 1; a += 2;",
+            "match (x) { [a, {a}] => print a; }",
+            "match (x) { _ => return; }",
+            "const [a, {b}] = xs; b = 1;",
+            "const a = 1; var a = 2;",
+            "const a = 1; fun a() {}",
+            "const [a] = xs; const a = 2;",
+            "{ var [a, a] = xs; }",
+            "{ var [a] = a; }",
+            "enum E { A, B(x), A(y) }",
+            "enum E { A(x, x) }",
+            "{ var E = 1; enum E {} }",
+            "class A { class x = this; }",
+            "class A { class x; class x; }",
+            "trait T { f() { return super.f(); } }",
+            "trait T { init() {} }",
+            "class A {} fun f(a) { return a.#x; }",
+            "var a; a.#x = 1;",
+            "trait T { f() { return this.#x; } }",
+            "trait T { #f() {} }",
+            "trait T { f() {} } trait U { f() {} } class A with T, U {}",
+            "{ trait T { f() {} } trait U { f() {} } class A with T, U {} }",
+            "trait T {} class A with T, T {}",
+            "yield 1;",
+            "fun f() { yield 1; }",
+            "fun* f() { fun g() { yield 1; } }",
+            "fun* f() { return 1; }",
+            "class A { *init() {} }",
+        ];
+
+        for source in test_cases {
+            let (_, result) = resolve_source(source);
+            assert!(result.is_err(), "{}", source);
+        }
+
+        // a trait is looked up in the scopes like any other name
+        let (_, result) = resolve_source(
+            "trait T { f() {} } { trait U { g() {} } class A with T, U {} } trait U { f() {} }",
+        );
+        assert!(result.is_ok());
+        let (_, result) = resolve_source(
+            "trait T { f() {} } trait U { g() {} } { trait U { f() {} } } class A with T, U {}",
+        );
+        assert!(result.is_ok());
+
+        // traits may share a method the class overrides, and use `this`
+        let (_, result) = resolve_source(
+            "trait T { f() { return this; } } trait U { f() {} } class A with T, U { f() {} }",
+        );
+        assert!(result.is_ok());
+
+        // a generator may end early, and functions inside it return values
+        let (_, result) = resolve_source(
+            "fun* f() { yield 1; var g = fun* () { yield 2; }; fun h() { return 3; } return; }",
+        );
+        assert!(result.is_ok());
+
+        // a class method named init is not an initializer
+        let (_, result) = resolve_source("class A { class init() { return 1; } }");
+        assert!(result.is_ok());
+
+        // globals may be redeclared and read in their own initializer
+        let (_, result) = resolve_source("var a = 1; var a = a;");
+        assert!(result.is_ok());
+
+        // constants may be shadowed
+        let (_, result) = resolve_source("const a = 1; { var a = 2; a = 3; } fun f(a) { a = 4; }");
+        assert!(result.is_ok());
+
+        // every arm gets its own scope for the names its pattern binds
+        let (_, result) =
+            resolve_source("{ match (x) { [a, ...b] if a => print b; {a} => print a; } }");
+        assert!(result.is_ok());
+
+        let (_, result) = resolve_source(
+            "outer: for (;;) { while (true) { if (a) break outer; continue outer; } break; }",
+        );
+        assert!(result.is_ok());
+
+        let (_, result) = resolve_source(
+            "class A { init() { return; } } class B < A { f() { return super.f(this); } }",
+        );
+        assert!(result.is_ok());
+
+        let (_, result) =
+            resolve_source("outer: for (var x in xs) { for (var y in x) continue outer; }");
+        assert!(result.is_ok());
     }
 }
@@ -1,6 +1,6 @@
 use crate::{
     common::errors::Error,
-    rlox::token::{Token, TokenLiteral, TokenType, KEYWORDS},
+    rlox::token::{Token, TokenLiteral, TokenType, Trivia, KEYWORDS},
 };
 
 /// This represents a structure for scanning the source file
@@ -11,6 +11,10 @@ pub struct Scanner {
     start: usize,
     current: usize,
     line: u32,
+    /// The trivia found since the last token was added
+    trivia: Vec<Trivia>,
+    /// The line of the last token or comment, 0 if there is none yet
+    trivia_line: u32,
 }
 
 impl Scanner {
@@ -21,6 +25,8 @@ impl Scanner {
             start: 0,
             current: 0,
             line: 1,
+            trivia: vec![],
+            trivia_line: 0,
         }
     }
 
@@ -43,8 +49,9 @@ impl Scanner {
             }
         }
 
+        let trivia = std::mem::take(&mut self.trivia);
         self.tokens
-            .push(Token::new(TokenType::EOF, "", TokenLiteral::Nil, self.line));
+            .push(Token::new(TokenType::EOF, "", TokenLiteral::Nil, self.line).with_trivia(trivia));
 
         if num_errors == 0 {
             Ok(self.tokens())
@@ -64,36 +71,93 @@ impl Scanner {
             Some(')') => self.add_token(TokenType::RightParen, TokenLiteral::Nil),
             Some('{') => self.add_token(TokenType::LeftBrace, TokenLiteral::Nil),
             Some('}') => self.add_token(TokenType::RightBrace, TokenLiteral::Nil),
+            Some('[') => self.add_token(TokenType::LeftBracket, TokenLiteral::Nil),
+            Some(']') => self.add_token(TokenType::RightBracket, TokenLiteral::Nil),
+            Some(':') => self.add_token(TokenType::Colon, TokenLiteral::Nil),
+            Some('&') => self.add_token(TokenType::Ampersand, TokenLiteral::Nil),
+            Some('^') => self.add_token(TokenType::Caret, TokenLiteral::Nil),
+            Some('|') => self.add_token(TokenType::Pipe, TokenLiteral::Nil),
+            Some('~') => self.add_token(TokenType::Tilde, TokenLiteral::Nil),
             Some(',') => self.add_token(TokenType::Comma, TokenLiteral::Nil),
-            Some('.') => self.add_token(TokenType::Dot, TokenLiteral::Nil),
-            Some('-') => self.add_token(TokenType::Minus, TokenLiteral::Nil),
-            Some('+') => self.add_token(TokenType::Plus, TokenLiteral::Nil),
+            Some('.') => {
+                if self.peek() == '.' && self.peek_next() == '.' {
+                    self.advance();
+                    self.advance();
+                    self.add_token(TokenType::Ellipsis, TokenLiteral::Nil);
+                } else {
+                    self.add_token(TokenType::Dot, TokenLiteral::Nil);
+                }
+            }
             Some(';') => self.add_token(TokenType::Semicolon, TokenLiteral::Nil),
-            Some('*') => self.add_token(TokenType::Star, TokenLiteral::Nil),
-            Some('!') => {
+            Some('-') => {
                 match self.conditionally_advance('=') {
-                    true => self.add_token(TokenType::BangEqual, TokenLiteral::Nil),
-                    false => self.add_token(TokenType::Bang, TokenLiteral::Nil),
+                    true => self.add_token(TokenType::MinusEqual, TokenLiteral::Nil),
+                    false => self.add_token(TokenType::Minus, TokenLiteral::Nil),
                 };
             }
-            Some('=') => {
+            Some('+') => {
                 match self.conditionally_advance('=') {
-                    true => self.add_token(TokenType::EqualEqual, TokenLiteral::Nil),
-                    false => self.add_token(TokenType::Equal, TokenLiteral::Nil),
+                    true => self.add_token(TokenType::PlusEqual, TokenLiteral::Nil),
+                    false => self.add_token(TokenType::Plus, TokenLiteral::Nil),
                 };
             }
-            Some('<') => {
+            Some('%') => {
                 match self.conditionally_advance('=') {
-                    true => self.add_token(TokenType::LessEqual, TokenLiteral::Nil),
-                    false => self.add_token(TokenType::Less, TokenLiteral::Nil),
+                    true => self.add_token(TokenType::PercentEqual, TokenLiteral::Nil),
+                    false => self.add_token(TokenType::Percent, TokenLiteral::Nil),
                 };
             }
-            Some('>') => {
+            Some('*') => {
+                if self.conditionally_advance('*') {
+                    self.add_token(TokenType::StarStar, TokenLiteral::Nil);
+                } else if self.conditionally_advance('=') {
+                    self.add_token(TokenType::StarEqual, TokenLiteral::Nil);
+                } else {
+                    self.add_token(TokenType::Star, TokenLiteral::Nil);
+                }
+            }
+            Some('?') => {
+                if self.conditionally_advance('.') {
+                    self.add_token(TokenType::QuestionDot, TokenLiteral::Nil);
+                } else if self.conditionally_advance('?') {
+                    self.add_token(TokenType::QuestionQuestion, TokenLiteral::Nil);
+                } else {
+                    self.add_token(TokenType::Question, TokenLiteral::Nil);
+                }
+            }
+            Some('!') => {
                 match self.conditionally_advance('=') {
-                    true => self.add_token(TokenType::GreaterEqual, TokenLiteral::Nil),
-                    false => self.add_token(TokenType::Greater, TokenLiteral::Nil),
+                    true => self.add_token(TokenType::BangEqual, TokenLiteral::Nil),
+                    false => self.add_token(TokenType::Bang, TokenLiteral::Nil),
                 };
             }
+            Some('=') => {
+                if self.conditionally_advance('=') {
+                    self.add_token(TokenType::EqualEqual, TokenLiteral::Nil);
+                } else if self.conditionally_advance('>') {
+                    self.add_token(TokenType::Arrow, TokenLiteral::Nil);
+                } else {
+                    self.add_token(TokenType::Equal, TokenLiteral::Nil);
+                }
+            }
+            Some('<') => {
+                if self.conditionally_advance('=') {
+                    self.add_token(TokenType::LessEqual, TokenLiteral::Nil);
+                } else if self.conditionally_advance('<') {
+                    self.add_token(TokenType::LessLess, TokenLiteral::Nil);
+                } else {
+                    self.add_token(TokenType::Less, TokenLiteral::Nil);
+                }
+            }
+            Some('>') => {
+                if self.conditionally_advance('=') {
+                    self.add_token(TokenType::GreaterEqual, TokenLiteral::Nil);
+                } else if self.conditionally_advance('>') {
+                    self.add_token(TokenType::GreaterGreater, TokenLiteral::Nil);
+                } else {
+                    self.add_token(TokenType::Greater, TokenLiteral::Nil);
+                }
+            }
             Some('/') => {
                 match self.conditionally_advance('/') {
                     true => {
@@ -101,10 +165,15 @@ impl Scanner {
                         while self.peek() != '\n' && !self.is_at_end() {
                             self.advance();
                         }
+                        self.add_comment();
                     }
-                    false => self.add_token(TokenType::Slash, TokenLiteral::Nil),
+                    false => match self.conditionally_advance('=') {
+                        true => self.add_token(TokenType::SlashEqual, TokenLiteral::Nil),
+                        false => self.add_token(TokenType::Slash, TokenLiteral::Nil),
+                    },
                 }
             }
+            Some('#') => self.parse_private_identifier()?,
             Some('"') => self.parse_string()?,
             Some('\n') => self.line += 1,
             // skip over and ignore other whitespaces
@@ -246,7 +315,7 @@ impl Scanner {
 
     /// Parses an identifier or keyword
     pub fn parse_identifier(&mut self) {
-        while self.peek().is_alphanumeric() {
+        while self.peek().is_alphanumeric() || self.peek() == '_' {
             self.advance();
         }
 
@@ -262,6 +331,21 @@ impl Scanner {
         self.add_token(token_type, TokenLiteral::Nil);
     }
 
+    /// Parses the name of a private class member, like `#count`
+    pub fn parse_private_identifier(&mut self) -> Result<(), Error> {
+        if !self.peek().is_alphabetic() && self.peek() != '_' {
+            return Err(Error::report_syntax(
+                self.line,
+                "Expected a name after '#'.",
+            ));
+        }
+        while self.peek().is_alphanumeric() || self.peek() == '_' {
+            self.advance();
+        }
+        self.add_token(TokenType::PrivateIdentifier, TokenLiteral::Nil);
+        Ok(())
+    }
+
     /// Creates a new token from the type and literal and pushes it to the
     /// scanner's token list.
     pub fn add_token(&mut self, token_type: TokenType, literal: TokenLiteral) {
@@ -271,8 +355,39 @@ impl Scanner {
             _ => &self.source[self.start..self.current],
         };
         let text = text.iter().collect::<String>();
+
+        // multi-line strings end on a later line than they start
+        let start_line = self.line - text.matches('\n').count() as u32;
+        self.add_blank_line(start_line);
+
+        let trivia = std::mem::take(&mut self.trivia);
         self.tokens
-            .push(Token::new(token_type, &text, literal, self.line));
+            .push(Token::new(token_type, &text, literal, self.line).with_trivia(trivia));
+        self.trivia_line = self.line;
+    }
+
+    /// Keeps the comment just scanned as trivia for the next token
+    fn add_comment(&mut self) {
+        let text = self.source[self.start..self.current]
+            .iter()
+            .collect::<String>();
+        let trailing = self.trivia.is_empty()
+            && matches!(self.tokens.last(), Some(token) if token.line() == self.line);
+
+        self.add_blank_line(self.line);
+        match trailing {
+            true => self.trivia.push(Trivia::TrailingComment(text)),
+            false => self.trivia.push(Trivia::Comment(text)),
+        }
+        self.trivia_line = self.line;
+    }
+
+    /// Records a blank line if there is an empty line between
+    /// the last token or comment and the given line
+    fn add_blank_line(&mut self, line: u32) {
+        if self.trivia_line > 0 && line > self.trivia_line + 1 {
+            self.trivia.push(Trivia::BlankLine);
+        }
     }
 }
 
@@ -316,6 +431,8 @@ mod tests {
             start: 0,
             current: 0,
             line: 1,
+            trivia: vec![],
+            trivia_line: 0,
         };
         assert_eq!(
             scanner.tokens(),
@@ -374,6 +491,138 @@ mod tests {
                 Token::new(TokenType::EOF, "", TokenLiteral::Nil, 1),
             ]
         );
+
+        let mut scanner = Scanner::new("(a) => a == 1".to_string());
+        let kinds = scanner
+            .scan_tokens()
+            .unwrap()
+            .iter()
+            .map(|token| token.kind())
+            .collect::<Vec<TokenType>>();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenType::LeftParen,
+                TokenType::Identifier,
+                TokenType::RightParen,
+                TokenType::Arrow,
+                TokenType::Identifier,
+                TokenType::EqualEqual,
+                TokenType::Integer,
+                TokenType::EOF,
+            ]
+        );
+
+        let mut scanner = Scanner::new("a ** 2 % b << 1 >> ~c ? x : y".to_string());
+        let kinds = scanner
+            .scan_tokens()
+            .unwrap()
+            .iter()
+            .map(|token| token.kind())
+            .collect::<Vec<TokenType>>();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenType::Identifier,
+                TokenType::StarStar,
+                TokenType::Integer,
+                TokenType::Percent,
+                TokenType::Identifier,
+                TokenType::LessLess,
+                TokenType::Integer,
+                TokenType::GreaterGreater,
+                TokenType::Tilde,
+                TokenType::Identifier,
+                TokenType::Question,
+                TokenType::Identifier,
+                TokenType::Colon,
+                TokenType::Identifier,
+                TokenType::EOF,
+            ]
+        );
+
+        let mut scanner = Scanner::new("+= -= *= /= %= & | ^".to_string());
+        let kinds = scanner
+            .scan_tokens()
+            .unwrap()
+            .iter()
+            .map(|token| token.kind())
+            .collect::<Vec<TokenType>>();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenType::PlusEqual,
+                TokenType::MinusEqual,
+                TokenType::StarEqual,
+                TokenType::SlashEqual,
+                TokenType::PercentEqual,
+                TokenType::Ampersand,
+                TokenType::Pipe,
+                TokenType::Caret,
+                TokenType::EOF,
+            ]
+        );
+
+        let mut scanner = Scanner::new("a?.b ?? c ? d : e".to_string());
+        let kinds = scanner
+            .scan_tokens()
+            .unwrap()
+            .iter()
+            .map(|token| token.kind())
+            .collect::<Vec<TokenType>>();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenType::Identifier,
+                TokenType::QuestionDot,
+                TokenType::Identifier,
+                TokenType::QuestionQuestion,
+                TokenType::Identifier,
+                TokenType::Question,
+                TokenType::Identifier,
+                TokenType::Colon,
+                TokenType::Identifier,
+                TokenType::EOF,
+            ]
+        );
+
+        let mut scanner = Scanner::new("(...rest) a.b".to_string());
+        let kinds = scanner
+            .scan_tokens()
+            .unwrap()
+            .iter()
+            .map(|token| token.kind())
+            .collect::<Vec<TokenType>>();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenType::LeftParen,
+                TokenType::Ellipsis,
+                TokenType::Identifier,
+                TokenType::RightParen,
+                TokenType::Identifier,
+                TokenType::Dot,
+                TokenType::Identifier,
+                TokenType::EOF,
+            ]
+        );
+        // underscores may appear anywhere in a name
+        let mut scanner = Scanner::new("__add__ snake_case _".to_string());
+        let lexemes = scanner
+            .scan_tokens()
+            .unwrap()
+            .iter()
+            .map(|token| token.lexeme())
+            .collect::<Vec<String>>();
+        assert_eq!(lexemes, vec!["__add__", "snake_case", "_", ""]);
+
+        // private names start with a hash
+        let mut scanner = Scanner::new("this.#count #_x".to_string());
+        let tokens = scanner.scan_tokens().unwrap();
+        assert_eq!(tokens[2].kind(), TokenType::PrivateIdentifier);
+        assert_eq!(tokens[2].lexeme(), "#count");
+        assert_eq!(tokens[3].lexeme(), "#_x");
+        assert!(Scanner::new("#1".to_string()).scan_tokens().is_err());
     }
 
     #[test]
@@ -386,6 +635,8 @@ mod tests {
             start: 0,
             current: 18,
             line: 1,
+            trivia: vec![],
+            trivia_line: 0,
         };
         assert!(scanner.is_at_end());
         scanner.current = 10;
@@ -402,6 +653,8 @@ mod tests {
             start: 0,
             current: 18,
             line: 1,
+            trivia: vec![],
+            trivia_line: 0,
         };
         assert_eq!(scanner.peek(), '\0');
         scanner.current = 10;
@@ -418,6 +671,8 @@ mod tests {
             start: 0,
             current: 18,
             line: 1,
+            trivia: vec![],
+            trivia_line: 0,
         };
         assert_eq!(scanner.peek_next(), '\0');
         scanner.current = 10;
@@ -434,6 +689,8 @@ mod tests {
             start: 0,
             current: 18,
             line: 1,
+            trivia: vec![],
+            trivia_line: 0,
         };
         assert_eq!(scanner.advance(), None);
         scanner.current = 10;
@@ -451,6 +708,8 @@ mod tests {
             start: 0,
             current: 18,
             line: 1,
+            trivia: vec![],
+            trivia_line: 0,
         };
         assert!(!scanner.conditionally_advance(' '));
         scanner.current = 10;
@@ -461,4 +720,28 @@ mod tests {
         assert!(!scanner.conditionally_advance('"'));
         assert_eq!(scanner.current, 12);
     }
+
+    #[test]
+    fn test_scan_trivia() {
+        let source = "// header\nvar a = 1; // one\n\n\n// two\nprint a;\n// end\n";
+        let tokens = Scanner::new(source.to_string()).scan_tokens().unwrap();
+
+        assert_eq!(
+            tokens[0].trivia(),
+            &vec![Trivia::Comment("// header".to_string())]
+        );
+        assert_eq!(
+            tokens[5].trivia(),
+            &vec![
+                Trivia::TrailingComment("// one".to_string()),
+                Trivia::BlankLine,
+                Trivia::Comment("// two".to_string()),
+            ]
+        );
+        assert!(tokens[6].trivia().is_empty());
+        assert_eq!(
+            tokens.last().unwrap().trivia(),
+            &vec![Trivia::Comment("// end".to_string())]
+        );
+    }
 }
@@ -1,13 +1,17 @@
 use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, fmt::Debug};
 
 /// This represents a chunk of a source file, a token.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Token {
     kind: TokenType,
     lexeme: String,
     literal: TokenLiteral,
     line: u32,
+    /// The comments and blank lines found before this token
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    trivia: Vec<Trivia>,
 }
 
 impl Token {
@@ -18,9 +22,16 @@ impl Token {
             lexeme: lexeme.to_string(),
             literal,
             line,
+            trivia: Vec::new(),
         }
     }
 
+    /// Attaches the trivia found before this token
+    pub fn with_trivia(mut self, trivia: Vec<Trivia>) -> Self {
+        self.trivia = trivia;
+        self
+    }
+
     /// Returns the lexeme
     pub fn lexeme(&self) -> String {
         self.lexeme.clone()
@@ -40,6 +51,23 @@ impl Token {
     pub fn line(&self) -> u32 {
         self.line
     }
+
+    /// Returns the trivia found before this token
+    pub fn trivia(&self) -> &Vec<Trivia> {
+        &self.trivia
+    }
+}
+
+/// Represents source text that carries no meaning for the program,
+/// kept so that tools can rewrite the source faithfully
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Trivia {
+    /// A comment on a line of its own, including the leading `//`
+    Comment(String),
+    /// A comment at the end of a line holding the previous token
+    TrailingComment(String),
+    /// One or more empty lines
+    BlankLine,
 }
 
 impl std::fmt::Display for Token {
@@ -49,54 +77,92 @@ impl std::fmt::Display for Token {
 }
 
 /// Represents the different possible token types
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum TokenType {
     // Single-character tokens.
     LeftParen,
     RightParen,
     LeftBrace,
     RightBrace,
+    LeftBracket,
+    RightBracket,
+    Ampersand,
+    Caret,
+    Colon,
     Comma,
     Dot,
-    Minus,
-    Plus,
+    Pipe,
     Semicolon,
-    Slash,
-    Star,
+    Tilde,
 
     // One or two character tokens.
+    Arrow,
     Bang,
     BangEqual,
     Equal,
     EqualEqual,
     Greater,
     GreaterEqual,
+    GreaterGreater,
     Less,
     LessEqual,
+    LessLess,
+    Minus,
+    MinusEqual,
+    Percent,
+    PercentEqual,
+    Plus,
+    PlusEqual,
+    Question,
+    QuestionDot,
+    QuestionQuestion,
+    Slash,
+    SlashEqual,
+    Star,
+    StarEqual,
+    StarStar,
+
+    // Three character tokens.
+    Ellipsis,
 
     // Literals.
     Identifier,
+    /// A name starting with `#`, which only class members can have
+    PrivateIdentifier,
     String,
     Integer,
     Float,
 
     // Keywords.
     And,
+    Break,
+    Catch,
     Class,
+    Const,
+    Continue,
     Else,
+    Enum,
     False,
+    Finally,
     Fun,
     For,
     If,
+    In,
+    Match,
     Nil,
     Or,
     Print,
     Return,
     Super,
     This,
+    Throw,
+    Trait,
     True,
+    Try,
     Var,
     While,
+    With,
+    Yield,
 
     EOF,
 }
@@ -104,21 +170,34 @@ pub enum TokenType {
 pub static KEYWORDS: Lazy<HashMap<&str, TokenType>> = Lazy::new(|| {
     HashMap::from([
         ("and", TokenType::And),
+        ("break", TokenType::Break),
+        ("catch", TokenType::Catch),
         ("class", TokenType::Class),
+        ("const", TokenType::Const),
+        ("continue", TokenType::Continue),
         ("else", TokenType::Else),
+        ("enum", TokenType::Enum),
         ("false", TokenType::False),
+        ("finally", TokenType::Finally),
         ("for", TokenType::For),
         ("fun", TokenType::Fun),
         ("if", TokenType::If),
+        ("in", TokenType::In),
+        ("match", TokenType::Match),
         ("nil", TokenType::Nil),
         ("or", TokenType::Or),
         ("print", TokenType::Print),
         ("return", TokenType::Return),
         ("super", TokenType::Super),
         ("this", TokenType::This),
+        ("throw", TokenType::Throw),
+        ("trait", TokenType::Trait),
         ("true", TokenType::True),
+        ("try", TokenType::Try),
         ("var", TokenType::Var),
         ("while", TokenType::While),
+        ("with", TokenType::With),
+        ("yield", TokenType::Yield),
     ])
 });
 
@@ -126,7 +205,7 @@ pub static KEYWORDS: Lazy<HashMap<&str, TokenType>> = Lazy::new(|| {
 /// This literal is the actual value of the token
 ///
 /// Reserved keywords e.g Identifiers do not have a token literal
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TokenLiteral {
     /// Represents a string literal
     String(String),
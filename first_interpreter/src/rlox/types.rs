@@ -41,7 +41,7 @@ impl Value {
         let boxed = self
             .0
             .downcast()
-            .map_err(|_| Error::report_generic("Failed to convert to concrete type"))?;
+            .map_err(|_| Error::generic("Failed to convert to concrete type"))?;
         Ok(*boxed)
     }
 
@@ -52,7 +52,7 @@ impl Value {
         let t = self
             .0
             .downcast_ref()
-            .map_err(|_| Error::report_generic("Failed to convert to concrete type"))?;
+            .map_err(|_| Error::generic("Failed to convert to concrete type"))?;
         Ok(t)
     }
 
@@ -66,6 +66,7 @@ impl Value {
 mod tests {
     use super::*;
     use crate::{
+        common::NodeId,
         rlox::{native::ClockFunction, token::TokenType, NativeCallable, Token, TokenLiteral},
         stmt::{Class, Function},
     };
@@ -87,6 +88,7 @@ mod tests {
         assert!(!value.is::<i32>());
 
         let value = Value::new(Box::new(Function::new(
+            NodeId::default(),
             Token::new(TokenType::Nil, "", TokenLiteral::Nil, 0),
             vec![],
             vec![],
@@ -0,0 +1,247 @@
+use std::{fmt::Display, rc::Rc};
+
+use crate::{
+    common::Error,
+    rlox::{Arity, Interpreter, NativeCallable, RloxCallable, Token, TokenLiteral, Unwind, Value},
+};
+
+/// Represents an enum at runtime, whose properties are its variants
+///
+/// Cloning an enum clones the reference, so every copy is the same enum
+#[derive(Debug, Clone)]
+pub struct RloxEnum {
+    name: String,
+    /// The name of every variant with the names of its fields
+    variants: Rc<Vec<(String, Rc<Vec<String>>)>>,
+}
+
+impl RloxEnum {
+    /// Constructs a new RloxEnum
+    pub fn new(name: String, variants: Vec<(String, Vec<String>)>) -> RloxEnum {
+        let variants = variants
+            .into_iter()
+            .map(|(variant, fields)| (variant, Rc::new(fields)))
+            .collect();
+        RloxEnum {
+            name,
+            variants: Rc::new(variants),
+        }
+    }
+
+    /// Returns the variant with the given name
+    ///
+    /// A variant with fields is a constructor for its values,
+    /// and a variant without fields is its only value
+    pub fn get(&self, name: &Token) -> Result<Value, Error> {
+        let (variant, fields) = match self
+            .variants
+            .iter()
+            .find(|(variant, _)| *variant == name.lexeme())
+        {
+            Some(variant) => variant.clone(),
+            None => {
+                return Err(Error::runtime(
+                    name.clone(),
+                    &format!("Enum {} has no variant '{}'.", self.name, name.lexeme()),
+                ))
+            }
+        };
+        if fields.is_empty() {
+            return Ok(Value::new(RloxVariant::new(
+                self.name.clone(),
+                variant,
+                fields,
+                Vec::new(),
+            )));
+        }
+        Ok(Value::new(NativeCallable::VariantConstructor(
+            VariantConstructor {
+                enum_name: self.name.clone(),
+                name: variant,
+                fields,
+            },
+        )))
+    }
+}
+
+impl Display for RloxEnum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<enum {}>", self.name)
+    }
+}
+
+/// Represents the native function that makes the values
+/// of a variant with fields, like `Shape.Circle`
+#[derive(Debug, Clone)]
+pub struct VariantConstructor {
+    enum_name: String,
+    name: String,
+    fields: Rc<Vec<String>>,
+}
+
+impl VariantConstructor {
+    /// Returns true if both make the values of the same variant
+    pub fn same(&self, other: &VariantConstructor) -> bool {
+        Rc::ptr_eq(&self.fields, &other.fields) && self.name == other.name
+    }
+}
+
+impl Display for VariantConstructor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<variant {}.{}>", self.enum_name, self.name)
+    }
+}
+
+impl RloxCallable for VariantConstructor {
+    fn arity(&self) -> Arity {
+        Arity::exact(self.fields.len())
+    }
+
+    fn call(&self, _interpreter: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, Unwind> {
+        Ok(Value::new(RloxVariant::new(
+            self.enum_name.clone(),
+            self.name.clone(),
+            self.fields.clone(),
+            arguments,
+        )))
+    }
+}
+
+/// Represents a value of an enum, which is one of its
+/// variants along with the values of the fields of the variant
+#[derive(Debug, Clone)]
+pub struct RloxVariant {
+    enum_name: String,
+    name: String,
+    fields: Rc<Vec<String>>,
+    values: Vec<Value>,
+}
+
+impl RloxVariant {
+    /// Constructs a new RloxVariant
+    fn new(
+        enum_name: String,
+        name: String,
+        fields: Rc<Vec<String>>,
+        values: Vec<Value>,
+    ) -> RloxVariant {
+        RloxVariant {
+            enum_name,
+            name,
+            fields,
+            values,
+        }
+    }
+
+    /// Returns the name of the enum the variant belongs to
+    pub fn enum_name(&self) -> String {
+        self.enum_name.clone()
+    }
+
+    /// Returns the name of the variant
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    /// Returns the values of the fields, in the order they were declared
+    pub fn values(&self) -> Vec<Value> {
+        self.values.clone()
+    }
+
+    /// Returns the value of a field, or the name of the variant
+    /// for the `variant` property
+    pub fn get(&self, name: &Token) -> Result<Value, Error> {
+        if let Some(position) = self.fields.iter().position(|field| *field == name.lexeme()) {
+            return Ok(self.values[position].clone());
+        }
+        if name.lexeme() == "variant" {
+            return Ok(Value::new(TokenLiteral::String(self.name.clone())));
+        }
+        Err(Error::runtime(
+            name.clone(),
+            &format!("Undefined property '{}'.", name.lexeme()),
+        ))
+    }
+
+    /// Returns true if both values are the same variant of the same enum,
+    /// comparing the values of their fields with the given function
+    pub fn equals(&self, other: &RloxVariant, equal: impl Fn(&Value, &Value) -> bool) -> bool {
+        self.enum_name == other.enum_name
+            && self.name == other.name
+            && self.values.len() == other.values.len()
+            && self
+                .values
+                .iter()
+                .zip(other.values.iter())
+                .all(|(left, right)| equal(left, right))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rlox::token::TokenType;
+
+    fn token(lexeme: &str) -> Token {
+        Token::new(TokenType::Identifier, lexeme, TokenLiteral::Nil, 1)
+    }
+
+    fn integer(value: i32) -> Value {
+        Value::new(TokenLiteral::Integer(value))
+    }
+
+    fn literals_equal(left: &Value, right: &Value) -> bool {
+        left.borrowed::<TokenLiteral>().ok() == right.borrowed::<TokenLiteral>().ok()
+    }
+
+    #[test]
+    fn test_variants() {
+        let shape = RloxEnum::new(
+            "Shape".to_string(),
+            vec![
+                ("Circle".to_string(), vec!["r".to_string()]),
+                ("Empty".to_string(), vec![]),
+            ],
+        );
+
+        let empty = shape.get(&token("Empty")).unwrap();
+        let empty = empty.borrowed::<RloxVariant>().unwrap();
+        assert_eq!(empty.name(), "Empty");
+        assert!(empty.values().is_empty());
+
+        let circle = shape.get(&token("Circle")).unwrap();
+        let circle = circle.owned::<NativeCallable>().unwrap();
+        assert_eq!(circle.to_string(), "<variant Shape.Circle>");
+        assert_eq!(circle.arity(), Arity::exact(1));
+
+        assert!(shape.get(&token("Square")).is_err());
+    }
+
+    #[test]
+    fn test_variant_values() {
+        let fields = Rc::new(vec!["w".to_string(), "h".to_string()]);
+        let rect = |w, h| {
+            RloxVariant::new(
+                "Shape".to_string(),
+                "Rect".to_string(),
+                fields.clone(),
+                vec![integer(w), integer(h)],
+            )
+        };
+
+        let value = rect(2, 3).get(&token("h")).unwrap();
+        assert_eq!(
+            value.owned::<TokenLiteral>().unwrap(),
+            TokenLiteral::Integer(3)
+        );
+        let value = rect(2, 3).get(&token("variant")).unwrap();
+        assert_eq!(
+            value.owned::<TokenLiteral>().unwrap(),
+            TokenLiteral::String("Rect".to_string())
+        );
+        assert!(rect(2, 3).get(&token("r")).is_err());
+
+        assert!(rect(2, 3).equals(&rect(2, 3), literals_equal));
+        assert!(!rect(2, 3).equals(&rect(3, 2), literals_equal));
+    }
+}
@@ -1,19 +1,44 @@
+use crate::common::NodeId;
 use crate::stmt::Stmt;
+use serde::{Deserialize, Serialize};
 
 /// Represents a block of statements
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Block {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
     statements: Vec<Stmt>,
+    /// True if the parser built this block by desugaring a `for` loop
+    for_loop: bool,
 }
 
 impl Block {
     /// Construct a new Block
-    pub fn new(statements: Vec<Stmt>) -> Block {
-        Block { statements }
+    pub fn new(id: NodeId, statements: Vec<Stmt>) -> Block {
+        Block {
+            id,
+            statements,
+            for_loop: false,
+        }
+    }
+
+    /// Construct a new Block holding a desugared `for` loop, that is
+    /// an optional initializer followed by a While statement
+    pub fn for_loop(id: NodeId, statements: Vec<Stmt>) -> Block {
+        Block {
+            id,
+            statements,
+            for_loop: true,
+        }
     }
 
     /// Return the embedded statements
     pub fn statements(&self) -> Vec<Stmt> {
         self.statements.clone()
     }
+
+    /// Returns true if the block is a desugared `for` loop
+    pub fn is_for_loop(&self) -> bool {
+        self.for_loop
+    }
 }
@@ -0,0 +1,30 @@
+use crate::common::NodeId;
+use crate::rlox::Token;
+use serde::{Deserialize, Serialize};
+
+/// Represents a Break statement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Break {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
+    keyword: Token,
+    /// The label of the loop to leave, the innermost loop if None
+    label: Option<Token>,
+}
+
+impl Break {
+    /// Construct a new Break statement
+    pub fn new(id: NodeId, keyword: Token, label: Option<Token>) -> Break {
+        Break { id, keyword, label }
+    }
+
+    /// Returns the keyword
+    pub fn keyword(&self) -> Token {
+        self.keyword.clone()
+    }
+
+    /// Returns the label
+    pub fn label(&self) -> Option<Token> {
+        self.label.clone()
+    }
+}
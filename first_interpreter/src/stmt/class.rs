@@ -1,50 +1,276 @@
+use std::{cell::RefCell, collections::HashMap, fmt::Display, rc::Rc};
+
 use crate::common::Error;
+use crate::common::NodeId;
 use crate::rlox::interpreter::Interpreter;
-use crate::rlox::{RloxCallable, Value};
-use crate::stmt::Function;
+use crate::rlox::{Arity, RloxCallable, RloxInstance, Unwind, Value};
+use crate::stmt::{Function, RloxFunction, Var};
 use crate::{expressions::Variable, rlox::Token};
+use serde::{Deserialize, Serialize};
 
-/// Represents a Class
+/// Represents a wrapper over the Class to keep the
+/// interpreter logic separate from the front-end's syntax classes
+///
+/// Cloning a class clones the reference, so every copy is the same class
 #[derive(Debug, Clone)]
+pub struct RloxClass {
+    name: String,
+    superclass: Option<Rc<RloxClass>>,
+    methods: Rc<HashMap<String, RloxFunction>>,
+    class_methods: Rc<HashMap<String, RloxFunction>>,
+    /// The fields of the class itself, shared by all of its instances
+    fields: Rc<RefCell<HashMap<String, Value>>>,
+}
+
+impl RloxClass {
+    /// Constructs a new RloxClass without fields
+    pub fn new(
+        name: String,
+        superclass: Option<RloxClass>,
+        methods: HashMap<String, RloxFunction>,
+        class_methods: HashMap<String, RloxFunction>,
+    ) -> RloxClass {
+        RloxClass {
+            name,
+            superclass: superclass.map(Rc::new),
+            methods: Rc::new(methods),
+            class_methods: Rc::new(class_methods),
+            fields: Rc::new(RefCell::new(HashMap::new())),
+        }
+    }
+
+    /// Returns the name
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    /// Returns the method with the given name, looking
+    /// through the superclasses if the class does not have it
+    pub fn find_method(&self, name: &str) -> Option<RloxFunction> {
+        match self.methods.get(name) {
+            Some(method) => Some(method.clone()),
+            None => self
+                .superclass
+                .as_ref()
+                .and_then(|superclass| superclass.find_method(name)),
+        }
+    }
+
+    /// Returns the class method with the given name, looking
+    /// through the superclasses if the class does not have it
+    pub fn find_class_method(&self, name: &str) -> Option<RloxFunction> {
+        match self.class_methods.get(name) {
+            Some(method) => Some(method.clone()),
+            None => self
+                .superclass
+                .as_ref()
+                .and_then(|superclass| superclass.find_class_method(name)),
+        }
+    }
+
+    /// Returns the value of a property of the class itself
+    ///
+    /// Fields shadow class methods, and class methods are bound to the
+    /// class. Both are looked up through the superclasses.
+    pub fn get(&self, name: &Token) -> Result<Value, Error> {
+        if let Some(value) = self.field(&name.lexeme()) {
+            return Ok(value);
+        }
+        if let Some(method) = self.find_class_method(&name.lexeme()) {
+            return Ok(Value::new(method.bind_class(self.clone())));
+        }
+        Err(Error::runtime(
+            name.clone(),
+            &format!("Undefined property '{}'.", name.lexeme()),
+        ))
+    }
+
+    /// Returns the class getter for a property, bound to the
+    /// class, unless a field of the class shadows it
+    pub fn getter(&self, name: &Token) -> Option<RloxFunction> {
+        if self.field(&name.lexeme()).is_some() {
+            return None;
+        }
+        self.find_class_method(&name.lexeme())
+            .filter(RloxFunction::is_getter)
+            .map(|getter| getter.bind_class(self.clone()))
+    }
+
+    /// Sets the value of a field of the class, creating the field if needed
+    pub fn set(&self, name: &Token, value: Value) {
+        self.fields.borrow_mut().insert(name.lexeme(), value);
+    }
+
+    /// Returns the value of a field of the class or of its superclasses
+    fn field(&self, name: &str) -> Option<Value> {
+        if let Some(value) = self.fields.borrow().get(name) {
+            return Some(value.clone());
+        }
+        self.superclass
+            .as_ref()
+            .and_then(|superclass| superclass.field(name))
+    }
+
+    /// Returns the class that defines the method with the given
+    /// name, which is this class or one of its superclasses
+    pub fn method_class(&self, name: &str) -> Option<RloxClass> {
+        match self.methods.contains_key(name) {
+            true => Some(self.clone()),
+            false => self
+                .superclass
+                .as_ref()
+                .and_then(|superclass| superclass.method_class(name)),
+        }
+    }
+
+    /// Returns the class that declares a field or a class method of
+    /// the class itself, which is this class or one of its superclasses
+    pub fn declaring_class(&self, name: &str) -> Option<RloxClass> {
+        if self.fields.borrow().contains_key(name) || self.class_methods.contains_key(name) {
+            return Some(self.clone());
+        }
+        self.superclass
+            .as_ref()
+            .and_then(|superclass| superclass.declaring_class(name))
+    }
+
+    /// Returns true if both values are the same class
+    pub fn same(&self, other: &RloxClass) -> bool {
+        Rc::ptr_eq(&self.methods, &other.methods)
+    }
+
+    /// Returns true if the class is the other class or one of its subclasses
+    pub fn inherits_from(&self, other: &RloxClass) -> bool {
+        self.same(other)
+            || self
+                .superclass
+                .as_ref()
+                .is_some_and(|superclass| superclass.inherits_from(other))
+    }
+}
+
+impl Display for RloxClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<class {}>", self.name)
+    }
+}
+
+impl RloxCallable for RloxClass {
+    fn arity(&self) -> Arity {
+        match self.find_method("init") {
+            Some(initializer) => initializer.arity(),
+            None => Arity::exact(0),
+        }
+    }
+
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, Unwind> {
+        let instance = RloxInstance::new(self.clone());
+        if let Some(initializer) = self.find_method("init") {
+            initializer
+                .bind(instance.clone())
+                .call(interpreter, arguments)?;
+        }
+        Ok(Value::new(instance))
+    }
+
+    fn call_with_named(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<Value>,
+        named: Vec<(Token, Value)>,
+        paren: &Token,
+    ) -> Result<Value, Unwind> {
+        let instance = RloxInstance::new(self.clone());
+        match self.find_method("init") {
+            Some(initializer) => {
+                initializer.bind(instance.clone()).call_with_named(
+                    interpreter,
+                    arguments,
+                    named,
+                    paren,
+                )?;
+            }
+            // without an initializer the class takes no parameters at all
+            None => {
+                if let Some((name, _)) = named.first() {
+                    return Err(Error::runtime(
+                        name.clone(),
+                        &format!("Unknown parameter '{}'.", name.lexeme()),
+                    )
+                    .into());
+                }
+            }
+        }
+        Ok(Value::new(instance))
+    }
+}
+
+/// Represents a Class
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Class {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
     name: Token,
-    superclass: Variable,
-    methods: Function,
+    superclass: Option<Variable>,
+    /// The traits whose methods the class is declared with
+    traits: Vec<Variable>,
+    /// The fields of the class itself, which come before the methods
+    fields: Vec<Var>,
+    methods: Vec<Function>,
 }
 
 impl Class {
     /// Construct a new Class
-    pub fn new(name: Token, superclass: Variable, methods: Function) -> Class {
+    pub fn new(
+        id: NodeId,
+        name: Token,
+        superclass: Option<Variable>,
+        methods: Vec<Function>,
+    ) -> Class {
         Class {
+            id,
             name,
             superclass,
+            traits: Vec::new(),
+            fields: Vec::new(),
             methods,
         }
     }
 
+    /// Sets the traits the class is declared with
+    pub fn with_traits(mut self, traits: Vec<Variable>) -> Class {
+        self.traits = traits;
+        self
+    }
+
+    /// Sets the fields of the class itself
+    pub fn with_fields(mut self, fields: Vec<Var>) -> Class {
+        self.fields = fields;
+        self
+    }
+
     /// Returns the name
     pub fn name(&self) -> Token {
         self.name.clone()
     }
 
     /// Returns the superclass
-    pub fn superclass(&self) -> Variable {
+    pub fn superclass(&self) -> Option<Variable> {
         self.superclass.clone()
     }
 
-    /// Returns the methods
-    pub fn methods(&self) -> Function {
-        self.methods.clone()
+    /// Returns the traits, in the order they were listed
+    pub fn traits(&self) -> Vec<Variable> {
+        self.traits.clone()
     }
-}
 
-impl RloxCallable for Class {
-    fn arity(&self) -> usize {
-        0
+    /// Returns the fields of the class itself
+    pub fn fields(&self) -> Vec<Var> {
+        self.fields.clone()
     }
 
-    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, Error> {
-        println!("Class called");
-        Ok(Value::new(0))
+    /// Returns the methods, including class methods and getters
+    pub fn methods(&self) -> Vec<Function> {
+        self.methods.clone()
     }
 }
@@ -0,0 +1,30 @@
+use crate::common::NodeId;
+use crate::rlox::Token;
+use serde::{Deserialize, Serialize};
+
+/// Represents a Continue statement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Continue {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
+    keyword: Token,
+    /// The label of the loop to continue, the innermost loop if None
+    label: Option<Token>,
+}
+
+impl Continue {
+    /// Construct a new Continue statement
+    pub fn new(id: NodeId, keyword: Token, label: Option<Token>) -> Continue {
+        Continue { id, keyword, label }
+    }
+
+    /// Returns the keyword
+    pub fn keyword(&self) -> Token {
+        self.keyword.clone()
+    }
+
+    /// Returns the label
+    pub fn label(&self) -> Option<Token> {
+        self.label.clone()
+    }
+}
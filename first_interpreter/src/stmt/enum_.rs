@@ -0,0 +1,67 @@
+use std::fmt;
+
+use crate::common::NodeId;
+use crate::rlox::Token;
+use serde::{Deserialize, Serialize};
+
+/// Represents one variant of an Enum with the names of its fields
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnumVariant {
+    name: Token,
+    // a variant without fields is a value rather than a constructor
+    fields: Vec<Token>,
+}
+
+impl EnumVariant {
+    /// Construct a new EnumVariant
+    pub fn new(name: Token, fields: Vec<Token>) -> EnumVariant {
+        EnumVariant { name, fields }
+    }
+
+    /// Returns the name
+    pub fn name(&self) -> &Token {
+        &self.name
+    }
+
+    /// Returns the names of the fields
+    pub fn fields(&self) -> Vec<Token> {
+        self.fields.clone()
+    }
+}
+
+/// Prints the variant the way it is written in source code
+impl fmt::Display for EnumVariant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.fields.is_empty() {
+            return write!(f, "{}", self.name.lexeme());
+        }
+        let fields: Vec<String> = self.fields.iter().map(Token::lexeme).collect();
+        write!(f, "{}({})", self.name.lexeme(), fields.join(", "))
+    }
+}
+
+/// Represents an Enum declaration, like `enum Shape { Circle(r), Empty }`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Enum {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
+    name: Token,
+    variants: Vec<EnumVariant>,
+}
+
+impl Enum {
+    /// Construct a new Enum
+    pub fn new(id: NodeId, name: Token, variants: Vec<EnumVariant>) -> Enum {
+        Enum { id, name, variants }
+    }
+
+    /// Returns the name
+    pub fn name(&self) -> Token {
+        self.name.clone()
+    }
+
+    /// Returns the variants
+    pub fn variants(&self) -> Vec<EnumVariant> {
+        self.variants.clone()
+    }
+}
@@ -1,15 +1,19 @@
+use crate::common::NodeId;
 use crate::expressions::Expr;
+use serde::{Deserialize, Serialize};
 
 /// Represents an Expression
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Expression {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
     expression: Expr,
 }
 
 impl Expression {
     /// Construct a new Expression
-    pub fn new(expression: Expr) -> Expression {
-        Expression { expression }
+    pub fn new(id: NodeId, expression: Expr) -> Expression {
+        Expression { id, expression }
     }
 
     /// Return the expression
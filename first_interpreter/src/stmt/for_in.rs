@@ -0,0 +1,62 @@
+use crate::common::NodeId;
+use crate::{expressions::Expr, rlox::Token, stmt::Stmt};
+use serde::{Deserialize, Serialize};
+
+/// Represents a for-in statement, like `for (var x in xs) body`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForIn {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
+    /// The variable every element is bound to
+    name: Token,
+    /// The `in` keyword, where errors about the iterable are reported
+    keyword: Token,
+    iterable: Expr,
+    body: Box<Stmt>,
+    /// The label break and continue statements can name the loop by
+    label: Option<Token>,
+}
+
+impl ForIn {
+    /// Construct a new ForIn statement
+    pub fn new(id: NodeId, name: Token, keyword: Token, iterable: Expr, body: Box<Stmt>) -> ForIn {
+        ForIn {
+            id,
+            name,
+            keyword,
+            iterable,
+            body,
+            label: None,
+        }
+    }
+
+    /// Returns the same loop, named by the given label
+    pub fn labeled(self, label: Option<Token>) -> ForIn {
+        ForIn { label, ..self }
+    }
+
+    /// Returns the name of the loop variable
+    pub fn name(&self) -> Token {
+        self.name.clone()
+    }
+
+    /// Returns the `in` keyword
+    pub fn keyword(&self) -> Token {
+        self.keyword.clone()
+    }
+
+    /// Returns the expression iterated over
+    pub fn iterable(&self) -> Expr {
+        self.iterable.clone()
+    }
+
+    /// Returns the body
+    pub fn body(&self) -> Box<Stmt> {
+        self.body.clone()
+    }
+
+    /// Returns the label
+    pub fn label(&self) -> Option<Token> {
+        self.label.clone()
+    }
+}
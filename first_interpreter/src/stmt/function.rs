@@ -1,13 +1,16 @@
 use std::{cell::RefCell, fmt::Display, rc::Rc};
 
+use crate::common::NodeId;
 use crate::{
     common::Error,
+    expressions::Expr,
     rlox::{
-        environment::Environment, interpreter::Interpreter, RloxCallable, Token, TokenLiteral,
-        Value,
+        environment::Environment, interpreter::Interpreter, token::TokenType, Arity, Completion,
+        RloxCallable, RloxGenerator, RloxInstance, RloxList, Token, TokenLiteral, Unwind, Value,
     },
-    stmt::Stmt,
+    stmt::{RloxClass, Stmt},
 };
+use serde::{Deserialize, Serialize};
 
 /// Represents a wrapper over the Function to keep the
 /// interpreter logic separate from the front-end's syntax classes
@@ -15,54 +18,307 @@ use crate::{
 pub struct RloxFunction {
     declaration: Function,
     closure: Rc<RefCell<Environment>>,
+    /// Initializers always return the instance they were called on
+    is_initializer: bool,
 }
 
 impl RloxFunction {
-    pub fn new(declaration: Function, closure: Rc<RefCell<Environment>>) -> RloxFunction {
+    pub fn new(
+        declaration: Function,
+        closure: Rc<RefCell<Environment>>,
+        is_initializer: bool,
+    ) -> RloxFunction {
         RloxFunction {
             declaration,
             closure,
+            is_initializer,
         }
     }
+
+    /// Returns true if both are the same declaration closed over the same scope
+    pub fn same(&self, other: &RloxFunction) -> bool {
+        self.declaration.id() == other.declaration.id() && Rc::ptr_eq(&self.closure, &other.closure)
+    }
+
+    /// Returns a copy of the method with `this` bound to the instance
+    pub fn bind(&self, instance: RloxInstance) -> RloxFunction {
+        self.bind_this(Value::new(instance))
+    }
+
+    /// Returns a copy of the class method with `this` bound to the class
+    pub fn bind_class(&self, class: RloxClass) -> RloxFunction {
+        self.bind_this(Value::new(class))
+    }
+
+    /// Returns true if the method runs when its property is read
+    pub fn is_getter(&self) -> bool {
+        self.declaration.is_getter()
+    }
+
+    /// Returns a copy of the function with `this` bound to the value
+    fn bind_this(&self, this: Value) -> RloxFunction {
+        let mut environment = Environment::with_parent(self.closure.clone());
+        environment.define("this".to_string(), this);
+        RloxFunction::new(
+            self.declaration.clone(),
+            Rc::new(RefCell::new(environment)),
+            self.is_initializer,
+        )
+    }
+
+    /// Matches positional arguments to the parameters in order, returning
+    /// the argument for each parameter other than the rest one, and the
+    /// arguments left over for it
+    fn arrange(&self, arguments: Vec<Value>) -> (Vec<Option<Value>>, Vec<Value>) {
+        let count = self.declaration.positional_params().len();
+        let mut arguments = arguments.into_iter();
+        let slots = (0..count).map(|_| arguments.next()).collect();
+        (slots, arguments.collect())
+    }
+
+    /// Runs the body with every parameter bound, using the default
+    /// value of each parameter that was not given an argument
+    ///
+    /// Defaults are evaluated when the call is made, in the function's
+    /// own scope, so they can refer to the parameters before them.
+    fn invoke(
+        &self,
+        interpreter: &mut Interpreter,
+        slots: Vec<Option<Value>>,
+        rest: Vec<Value>,
+    ) -> Result<Value, Unwind> {
+        let environment = Rc::new(RefCell::new(Environment::with_parent(self.closure.clone())));
+        for (param, slot) in self.declaration.positional_params().iter().zip(slots) {
+            let value = match (slot, param.default()) {
+                (Some(value), _) => value,
+                (None, Some(default)) => interpreter.evaluate_in(default, environment.clone())?,
+                (None, None) => unreachable!("missing arguments are checked before the call"),
+            };
+            environment
+                .borrow_mut()
+                .define(param.name().lexeme(), value);
+        }
+        if let Some(param) = self.declaration.rest_param() {
+            let list = Value::new(RloxList::new(rest));
+            environment.borrow_mut().define(param.name().lexeme(), list);
+        }
+        // a generator runs none of its body until `next()` is called on it
+        if self.declaration.is_generator() {
+            let generator = RloxGenerator::new(self.name(), self.declaration.body(), environment);
+            return Ok(Value::new(generator));
+        }
+
+        match interpreter.execute_block(self.declaration.body(), environment)? {
+            // the resolver only allows an initializer to return without a value
+            Completion::Return(_) if self.is_initializer => self.this(),
+            Completion::Return(ret_val) => Ok(ret_val),
+            // the exception keeps unwinding through the expression that made the call
+            completion @ Completion::Throw(_) => Err(Unwind::Throw(completion)),
+            // break and continue never escape the loop they are in
+            Completion::Normal | Completion::Break(_) | Completion::Continue(_) => {
+                match self.is_initializer {
+                    true => self.this(),
+                    false => Ok(Value::new(TokenLiteral::Nil)),
+                }
+            }
+        }
+    }
+
+    /// Returns the name the function is printed with
+    fn name(&self) -> String {
+        match self.declaration.name.kind() {
+            TokenType::Identifier | TokenType::PrivateIdentifier => self.declaration.name.lexeme(),
+            // anonymous functions are named by their `fun` keyword or arrow
+            _ => "anonymous".to_string(),
+        }
+    }
+
+    /// Returns the instance an initializer was bound to
+    fn this(&self) -> Result<Value, Unwind> {
+        let this = Token::new(
+            TokenType::This,
+            "this",
+            TokenLiteral::Nil,
+            self.declaration.name.line(),
+        );
+        Ok(self.closure.borrow().get_at(0, &this)?)
+    }
 }
 
 impl Display for RloxFunction {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "<fn {}>", self.declaration.name.lexeme())
+        write!(f, "<fn {}>", self.name())
     }
 }
 
 impl RloxCallable for RloxFunction {
-    fn arity(&self) -> usize {
-        self.declaration.params.len()
+    fn arity(&self) -> Arity {
+        let params = self.declaration.positional_params();
+        let required = params.iter().filter(|param| param.default().is_none());
+        match self.declaration.rest_param() {
+            Some(_) => Arity::at_least(required.count()),
+            None => Arity::range(required.count(), params.len()),
+        }
     }
 
-    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, Error> {
-        let mut environment = Environment::with_parent(self.closure.clone());
-        for i in 0..self.declaration.params.len() {
-            environment.define(self.declaration.params[i].lexeme(), arguments[i].clone())
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, Unwind> {
+        let (slots, rest) = self.arrange(arguments);
+        self.invoke(interpreter, slots, rest)
+    }
+
+    fn call_with_named(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<Value>,
+        named: Vec<(Token, Value)>,
+        paren: &Token,
+    ) -> Result<Value, Unwind> {
+        let arity = self.arity();
+        if arity.max().is_some_and(|max| arguments.len() > max) {
+            return Err(Error::runtime(
+                paren.clone(),
+                &format!(
+                    "Expected {} arguments but got {}",
+                    arity,
+                    arguments.len() + named.len()
+                ),
+            )
+            .into());
+        }
+
+        let params = self.declaration.positional_params();
+        let (mut slots, rest) = self.arrange(arguments);
+        for (name, value) in named {
+            match params
+                .iter()
+                .position(|param| param.name().lexeme() == name.lexeme())
+            {
+                Some(i) if slots[i].is_none() => slots[i] = Some(value),
+                Some(_) => {
+                    return Err(Error::runtime(
+                        name.clone(),
+                        &format!("Parameter '{}' was given more than once.", name.lexeme()),
+                    )
+                    .into())
+                }
+                None => {
+                    return Err(Error::runtime(
+                        name.clone(),
+                        &format!("Unknown parameter '{}'.", name.lexeme()),
+                    )
+                    .into())
+                }
+            }
         }
-        match interpreter.execute_block(self.declaration.body(), Rc::new(RefCell::new(environment)))
-        {
-            Ok(_) => Ok(Value::new(TokenLiteral::Nil)),
-            Err(Error::Return(ret_val)) => Ok(ret_val),
-            Err(err) => Err(err),
+        let missing = params
+            .iter()
+            .zip(&slots)
+            .find(|(param, slot)| slot.is_none() && param.default().is_none());
+        if let Some((param, _)) = missing {
+            return Err(Error::runtime(
+                paren.clone(),
+                &format!(
+                    "Missing argument for parameter '{}'.",
+                    param.name().lexeme()
+                ),
+            )
+            .into());
         }
+        self.invoke(interpreter, slots, rest)
+    }
+}
+
+/// Represents a parameter of a function, like `a`, `b = 2` or `...rest`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Param {
+    name: Token,
+    /// The value the parameter takes when the call gives no argument for it
+    default: Option<Expr>,
+    /// Rest parameters collect the arguments left over into a list
+    is_rest: bool,
+}
+
+impl Param {
+    /// Constructs a new Param that takes an argument
+    pub fn new(name: Token, default: Option<Expr>) -> Param {
+        Param {
+            name,
+            default,
+            is_rest: false,
+        }
+    }
+
+    /// Constructs a new rest Param
+    pub fn rest(name: Token) -> Param {
+        Param {
+            name,
+            default: None,
+            is_rest: true,
+        }
+    }
+
+    /// Returns the name
+    pub fn name(&self) -> Token {
+        self.name.clone()
+    }
+
+    /// Returns the default value
+    pub fn default(&self) -> Option<Expr> {
+        self.default.clone()
+    }
+
+    /// Returns true if the parameter collects the arguments left over
+    pub fn is_rest(&self) -> bool {
+        self.is_rest
     }
 }
 
 /// Represents a Function
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Function {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
     name: Token,
-    params: Vec<Token>,
+    params: Vec<Param>,
     body: Vec<Stmt>,
+    /// Class methods are called on the class rather than its instances
+    is_class_method: bool,
+    /// Getters are written without parameters and run when the property is read
+    is_getter: bool,
+    /// Generators run their body one `yield` at a time instead of when called
+    is_generator: bool,
 }
 
 impl Function {
     /// Construct a new Function
-    pub fn new(name: Token, params: Vec<Token>, body: Vec<Stmt>) -> Function {
-        Function { name, params, body }
+    pub fn new(id: NodeId, name: Token, params: Vec<Param>, body: Vec<Stmt>) -> Function {
+        Function {
+            id,
+            name,
+            params,
+            body,
+            is_class_method: false,
+            is_getter: false,
+            is_generator: false,
+        }
+    }
+
+    /// Marks the method as a class method
+    pub fn class_method(mut self) -> Function {
+        self.is_class_method = true;
+        self
+    }
+
+    /// Marks the method as a getter
+    pub fn getter(mut self) -> Function {
+        self.is_getter = true;
+        self
+    }
+
+    /// Marks the function as a generator
+    pub fn generator(mut self) -> Function {
+        self.is_generator = true;
+        self
     }
 
     /// Return the name of the function
@@ -71,12 +327,46 @@ impl Function {
     }
 
     /// Return the parameters of the function
-    pub fn params(&self) -> Vec<Token> {
+    pub fn params(&self) -> Vec<Param> {
         self.params.clone()
     }
 
+    /// Return the parameters that are not the rest parameter
+    pub fn positional_params(&self) -> Vec<Param> {
+        self.params
+            .iter()
+            .filter(|param| !param.is_rest())
+            .cloned()
+            .collect()
+    }
+
+    /// Return the rest parameter, which the parser only allows last
+    pub fn rest_param(&self) -> Option<Param> {
+        self.params.last().filter(|param| param.is_rest()).cloned()
+    }
+
     /// Return the body of the function
     pub fn body(&self) -> Vec<Stmt> {
         self.body.clone()
     }
+
+    /// Returns true if the method is called on the class
+    pub fn is_class_method(&self) -> bool {
+        self.is_class_method
+    }
+
+    /// Returns true if the method runs when its property is read
+    pub fn is_getter(&self) -> bool {
+        self.is_getter
+    }
+
+    /// Returns true if calling the function makes a generator
+    pub fn is_generator(&self) -> bool {
+        self.is_generator
+    }
+
+    /// Returns the id the parser gave this node
+    pub fn id(&self) -> NodeId {
+        self.id
+    }
 }
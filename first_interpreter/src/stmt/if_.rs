@@ -1,8 +1,14 @@
-use crate::{expressions::Expr, stmt::Stmt};
+use crate::common::NodeId;
+use crate::{expressions::Expr, rlox::Token, stmt::Stmt};
+use serde::{Deserialize, Serialize};
 
 /// Represents an if statement
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct If {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
+    /// The `if` keyword, where errors about the condition are reported
+    keyword: Token,
     condition: Expr,
     then_branch: Box<Stmt>,
     // not all if constructs have an else branch
@@ -11,14 +17,27 @@ pub struct If {
 
 impl If {
     /// Construct a new If statement
-    pub fn new(condition: Expr, then_branch: Stmt, else_branch: Option<Stmt>) -> If {
+    pub fn new(
+        id: NodeId,
+        keyword: Token,
+        condition: Expr,
+        then_branch: Stmt,
+        else_branch: Option<Stmt>,
+    ) -> If {
         If {
+            id,
+            keyword,
             condition,
             then_branch: Box::new(then_branch),
             else_branch: else_branch.map(|b| Box::new(b)),
         }
     }
 
+    /// Return the `if` keyword
+    pub fn keyword(&self) -> Token {
+        self.keyword.clone()
+    }
+
     /// Return the condition
     pub fn condition(&self) -> Expr {
         self.condition.clone()
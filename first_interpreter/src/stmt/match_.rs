@@ -0,0 +1,425 @@
+use std::fmt;
+
+use crate::common::{Error, NodeId};
+use crate::{
+    expressions::Expr,
+    rlox::{
+        token::TokenType, RloxInstance, RloxList, RloxMap, RloxVariant, Token, TokenLiteral, Value,
+    },
+    stmt::Stmt,
+};
+use serde::{Deserialize, Serialize};
+
+/// Represents the shape a value is compared against in a match arm
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Pattern {
+    /// `_`, which matches any value without binding it
+    Wildcard(Token),
+    /// A literal like `1`, `-2.5`, `"a"`, `true` or `nil`,
+    /// which matches values equal to it
+    Literal { token: Token, value: TokenLiteral },
+    /// A name, which matches any value and binds it to the name
+    Binding(Token),
+    /// Patterns separated by `|`, which match when any of them does
+    Alternative(Vec<Pattern>),
+    /// `[first, second, ...rest]`, which matches lists element by element
+    List {
+        bracket: Token,
+        elements: Vec<Pattern>,
+        // without a rest pattern the list must have exactly as many elements
+        rest: Option<Token>,
+    },
+    /// `{"key": pattern, name}`, which matches maps having every key listed
+    /// and instances having every property listed
+    Map {
+        brace: Token,
+        entries: Vec<(Token, Pattern)>,
+    },
+    /// `Shape.Circle(r)` or `Circle(r)`, which matches values of the variant,
+    /// field by field when the fields are listed
+    Variant {
+        // without the enum name, variants of any enum with that name match
+        enum_name: Option<Token>,
+        name: Token,
+        fields: Option<Vec<Pattern>>,
+    },
+}
+
+impl Pattern {
+    /// Returns the token the pattern starts with
+    pub fn token(&self) -> &Token {
+        match self {
+            Pattern::Wildcard(token) | Pattern::Literal { token, .. } | Pattern::Binding(token) => {
+                token
+            }
+            Pattern::Alternative(alternatives) => alternatives
+                .first()
+                .expect("the parser only builds alternatives from two patterns or more")
+                .token(),
+            Pattern::List { bracket, .. } => bracket,
+            Pattern::Map { brace, .. } => brace,
+            Pattern::Variant {
+                enum_name, name, ..
+            } => enum_name.as_ref().unwrap_or(name),
+        }
+    }
+
+    /// Returns the names the pattern binds, in the order they appear
+    pub fn bindings(&self) -> Vec<Token> {
+        match self {
+            Pattern::Wildcard(_) | Pattern::Literal { .. } => vec![],
+            Pattern::Binding(name) => vec![name.clone()],
+            Pattern::Alternative(alternatives) => {
+                alternatives.iter().flat_map(Pattern::bindings).collect()
+            }
+            Pattern::List { elements, rest, .. } => {
+                let mut names: Vec<Token> = elements.iter().flat_map(Pattern::bindings).collect();
+                if let Some(rest) = rest.as_ref().filter(|rest| rest.lexeme() != "_") {
+                    names.push(rest.clone());
+                }
+                names
+            }
+            Pattern::Map { entries, .. } => entries
+                .iter()
+                .flat_map(|(_, pattern)| pattern.bindings())
+                .collect(),
+            Pattern::Variant { fields, .. } => fields
+                .iter()
+                .flatten()
+                .flat_map(Pattern::bindings)
+                .collect(),
+        }
+    }
+
+    /// Returns true if the value matches the pattern, adding the values
+    /// of the names the pattern binds to the bindings
+    ///
+    /// Bindings added before a nested pattern fails to match are left
+    /// in place, so callers should discard them when this returns false
+    pub fn matches(
+        &self,
+        value: &Value,
+        bindings: &mut Vec<(Token, Value)>,
+    ) -> Result<bool, Error> {
+        match self {
+            Pattern::Wildcard(_) => Ok(true),
+            Pattern::Literal { value: literal, .. } => {
+                Ok(value.borrowed::<TokenLiteral>().ok() == Some(literal))
+            }
+            Pattern::Binding(name) => {
+                bindings.push((name.clone(), value.clone()));
+                Ok(true)
+            }
+            Pattern::Alternative(alternatives) => {
+                for alternative in alternatives {
+                    if alternative.matches(value, bindings)? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+            Pattern::List { elements, rest, .. } => {
+                let list = match value.borrowed::<RloxList>() {
+                    Ok(list) => list.elements(),
+                    Err(_) => return Ok(false),
+                };
+                let fits = match rest {
+                    Some(_) => list.len() >= elements.len(),
+                    None => list.len() == elements.len(),
+                };
+                if !fits {
+                    return Ok(false);
+                }
+                for (element, value) in elements.iter().zip(list.iter()) {
+                    if !element.matches(value, bindings)? {
+                        return Ok(false);
+                    }
+                }
+                if let Some(rest) = rest.as_ref().filter(|rest| rest.lexeme() != "_") {
+                    let remaining = list[elements.len()..].to_vec();
+                    bindings.push((rest.clone(), Value::new(RloxList::new(remaining))));
+                }
+                Ok(true)
+            }
+            Pattern::Map { brace, entries } => {
+                for (key, pattern) in entries {
+                    let entry = match entry(value, brace, key) {
+                        Ok(entry) => entry,
+                        // a value without the key or of another type does not match
+                        Err(_) => return Ok(false),
+                    };
+                    if !pattern.matches(&entry, bindings)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            Pattern::Variant {
+                enum_name,
+                name,
+                fields,
+            } => {
+                let variant = match value.borrowed::<RloxVariant>() {
+                    Ok(variant) => variant,
+                    Err(_) => return Ok(false),
+                };
+                let same_enum = enum_name
+                    .as_ref()
+                    .is_none_or(|enum_name| enum_name.lexeme() == variant.enum_name());
+                if !same_enum || name.lexeme() != variant.name() {
+                    return Ok(false);
+                }
+                let fields = match fields {
+                    Some(fields) => fields,
+                    None => return Ok(true),
+                };
+                let values = variant.values();
+                if fields.len() != values.len() {
+                    return Ok(false);
+                }
+                for (field, value) in fields.iter().zip(values.iter()) {
+                    if !field.matches(value, bindings)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+        }
+    }
+
+    /// Adds the values of the names the pattern binds to the bindings,
+    /// failing if the value does not have the shape of the pattern
+    ///
+    /// Unlike matching, destructuring a list only needs the list to
+    /// have enough elements, so elements past the pattern are ignored
+    pub fn destructure(
+        &self,
+        value: &Value,
+        bindings: &mut Vec<(Token, Value)>,
+    ) -> Result<(), Error> {
+        match self {
+            Pattern::Wildcard(_) => Ok(()),
+            Pattern::Binding(name) => {
+                bindings.push((name.clone(), value.clone()));
+                Ok(())
+            }
+            Pattern::Literal { .. } | Pattern::Alternative(_) | Pattern::Variant { .. } => {
+                match self.matches(value, bindings)? {
+                    true => Ok(()),
+                    false => Err(Error::runtime(
+                        self.token().clone(),
+                        &format!("Value does not match the pattern '{}'.", self),
+                    )),
+                }
+            }
+            Pattern::List {
+                bracket,
+                elements,
+                rest,
+            } => {
+                let list = match value.borrowed::<RloxList>() {
+                    Ok(list) => list.elements(),
+                    Err(_) => {
+                        return Err(Error::runtime(
+                            bracket.clone(),
+                            "Only lists can be destructured with a list pattern.",
+                        ))
+                    }
+                };
+                if list.len() < elements.len() {
+                    return Err(Error::runtime(
+                        bracket.clone(),
+                        &format!(
+                            "Expected at least {} elements to destructure but got {}.",
+                            elements.len(),
+                            list.len()
+                        ),
+                    ));
+                }
+                for (element, value) in elements.iter().zip(list.iter()) {
+                    element.destructure(value, bindings)?;
+                }
+                if let Some(rest) = rest.as_ref().filter(|rest| rest.lexeme() != "_") {
+                    let remaining = list[elements.len()..].to_vec();
+                    bindings.push((rest.clone(), Value::new(RloxList::new(remaining))));
+                }
+                Ok(())
+            }
+            Pattern::Map { brace, entries } => {
+                for (key, pattern) in entries {
+                    pattern.destructure(&entry(value, brace, key)?, bindings)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Returns the value a key of a map pattern names in a map or an instance,
+/// failing if the value is neither or does not have the key
+fn entry(value: &Value, brace: &Token, key: &Token) -> Result<Value, Error> {
+    if let Ok(map) = value.borrowed::<RloxMap>() {
+        let key_value = Value::new(key_literal(key));
+        return match map.has(key_value.clone(), key)? {
+            true => map.get(key_value, key),
+            false => Err(Error::runtime(
+                key.clone(),
+                &format!("Missing key {} to destructure.", source(key)),
+            )),
+        };
+    }
+    if let Ok(instance) = value.borrowed::<RloxInstance>() {
+        if key.lexeme().starts_with('#') {
+            return Err(Error::runtime(
+                key.clone(),
+                &format!("Cannot destructure private property '{}'.", key.lexeme()),
+            ));
+        }
+        // the lexeme of a string key is the name of the property
+        return instance.get(key);
+    }
+    Err(Error::runtime(
+        brace.clone(),
+        "Only maps and instances can be destructured with a map pattern.",
+    ))
+}
+
+/// Returns the map key a key of a map pattern stands for,
+/// where a bare name stands for the string of its name
+fn key_literal(key: &Token) -> TokenLiteral {
+    match key.kind() {
+        TokenType::Identifier => TokenLiteral::String(key.lexeme()),
+        _ => key.literal(),
+    }
+}
+
+/// Returns the token the way it is written in source code
+fn source(token: &Token) -> String {
+    match token.kind() {
+        // the lexeme of a string leaves out its quotes
+        TokenType::String => format!("\"{}\"", token.lexeme()),
+        _ => token.lexeme(),
+    }
+}
+
+/// Prints the pattern the way it is written in source code
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Pattern::Wildcard(token) | Pattern::Literal { token, .. } | Pattern::Binding(token) => {
+                write!(f, "{}", source(token))
+            }
+            Pattern::Alternative(alternatives) => {
+                let alternatives: Vec<String> =
+                    alternatives.iter().map(Pattern::to_string).collect();
+                write!(f, "{}", alternatives.join(" | "))
+            }
+            Pattern::List { elements, rest, .. } => {
+                let mut parts: Vec<String> = elements.iter().map(Pattern::to_string).collect();
+                if let Some(rest) = rest {
+                    parts.push(format!("...{}", rest.lexeme()));
+                }
+                write!(f, "[{}]", parts.join(", "))
+            }
+            Pattern::Map { entries, .. } => {
+                let parts: Vec<String> = entries
+                    .iter()
+                    .map(|(key, pattern)| match pattern {
+                        // `{name}` is short for `{name: name}`
+                        Pattern::Binding(name) if name.lexeme() == key.lexeme() => key.lexeme(),
+                        pattern => format!("{}: {}", source(key), pattern),
+                    })
+                    .collect();
+                write!(f, "{{{}}}", parts.join(", "))
+            }
+            Pattern::Variant {
+                enum_name,
+                name,
+                fields,
+            } => {
+                if let Some(enum_name) = enum_name {
+                    write!(f, "{}.", enum_name.lexeme())?;
+                }
+                write!(f, "{}", name.lexeme())?;
+                if let Some(fields) = fields {
+                    let fields: Vec<String> = fields.iter().map(Pattern::to_string).collect();
+                    write!(f, "({})", fields.join(", "))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Represents one arm of a Match statement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchArm {
+    pattern: Pattern,
+    // an arm with a guard only runs when the guard is also true
+    guard: Option<Expr>,
+    body: Stmt,
+}
+
+impl MatchArm {
+    /// Construct a new MatchArm
+    pub fn new(pattern: Pattern, guard: Option<Expr>, body: Stmt) -> MatchArm {
+        MatchArm {
+            pattern,
+            guard,
+            body,
+        }
+    }
+
+    /// Returns the pattern
+    pub fn pattern(&self) -> Pattern {
+        self.pattern.clone()
+    }
+
+    /// Returns the guard
+    pub fn guard(&self) -> Option<Expr> {
+        self.guard.clone()
+    }
+
+    /// Returns the body
+    pub fn body(&self) -> Stmt {
+        self.body.clone()
+    }
+}
+
+/// Represents a Match statement, which runs the first arm
+/// whose pattern matches the subject
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Match {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
+    keyword: Token,
+    subject: Expr,
+    arms: Vec<MatchArm>,
+}
+
+impl Match {
+    /// Construct a new Match statement
+    pub fn new(id: NodeId, keyword: Token, subject: Expr, arms: Vec<MatchArm>) -> Match {
+        Match {
+            id,
+            keyword,
+            subject,
+            arms,
+        }
+    }
+
+    /// Returns the `match` keyword
+    pub fn keyword(&self) -> &Token {
+        &self.keyword
+    }
+
+    /// Returns the value being matched
+    pub fn subject(&self) -> Expr {
+        self.subject.clone()
+    }
+
+    /// Returns the arms
+    pub fn arms(&self) -> Vec<MatchArm> {
+        self.arms.clone()
+    }
+}
@@ -1,17 +1,28 @@
 //! Defines all the statements associated with the Rlox language
 
 pub mod block;
+pub mod break_;
 pub mod class;
+pub mod continue_;
+pub mod enum_;
 pub mod expression;
+pub mod for_in;
 pub mod function;
 pub mod if_;
+pub mod match_;
 pub mod print;
 pub mod return_;
 pub mod stmt;
+pub mod throw;
+pub mod trait_;
+pub mod try_;
 pub mod var;
+pub mod var_pattern;
 pub mod while_;
+pub mod yield_;
 
 pub use {
-    block::Block, class::Class, expression::Expression, function::Function, function::RloxFunction,
-    if_::If, print::Print, return_::Return, stmt::Stmt, var::Var, while_::While,
+    block::Block, break_::Break, class::Class, class::RloxClass, continue_::Continue, enum_::Enum, enum_::EnumVariant, expression::Expression, for_in::ForIn, function::Function, function::Param, function::RloxFunction,
+    if_::If, match_::Match, match_::MatchArm, match_::Pattern, print::Print, return_::Return, stmt::Stmt, throw::Throw, trait_::RloxTrait, trait_::Trait, try_::CatchClause,
+    try_::Try, var::Var, var_pattern::VarPattern, while_::While, yield_::Yield,
 };
@@ -1,15 +1,19 @@
+use crate::common::NodeId;
 use crate::expressions::Expr;
+use serde::{Deserialize, Serialize};
 
 /// Represents a Print statement
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Print {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
     expression: Expr,
 }
 
 impl Print {
     /// Construct a new Print statement
-    pub fn new(expression: Expr) -> Print {
-        Print { expression }
+    pub fn new(id: NodeId, expression: Expr) -> Print {
+        Print { id, expression }
     }
 
     /// Return the expression
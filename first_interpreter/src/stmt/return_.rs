@@ -1,16 +1,20 @@
+use crate::common::NodeId;
 use crate::{expressions::Expr, rlox::Token};
+use serde::{Deserialize, Serialize};
 
 /// Represents a Return statement
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Return {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
     keyword: Token,
     value: Option<Expr>,
 }
 
 impl Return {
     /// Construct a new Return statement
-    pub fn new(keyword: Token, value: Option<Expr>) -> Return {
-        Return { keyword, value }
+    pub fn new(id: NodeId, keyword: Token, value: Option<Expr>) -> Return {
+        Return { id, keyword, value }
     }
 
     /// Returns the keyword
@@ -1,31 +1,55 @@
-use crate::stmt::{Block, Class, Expression, Function, If, Print, Return, Var, While};
+use crate::stmt::{
+    Block, Break, Class, Continue, Enum, Expression, ForIn, Function, If, Match, Print, Return,
+    Throw, Trait, Try, Var, VarPattern, While, Yield,
+};
+use serde::{Deserialize, Serialize};
 
 /// Trait for a structure implementing all the methods to
 /// handle different statements
 pub trait Visitor<R> {
     fn visit_block_stmt(&mut self, stmt: &Block) -> R;
+    fn visit_break_stmt(&mut self, stmt: &Break) -> R;
     fn visit_class_stmt(&mut self, stmt: &Class) -> R;
+    fn visit_continue_stmt(&mut self, stmt: &Continue) -> R;
+    fn visit_enum_stmt(&mut self, stmt: &Enum) -> R;
     fn visit_expression_stmt(&mut self, stmt: &Expression) -> R;
+    fn visit_for_in_stmt(&mut self, stmt: &ForIn) -> R;
     fn visit_function_stmt(&mut self, stmt: &Function) -> R;
     fn visit_if_stmt(&mut self, stmt: &If) -> R;
+    fn visit_match_stmt(&mut self, stmt: &Match) -> R;
     fn visit_print_stmt(&mut self, stmt: &Print) -> R;
     fn visit_return_stmt(&mut self, stmt: &Return) -> R;
+    fn visit_throw_stmt(&mut self, stmt: &Throw) -> R;
+    fn visit_trait_stmt(&mut self, stmt: &Trait) -> R;
+    fn visit_try_stmt(&mut self, stmt: &Try) -> R;
     fn visit_var_stmt(&mut self, stmt: &Var) -> R;
+    fn visit_var_pattern_stmt(&mut self, stmt: &VarPattern) -> R;
     fn visit_while_stmt(&mut self, stmt: &While) -> R;
+    fn visit_yield_stmt(&mut self, stmt: &Yield) -> R;
 }
 
 /// Represents all forms of statements
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Stmt {
     Block(Block),
+    Break(Break),
     Class(Class),
+    Continue(Continue),
+    Enum(Enum),
     Expression(Expression),
+    ForIn(ForIn),
     Function(Function),
     If(If),
+    Match(Match),
     Print(Print),
     Return(Return),
+    Throw(Throw),
+    Trait(Trait),
+    Try(Try),
     Var(Var),
+    VarPattern(VarPattern),
     While(While),
+    Yield(Yield),
 }
 
 impl Stmt {
@@ -33,14 +57,24 @@ impl Stmt {
     pub fn accept<R>(&self, visitor: &mut dyn Visitor<R>) -> R {
         match self {
             Stmt::Block(stmt) => visitor.visit_block_stmt(stmt),
+            Stmt::Break(stmt) => visitor.visit_break_stmt(stmt),
             Stmt::Class(stmt) => visitor.visit_class_stmt(stmt),
+            Stmt::Continue(stmt) => visitor.visit_continue_stmt(stmt),
+            Stmt::Enum(stmt) => visitor.visit_enum_stmt(stmt),
             Stmt::Expression(stmt) => visitor.visit_expression_stmt(stmt),
+            Stmt::ForIn(stmt) => visitor.visit_for_in_stmt(stmt),
             Stmt::Function(stmt) => visitor.visit_function_stmt(stmt),
             Stmt::If(stmt) => visitor.visit_if_stmt(stmt),
+            Stmt::Match(stmt) => visitor.visit_match_stmt(stmt),
             Stmt::Print(stmt) => visitor.visit_print_stmt(stmt),
             Stmt::Return(stmt) => visitor.visit_return_stmt(stmt),
+            Stmt::Throw(stmt) => visitor.visit_throw_stmt(stmt),
+            Stmt::Trait(stmt) => visitor.visit_trait_stmt(stmt),
+            Stmt::Try(stmt) => visitor.visit_try_stmt(stmt),
             Stmt::Var(stmt) => visitor.visit_var_stmt(stmt),
+            Stmt::VarPattern(stmt) => visitor.visit_var_pattern_stmt(stmt),
             Stmt::While(stmt) => visitor.visit_while_stmt(stmt),
+            Stmt::Yield(stmt) => visitor.visit_yield_stmt(stmt),
         }
     }
 }
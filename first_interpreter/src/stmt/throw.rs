@@ -0,0 +1,29 @@
+use crate::common::NodeId;
+use crate::{expressions::Expr, rlox::Token};
+use serde::{Deserialize, Serialize};
+
+/// Represents a Throw statement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Throw {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
+    keyword: Token,
+    value: Expr,
+}
+
+impl Throw {
+    /// Construct a new Throw statement
+    pub fn new(id: NodeId, keyword: Token, value: Expr) -> Throw {
+        Throw { id, keyword, value }
+    }
+
+    /// Returns the keyword
+    pub fn keyword(&self) -> Token {
+        self.keyword.clone()
+    }
+
+    /// Returns the thrown value
+    pub fn value(&self) -> Expr {
+        self.value.clone()
+    }
+}
@@ -0,0 +1,73 @@
+use std::{collections::HashMap, fmt::Display, rc::Rc};
+
+use crate::common::NodeId;
+use crate::rlox::Token;
+use crate::stmt::{Function, RloxFunction};
+use serde::{Deserialize, Serialize};
+
+/// Represents a trait at runtime, whose methods are
+/// copied into every class declared with it
+///
+/// Cloning a trait clones the reference, so every copy is the same trait
+#[derive(Debug, Clone)]
+pub struct RloxTrait {
+    name: String,
+    methods: Rc<HashMap<String, RloxFunction>>,
+}
+
+impl RloxTrait {
+    /// Constructs a new RloxTrait
+    pub fn new(name: String, methods: HashMap<String, RloxFunction>) -> RloxTrait {
+        RloxTrait {
+            name,
+            methods: Rc::new(methods),
+        }
+    }
+
+    /// Returns the name
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    /// Returns true if both values are the same trait
+    pub fn same(&self, other: &RloxTrait) -> bool {
+        Rc::ptr_eq(&self.methods, &other.methods)
+    }
+
+    /// Returns the methods by name
+    pub fn methods(&self) -> HashMap<String, RloxFunction> {
+        (*self.methods).clone()
+    }
+}
+
+impl Display for RloxTrait {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<trait {}>", self.name)
+    }
+}
+
+/// Represents a Trait declaration, like `trait Comparable { ... }`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Trait {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
+    name: Token,
+    methods: Vec<Function>,
+}
+
+impl Trait {
+    /// Construct a new Trait
+    pub fn new(id: NodeId, name: Token, methods: Vec<Function>) -> Trait {
+        Trait { id, name, methods }
+    }
+
+    /// Returns the name
+    pub fn name(&self) -> Token {
+        self.name.clone()
+    }
+
+    /// Returns the methods, including getters
+    pub fn methods(&self) -> Vec<Function> {
+        self.methods.clone()
+    }
+}
@@ -0,0 +1,70 @@
+use crate::common::NodeId;
+use crate::{rlox::Token, stmt::Stmt};
+use serde::{Deserialize, Serialize};
+
+/// Represents the catch clause of a Try statement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatchClause {
+    name: Token,
+    body: Vec<Stmt>,
+}
+
+impl CatchClause {
+    /// Construct a new CatchClause
+    pub fn new(name: Token, body: Vec<Stmt>) -> CatchClause {
+        CatchClause { name, body }
+    }
+
+    /// Returns the name the caught value is bound to
+    pub fn name(&self) -> Token {
+        self.name.clone()
+    }
+
+    /// Returns the body
+    pub fn body(&self) -> Vec<Stmt> {
+        self.body.clone()
+    }
+}
+
+/// Represents a Try statement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Try {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
+    body: Vec<Stmt>,
+    // a try statement has a catch clause, a finally block or both
+    catch: Option<CatchClause>,
+    finally: Option<Vec<Stmt>>,
+}
+
+impl Try {
+    /// Construct a new Try statement
+    pub fn new(
+        id: NodeId,
+        body: Vec<Stmt>,
+        catch: Option<CatchClause>,
+        finally: Option<Vec<Stmt>>,
+    ) -> Try {
+        Try {
+            id,
+            body,
+            catch,
+            finally,
+        }
+    }
+
+    /// Returns the body
+    pub fn body(&self) -> Vec<Stmt> {
+        self.body.clone()
+    }
+
+    /// Returns the catch clause
+    pub fn catch(&self) -> Option<CatchClause> {
+        self.catch.clone()
+    }
+
+    /// Returns the finally block
+    pub fn finally(&self) -> Option<Vec<Stmt>> {
+        self.finally.clone()
+    }
+}
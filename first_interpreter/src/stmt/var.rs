@@ -1,33 +1,46 @@
+use crate::common::NodeId;
 use crate::{
     expressions::{literal::Literal, Expr},
     rlox::{Token, TokenLiteral, Value},
 };
+use serde::{Deserialize, Serialize};
 
 /// Represents a Return statement
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Var {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
     name: Token,
     initializer: Expr,
     is_initialized: bool,
+    /// Constants cannot be assigned to after they are declared
+    is_const: bool,
 }
 
 impl Var {
     /// Construct a new variable Var
-    pub fn new(name: Token, initializer: Option<Expr>) -> Var {
-        let is_initialized = match initializer {
-            Some(_) => true,
-            None => false,
-        };
-
-        let initializer =
-            initializer.unwrap_or(Expr::Literal(Literal::new(Value::new(TokenLiteral::Nil))));
+    pub fn new(id: NodeId, name: Token, initializer: Option<Expr>) -> Var {
+        let is_initialized = initializer.is_some();
+
+        let initializer = initializer.unwrap_or(Expr::Literal(Literal::new(
+            NodeId::default(),
+            Value::new(TokenLiteral::Nil),
+        )));
         Var {
+            id,
             name,
             initializer,
             is_initialized,
+            is_const: false,
         }
     }
 
+    /// Marks the variable as a constant
+    pub fn constant(mut self) -> Var {
+        self.is_const = true;
+        self
+    }
+
     /// Returns the name
     pub fn name(&self) -> Token {
         self.name.clone()
@@ -42,4 +55,9 @@ impl Var {
     pub fn is_initialized(&self) -> bool {
         self.is_initialized
     }
+
+    /// Returns true if the variable was declared with `const`
+    pub fn is_const(&self) -> bool {
+        self.is_const
+    }
 }
@@ -0,0 +1,48 @@
+use crate::common::NodeId;
+use crate::{expressions::Expr, stmt::Pattern};
+use serde::{Deserialize, Serialize};
+
+/// Represents a variable declaration that destructures
+/// its initializer, like `var [a, b] = pair;`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VarPattern {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
+    pattern: Pattern,
+    initializer: Expr,
+    /// Constants cannot be assigned to after they are declared
+    is_const: bool,
+}
+
+impl VarPattern {
+    /// Construct a new VarPattern declaration
+    pub fn new(id: NodeId, pattern: Pattern, initializer: Expr) -> VarPattern {
+        VarPattern {
+            id,
+            pattern,
+            initializer,
+            is_const: false,
+        }
+    }
+
+    /// Marks every variable the pattern declares as a constant
+    pub fn constant(mut self) -> VarPattern {
+        self.is_const = true;
+        self
+    }
+
+    /// Returns the pattern
+    pub fn pattern(&self) -> Pattern {
+        self.pattern.clone()
+    }
+
+    /// Returns the initializer
+    pub fn initializer(&self) -> Expr {
+        self.initializer.clone()
+    }
+
+    /// Returns true if the variables were declared with `const`
+    pub fn is_const(&self) -> bool {
+        self.is_const
+    }
+}
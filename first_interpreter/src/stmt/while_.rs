@@ -1,16 +1,52 @@
-use crate::{expressions::Expr, stmt::Stmt};
+use crate::common::NodeId;
+use crate::{expressions::Expr, rlox::Token, stmt::Stmt};
+use serde::{Deserialize, Serialize};
 
 /// Represents a While statement
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct While {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
     condition: Expr,
     body: Box<Stmt>,
+    // only loops desugared from a `for` have an increment
+    increment: Option<Expr>,
+    /// The label break and continue statements can name the loop by
+    label: Option<Token>,
 }
 
 impl While {
     /// Construct a new While statment
-    pub fn new(condition: Expr, body: Box<Stmt>) -> While {
-        While { condition, body }
+    pub fn new(id: NodeId, condition: Expr, body: Box<Stmt>) -> While {
+        While {
+            id,
+            condition,
+            body,
+            increment: None,
+            label: None,
+        }
+    }
+
+    /// Construct a new While statement that evaluates the
+    /// increment after every run of the body
+    pub fn with_increment(
+        id: NodeId,
+        condition: Expr,
+        body: Box<Stmt>,
+        increment: Option<Expr>,
+    ) -> While {
+        While {
+            id,
+            condition,
+            body,
+            increment,
+            label: None,
+        }
+    }
+
+    /// Returns the same loop, named by the given label
+    pub fn labeled(self, label: Option<Token>) -> While {
+        While { label, ..self }
     }
 
     /// Returns the condition
@@ -22,4 +58,14 @@ impl While {
     pub fn body(&self) -> Box<Stmt> {
         self.body.clone()
     }
+
+    /// Returns the increment
+    pub fn increment(&self) -> Option<Expr> {
+        self.increment.clone()
+    }
+
+    /// Returns the label
+    pub fn label(&self) -> Option<Token> {
+        self.label.clone()
+    }
 }
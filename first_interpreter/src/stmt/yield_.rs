@@ -0,0 +1,30 @@
+use crate::common::NodeId;
+use crate::{expressions::Expr, rlox::Token};
+use serde::{Deserialize, Serialize};
+
+/// Represents a Yield statement, which pauses a generator
+/// and hands the value to the caller of `next()`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Yield {
+    #[serde(skip_deserializing, default = "crate::common::NodeId::fresh")]
+    id: NodeId,
+    keyword: Token,
+    value: Expr,
+}
+
+impl Yield {
+    /// Construct a new Yield statement
+    pub fn new(id: NodeId, keyword: Token, value: Expr) -> Yield {
+        Yield { id, keyword, value }
+    }
+
+    /// Returns the keyword
+    pub fn keyword(&self) -> Token {
+        self.keyword.clone()
+    }
+
+    /// Returns the yielded value
+    pub fn value(&self) -> Expr {
+        self.value.clone()
+    }
+}
@@ -1,13 +1,21 @@
 use crate::{
     expressions::{
         assign::Assign,
+        assign_pattern::AssignPattern,
         binary::Binary,
-        expr::{Expr, Visitor},
+        expr::{Expr, Visitor as ExprVisitor},
         grouping::Grouping,
         literal::Literal,
         unary::Unary,
+        Call, Get, Index, Lambda, List, Logical, Map, Set, SetIndex, Super, Ternary, This,
+        Variable,
+    },
+    rlox::{Token, TokenLiteral},
+    stmt::{
+        stmt::Visitor as StmtVisitor, Block, Break, Class, Continue, Enum, Expression, ForIn,
+        Function, If, Match, Param, Print, Return, Stmt, Throw, Trait, Try, Var, VarPattern, While,
+        Yield,
     },
-    rlox::TokenLiteral,
 };
 
 /// Represents a printer for the abstract syntax tree
@@ -21,38 +29,135 @@ impl AstPrinter {
 
     /// Surrounds the given expression in paratheses
     fn parenthesize(&mut self, name: String, exprs: Vec<Expr>) -> String {
+        let parts = exprs.into_iter().map(|expr| expr.accept(self)).collect();
+        self.parenthesize_parts(name, parts)
+    }
+
+    /// Prints the parameters of a function, with each default
+    /// value as an assignment
+    fn params(&mut self, params: Vec<Param>) -> String {
+        params
+            .iter()
+            .map(|param| match (param.default(), param.is_rest()) {
+                (Some(default), _) => {
+                    let name = format!("= {}", param.name().lexeme());
+                    self.parenthesize(name, vec![default])
+                }
+                (None, true) => format!("...{}", param.name().lexeme()),
+                (None, false) => param.name().lexeme(),
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    /// Surrounds the given statements in paratheses
+    fn parenthesize_stmts(&mut self, name: String, statements: Vec<Stmt>) -> String {
+        let parts = statements
+            .into_iter()
+            .map(|stmt| stmt.accept(self))
+            .collect();
+        self.parenthesize_parts(name, parts)
+    }
+
+    /// Surrounds the already printed parts in paratheses
+    fn parenthesize_parts(&mut self, name: String, parts: Vec<String>) -> String {
         let mut builder = String::new();
 
         builder.push('(');
         builder.push_str(name.as_str());
-        for expr in exprs {
+        for part in parts {
             builder.push(' ');
-            builder.push_str(expr.accept(self).as_str());
+            builder.push_str(part.as_str());
         }
         builder.push(')');
 
         builder
     }
 
+    /// Prints a single expression
     pub fn print(&mut self, expr: Expr) -> String {
         expr.accept(self)
     }
+
+    /// Prints a single statement
+    pub fn print_stmt(&mut self, stmt: Stmt) -> String {
+        stmt.accept(self)
+    }
+
+    /// Prints a list of statements, one per line
+    pub fn print_program(&mut self, statements: Vec<Stmt>) -> String {
+        statements
+            .into_iter()
+            .map(|stmt| self.print_stmt(stmt))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
 }
 
-/// Implements the Visitor trait for AstPrinter
-impl Visitor<String> for AstPrinter {
-    fn visit_assign_expr(&mut self, _expr: &Assign) -> String {
-        unimplemented!()
+/// Returns the operator an assignment is written with, like `=` or `+=`
+fn assignment(operator: Option<Token>) -> String {
+    match operator {
+        Some(operator) => format!("{}=", operator.lexeme()),
+        None => "=".to_string(),
+    }
+}
+
+/// Implements the expression Visitor trait for AstPrinter
+impl ExprVisitor<String> for AstPrinter {
+    fn visit_assign_expr(&mut self, expr: &Assign) -> String {
+        let name = expr.name().lexeme();
+        let equals = assignment(expr.operator());
+        self.parenthesize(format!("{} {}", equals, name), vec![expr.value()])
+    }
+
+    fn visit_assign_pattern_expr(&mut self, expr: &AssignPattern) -> String {
+        let name = format!("= {}", expr.pattern());
+        self.parenthesize(name, vec![expr.value()])
     }
 
     fn visit_binary_expr(&mut self, expr: &Binary) -> String {
         self.parenthesize(expr.operator().lexeme(), vec![expr.left(), expr.right()])
     }
 
+    fn visit_call_expr(&mut self, expr: &Call) -> String {
+        let mut parts = vec![expr.callee().accept(self)];
+        for argument in expr.arguments() {
+            parts.push(argument.accept(self));
+        }
+        for (name, argument) in expr.named_arguments() {
+            let name = format!("{}:", name.lexeme());
+            parts.push(self.parenthesize(name, vec![argument.clone()]));
+        }
+        self.parenthesize_parts("call".to_string(), parts)
+    }
+
+    fn visit_get_expr(&mut self, expr: &Get) -> String {
+        let dot = if expr.is_optional() { "?." } else { "." };
+        let name = format!("{} {}", dot, expr.name().lexeme());
+        self.parenthesize(name, vec![expr.object()])
+    }
+
     fn visit_grouping_expr(&mut self, expr: &Grouping) -> String {
         self.parenthesize("group".to_string(), vec![expr.expression()])
     }
 
+    fn visit_index_expr(&mut self, expr: &Index) -> String {
+        self.parenthesize("[]".to_string(), vec![expr.object(), expr.index()])
+    }
+
+    fn visit_lambda_expr(&mut self, expr: &Lambda) -> String {
+        let params = self.params(expr.params());
+        let keyword = match expr.is_generator() {
+            true => "lambda*",
+            false => "lambda",
+        };
+        self.parenthesize_stmts(format!("{} ({})", keyword, params), expr.body())
+    }
+
+    fn visit_list_expr(&mut self, expr: &List) -> String {
+        self.parenthesize("list".to_string(), expr.elements().clone())
+    }
+
     fn visit_literal_expr(&mut self, expr: &Literal) -> String {
         expr.value()
             .owned::<TokenLiteral>()
@@ -60,47 +165,387 @@ impl Visitor<String> for AstPrinter {
             .to_string()
     }
 
+    fn visit_logical_expr(&mut self, expr: &Logical) -> String {
+        self.parenthesize(expr.operator().lexeme(), vec![expr.left(), expr.right()])
+    }
+
+    fn visit_map_expr(&mut self, expr: &Map) -> String {
+        let parts = expr
+            .entries()
+            .iter()
+            .map(|(key, value)| {
+                self.parenthesize(":".to_string(), vec![key.clone(), value.clone()])
+            })
+            .collect();
+        self.parenthesize_parts("map".to_string(), parts)
+    }
+
+    fn visit_set_expr(&mut self, expr: &Set) -> String {
+        let equals = assignment(expr.operator());
+        let name = format!(".{} {}", equals, expr.name().lexeme());
+        self.parenthesize(name, vec![expr.object(), expr.value()])
+    }
+
+    fn visit_super_expr(&mut self, expr: &Super) -> String {
+        format!("(super {})", expr.method().lexeme())
+    }
+
+    fn visit_ternary_expr(&mut self, expr: &Ternary) -> String {
+        self.parenthesize(
+            "?:".to_string(),
+            vec![expr.condition(), expr.then_branch(), expr.else_branch()],
+        )
+    }
+
+    fn visit_this_expr(&mut self, _expr: &This) -> String {
+        "this".to_string()
+    }
+
+    fn visit_set_index_expr(&mut self, expr: &SetIndex) -> String {
+        self.parenthesize(
+            format!("[]{}", assignment(expr.operator())),
+            vec![expr.object(), expr.index(), expr.value()],
+        )
+    }
+
     fn visit_unary_expr(&mut self, expr: &Unary) -> String {
         self.parenthesize(expr.operator().lexeme(), vec![expr.right()])
     }
 
-    fn visit_variable_expr(&mut self, _expr: &crate::expressions::Variable) -> String {
-        unimplemented!()
+    fn visit_variable_expr(&mut self, expr: &Variable) -> String {
+        expr.name().lexeme()
+    }
+}
+
+/// Implements the statement Visitor trait for AstPrinter
+impl StmtVisitor<String> for AstPrinter {
+    fn visit_block_stmt(&mut self, stmt: &Block) -> String {
+        self.parenthesize_stmts("block".to_string(), stmt.statements())
     }
 
-    fn visit_logical_expr(&mut self, _expr: &crate::expressions::Logical) -> String {
-        unimplemented!()
+    fn visit_break_stmt(&mut self, stmt: &Break) -> String {
+        match stmt.label() {
+            Some(label) => format!("(break {})", label.lexeme()),
+            None => "(break)".to_string(),
+        }
     }
 
-    fn visit_call_expr(&mut self, expr: &crate::expressions::Call) -> String {
-        unimplemented!()
+    fn visit_class_stmt(&mut self, stmt: &Class) -> String {
+        let mut name = match stmt.superclass() {
+            Some(superclass) => format!(
+                "class {} < {}",
+                stmt.name().lexeme(),
+                superclass.name().lexeme()
+            ),
+            None => format!("class {}", stmt.name().lexeme()),
+        };
+        let traits: Vec<String> = stmt
+            .traits()
+            .iter()
+            .map(|trait_| trait_.name().lexeme())
+            .collect();
+        if !traits.is_empty() {
+            name = format!("{} with {}", name, traits.join(", "));
+        }
+        let mut parts = Vec::new();
+        for field in stmt.fields() {
+            let name = format!("class {}", field.name().lexeme());
+            parts.push(self.parenthesize(name, vec![field.initializer()]));
+        }
+        for method in stmt.methods() {
+            parts.push(self.visit_function_stmt(&method));
+        }
+        self.parenthesize_parts(name, parts)
+    }
+
+    fn visit_continue_stmt(&mut self, stmt: &Continue) -> String {
+        match stmt.label() {
+            Some(label) => format!("(continue {})", label.lexeme()),
+            None => "(continue)".to_string(),
+        }
+    }
+
+    fn visit_enum_stmt(&mut self, stmt: &Enum) -> String {
+        let mut parts = vec![format!("enum {}", stmt.name().lexeme())];
+        for variant in stmt.variants() {
+            match variant.fields().is_empty() {
+                true => parts.push(variant.name().lexeme()),
+                false => {
+                    let mut names = vec![variant.name().lexeme()];
+                    names.extend(variant.fields().iter().map(Token::lexeme));
+                    parts.push(format!("({})", names.join(" ")));
+                }
+            }
+        }
+        format!("({})", parts.join(" "))
+    }
+
+    fn visit_expression_stmt(&mut self, stmt: &Expression) -> String {
+        self.parenthesize(";".to_string(), vec![stmt.expression()])
+    }
+
+    fn visit_for_in_stmt(&mut self, stmt: &ForIn) -> String {
+        let parts = vec![
+            stmt.name().lexeme(),
+            stmt.iterable().accept(self),
+            stmt.body().accept(self),
+        ];
+        let name = match stmt.label() {
+            Some(label) => format!("for-in {}:", label.lexeme()),
+            None => "for-in".to_string(),
+        };
+        self.parenthesize_parts(name, parts)
+    }
+
+    fn visit_function_stmt(&mut self, stmt: &Function) -> String {
+        let keyword = match (stmt.is_class_method(), stmt.is_generator()) {
+            (true, true) => "class fun*",
+            (true, false) => "class fun",
+            (false, true) => "fun*",
+            (false, false) => "fun",
+        };
+        // getters are the only functions printed without a parameter list
+        let name = match stmt.is_getter() {
+            true => format!("{} {}", keyword, stmt.name().lexeme()),
+            false => {
+                let params = self.params(stmt.params());
+                format!("{} {} ({})", keyword, stmt.name().lexeme(), params)
+            }
+        };
+        self.parenthesize_stmts(name, stmt.body())
+    }
+
+    fn visit_if_stmt(&mut self, stmt: &If) -> String {
+        let mut parts = vec![
+            stmt.condition().accept(self),
+            stmt.then_branch().accept(self),
+        ];
+        if let Some(else_branch) = stmt.else_branch() {
+            parts.push(else_branch.accept(self));
+        }
+        self.parenthesize_parts("if".to_string(), parts)
+    }
+
+    fn visit_match_stmt(&mut self, stmt: &Match) -> String {
+        let mut parts = vec![stmt.subject().accept(self)];
+        for arm in stmt.arms() {
+            let mut arm_parts = vec![arm.pattern().to_string()];
+            if let Some(guard) = arm.guard() {
+                arm_parts.push(self.parenthesize("if".to_string(), vec![guard]));
+            }
+            arm_parts.push(arm.body().accept(self));
+            parts.push(self.parenthesize_parts("=>".to_string(), arm_parts));
+        }
+        self.parenthesize_parts("match".to_string(), parts)
+    }
+
+    fn visit_print_stmt(&mut self, stmt: &Print) -> String {
+        self.parenthesize("print".to_string(), vec![stmt.expression()])
+    }
+
+    fn visit_return_stmt(&mut self, stmt: &Return) -> String {
+        match stmt.value() {
+            Some(value) => self.parenthesize("return".to_string(), vec![value]),
+            None => "(return)".to_string(),
+        }
+    }
+
+    fn visit_throw_stmt(&mut self, stmt: &Throw) -> String {
+        self.parenthesize("throw".to_string(), vec![stmt.value()])
+    }
+
+    fn visit_trait_stmt(&mut self, stmt: &Trait) -> String {
+        let name = format!("trait {}", stmt.name().lexeme());
+        let methods = stmt.methods().into_iter().map(Stmt::Function).collect();
+        self.parenthesize_stmts(name, methods)
+    }
+
+    fn visit_try_stmt(&mut self, stmt: &Try) -> String {
+        let mut parts = vec![self.parenthesize_stmts("block".to_string(), stmt.body())];
+        if let Some(catch) = stmt.catch() {
+            let name = format!("catch {}", catch.name().lexeme());
+            parts.push(self.parenthesize_stmts(name, catch.body()));
+        }
+        if let Some(finally) = stmt.finally() {
+            parts.push(self.parenthesize_stmts("finally".to_string(), finally));
+        }
+        self.parenthesize_parts("try".to_string(), parts)
+    }
+
+    fn visit_var_stmt(&mut self, stmt: &Var) -> String {
+        let keyword = if stmt.is_const() { "const" } else { "var" };
+        let name = format!("{} {}", keyword, stmt.name().lexeme());
+        match stmt.is_initialized() {
+            true => self.parenthesize(name, vec![stmt.initializer()]),
+            false => self.parenthesize(name, vec![]),
+        }
+    }
+
+    fn visit_var_pattern_stmt(&mut self, stmt: &VarPattern) -> String {
+        let keyword = if stmt.is_const() { "const" } else { "var" };
+        let name = format!("{} {}", keyword, stmt.pattern());
+        self.parenthesize(name, vec![stmt.initializer()])
+    }
+
+    fn visit_while_stmt(&mut self, stmt: &While) -> String {
+        let mut parts = vec![stmt.condition().accept(self), stmt.body().accept(self)];
+        if let Some(increment) = stmt.increment() {
+            parts.push(increment.accept(self));
+        }
+        let name = match stmt.label() {
+            Some(label) => format!("while {}:", label.lexeme()),
+            None => "while".to_string(),
+        };
+        self.parenthesize_parts(name, parts)
+    }
+
+    fn visit_yield_stmt(&mut self, stmt: &Yield) -> String {
+        self.parenthesize("yield".to_string(), vec![stmt.value()])
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::common::NodeId;
     use crate::rlox::{
+        parser::Parser,
+        scanner::Scanner,
         token::{Token, TokenLiteral, TokenType},
         Value,
     };
 
     use super::*;
 
+    fn print_source(source: &str) -> String {
+        let tokens = Scanner::new(source.to_string()).scan_tokens().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+        AstPrinter::new().print_program(statements)
+    }
+
     #[test]
     fn test_ast_printer() {
         let left = Expr::Unary(Unary::new(
+            NodeId::default(),
             Token::new(TokenType::Minus, "-", TokenLiteral::Nil, 1),
-            Expr::Literal(Literal::new(Value::new(TokenLiteral::Integer(123)))),
+            Expr::Literal(Literal::new(
+                NodeId::default(),
+                Value::new(TokenLiteral::Integer(123)),
+            )),
         ));
         let operator = Token::new(TokenType::Star, "*", TokenLiteral::Nil, 1);
-        let right = Expr::Grouping(Grouping::new(Expr::Literal(Literal::new(Value::new(
-            TokenLiteral::Float(45.67),
-        )))));
+        let right = Expr::Grouping(Grouping::new(
+            NodeId::default(),
+            Expr::Literal(Literal::new(
+                NodeId::default(),
+                Value::new(TokenLiteral::Float(45.67)),
+            )),
+        ));
 
-        let expression = Expr::Binary(Binary::new(left, operator, right));
+        let expression = Expr::Binary(Binary::new(NodeId::default(), left, operator, right));
         let mut ast_printer = AstPrinter::new();
         let output = ast_printer.print(expression);
 
         assert_eq!("(* (- 123) (group 45.67))".to_string(), output);
     }
+
+    #[test]
+    fn test_ast_printer_statements() {
+        let test_cases = [
+            ("var a;", "(var a)"),
+            ("var a = 1 + 2 * 3;", "(var a (+ 1 (* 2 3)))"),
+            ("a = b or c;", "(; (= a (or b c)))"),
+            ("print f(1, x);", "(print (call f 1 x))"),
+            (
+                "if (a) print 1; else { print 2; }",
+                "(if a (print 1) (block (print 2)))",
+            ),
+            (
+                "for (;;) { print 1; }",
+                "(block (while true (block (print 1))))",
+            ),
+            (
+                "fun add(a, b) { return a + b; }",
+                "(fun add (a b) (return (+ a b)))",
+            ),
+            (
+                "for (var i = 0; i < 3; i = i + 1) print i;",
+                "(block (var i 0) (while (< i 3) (print i) (= i (+ i 1))))",
+            ),
+            ("throw e.message;", "(throw (. message e))"),
+            (
+                "xs[0] = [1, xs[1 + 1], []];",
+                "(; ([]= xs 0 (list 1 ([] xs (+ 1 1)) (list))))",
+            ),
+            (
+                r#"print {"a": 1, 2: {}};"#,
+                r#"(print (map (: "a" 1) (: 2 (map))))"#,
+            ),
+            (
+                "outer: while (true) { if (a) break outer; continue; }",
+                "(while outer: true (block (if a (break outer)) (continue)))",
+            ),
+            (
+                "outer: for (var x in range(0, n, 1)) print x;",
+                "(for-in outer: x (call range 0 n 1) (print x))",
+            ),
+            (
+                "class B < A { init(x) { this.x = super.f(x); } }",
+                "(class B < A (fun init (x) (; (.= x this (call (super f) x)))))",
+            ),
+            (
+                "xs.map((x) => x * 2).filter(fun (x) { return x > 2; });",
+                "(; (call (. filter (call (. map xs) (lambda (x) (return (* x 2))))) (lambda (x) (return (> x 2)))))",
+            ),
+            ("const max = 3;", "(const max 3)"),
+            (
+                "x = a | b ^ c & 1 << 2 + 3 % 4 == -2 ** 2 ? 1 : ~y;",
+                "(; (= x (?: (== (| a (^ b (& c (<< 1 (+ 2 (% 3 4)))))) (- (** 2 2))) 1 (~ y))))",
+            ),
+            (
+                "print a?.b ?? c?.d(1) and e or f;",
+                "(print (?? (?. b a) (or (and (call (?. d c) 1) e) f)))",
+            ),
+            (
+                "x += 1; o.f -= a ? b : c ? d : e; xs[0] %= 2 ** 3 ** 2;",
+                "(; (+= x 1))\n(; (.-= f o (?: a b (?: c d e))))\n(; ([]%= xs 0 (** 2 (** 3 2))))",
+            ),
+            (
+                r#"match (x) { [a, ...b] if a > 1 => print b; "s" | 2 => {} _ => print x; }"#,
+                r#"(match x (=> [a, ...b] (if (> a 1)) (print b)) (=> "s" | 2 (block)) (=> _ (print x)))"#,
+            ),
+            (
+                r#"var [a, ...b] = xs; [a, b] = [b, a]; const {k, "n": [m]} = m;"#,
+                "(var [a, ...b] xs)\n(; (= [a, b] (list b a)))\n(const {k, \"n\": [m]} m)",
+            ),
+            (
+                "enum Shape { Circle(r), Rect(w, h), Empty } match (s) { Shape.Circle(_) | Rect(_, 1) => {} }",
+                "(enum Shape (Circle r) (Rect w h) Empty)\n(match s (=> Shape.Circle(_) | Rect(_, 1) (block)))",
+            ),
+            (
+                "class C { class n = 0; class make() { return C(); } area { return 1; } }",
+                "(class C (class n 0) (class fun make () (return (call C))) (fun area (return 1)))",
+            ),
+            (
+                "trait T { size { return 1; } } class C < B with T, U { f() {} }",
+                "(trait T (fun size (return 1)))\n(class C < B with T, U (fun f ()))",
+            ),
+            (
+                "fun f(a, b = a + 1, ...rest) { return g(a, key: b); }",
+                "(fun f (a (= b (+ a 1)) ...rest) (return (call g a (key: b))))",
+            ),
+            (
+                "try { f(); } catch (e) { print e; } finally { g(); }",
+                "(try (block (; (call f))) (catch e (print e)) (finally (; (call g))))",
+            ),
+            (
+                "fun* count(n) { while (true) { yield n; n = n + 1; } } var g = fun* () { yield 1; };",
+                "(fun* count (n) (while true (block (yield n) (; (= n (+ n 1))))))\n(var g (lambda* () (yield 1)))",
+            ),
+            ("var got = Fiber.yield(1);", "(var got (call (. yield Fiber) 1))"),
+        ];
+
+        for (source, expected) in test_cases {
+            assert_eq!(print_source(source), expected);
+        }
+    }
 }
@@ -0,0 +1,797 @@
+//! Builds a control-flow graph for the top level script and for every
+//! function declared in it, and renders the graphs as Graphviz DOT.
+//!
+//! `for` loops reach this pass already desugared by the parser into a
+//! `while` loop inside a block, so they need no special handling here
+//! beyond giving the increment its own block for `continue` to jump to.
+//!
+//! Exceptions are approximated: a try body gets a single edge to its
+//! handler, and only throw statements jump to the innermost handler.
+//! Returns, breaks and continues that leave a try statement go through
+//! its finally block, which then carries on to where they were going.
+
+use crate::{
+    common::NodeId,
+    stmt::{
+        stmt::Visitor as StmtVisitor, Block, Break, Class, Continue, Enum, Expression, ForIn,
+        Function, If, Match, Pattern, Print, Return, Stmt, Throw, Trait, Try, Var, VarPattern,
+        While, Yield,
+    },
+    visitors::{ast_printer::AstPrinter, dot_printer::escape},
+};
+
+/// Represents a straight-line run of statements with a single entry point
+#[derive(Debug, Clone, Default)]
+pub struct BasicBlock {
+    statements: Vec<String>,
+    successors: Vec<(usize, Option<&'static str>)>,
+}
+
+impl BasicBlock {
+    /// Returns the printed statements of the block
+    pub fn statements(&self) -> &Vec<String> {
+        &self.statements
+    }
+
+    /// Returns the blocks control can flow to, with the edge labels
+    pub fn successors(&self) -> &Vec<(usize, Option<&'static str>)> {
+        &self.successors
+    }
+}
+
+/// Represents the control-flow graph of a single function
+#[derive(Debug, Clone)]
+pub struct ControlFlowGraph {
+    name: String,
+    blocks: Vec<BasicBlock>,
+}
+
+impl ControlFlowGraph {
+    /// The index of the block control enters the function through
+    pub const ENTRY: usize = 0;
+    /// The index of the block control leaves the function through
+    pub const EXIT: usize = 1;
+
+    /// Constructs an empty graph holding only the entry and exit blocks
+    fn new(name: String) -> ControlFlowGraph {
+        ControlFlowGraph {
+            name,
+            blocks: vec![BasicBlock::default(), BasicBlock::default()],
+        }
+    }
+
+    /// Returns the name of the function the graph describes
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the basic blocks of the graph
+    pub fn blocks(&self) -> &Vec<BasicBlock> {
+        &self.blocks
+    }
+}
+
+/// Builds the control-flow graphs of the script and of every function in it
+pub fn build(statements: Vec<Stmt>) -> Vec<ControlFlowGraph> {
+    let mut pending = vec![("<script>".to_string(), statements)];
+    let mut graphs = Vec::new();
+
+    let mut i = 0;
+    while i < pending.len() {
+        let (name, body) = pending[i].clone();
+        let mut builder = CfgBuilder::new(name);
+        for statement in body {
+            statement.accept(&mut builder);
+        }
+        builder.finish();
+
+        for function in builder.functions.drain(..) {
+            pending.push((function.name().lexeme(), function.body()));
+        }
+        graphs.push(builder.graph);
+        i += 1;
+    }
+
+    graphs
+}
+
+/// Renders the control-flow graphs as one DOT graph, one cluster per function
+pub fn to_dot(graphs: &[ControlFlowGraph]) -> String {
+    let mut builder = String::new();
+    builder.push_str("digraph cfg {\n");
+    builder.push_str("  node [shape=box, fontname=\"monospace\"];\n");
+
+    for (f, graph) in graphs.iter().enumerate() {
+        builder.push_str(&format!("  subgraph cluster_{} {{\n", f));
+        builder.push_str(&format!("    label=\"{}\";\n", escape(graph.name())));
+
+        for (b, block) in graph.blocks().iter().enumerate() {
+            let mut label = match b {
+                ControlFlowGraph::ENTRY => "entry\\l".to_string(),
+                ControlFlowGraph::EXIT => "exit\\l".to_string(),
+                _ => format!("B{}\\l", b),
+            };
+            for statement in block.statements() {
+                label.push_str(&escape(statement));
+                label.push_str("\\l");
+            }
+            builder.push_str(&format!("    f{}_b{} [label=\"{}\"];\n", f, b, label));
+        }
+
+        for (b, block) in graph.blocks().iter().enumerate() {
+            for (successor, edge_label) in block.successors() {
+                match edge_label {
+                    Some(edge_label) => builder.push_str(&format!(
+                        "    f{}_b{} -> f{}_b{} [label=\"{}\"];\n",
+                        f, b, f, successor, edge_label
+                    )),
+                    None => {
+                        builder.push_str(&format!("    f{}_b{} -> f{}_b{};\n", f, b, f, successor))
+                    }
+                }
+            }
+        }
+        builder.push_str("  }\n");
+    }
+    builder.push('}');
+
+    builder
+}
+
+/// Represents the blocks break and continue statements jump to inside a loop
+struct LoopTargets {
+    label: Option<String>,
+    /// The block a continue statement goes to
+    next: usize,
+    /// The block a break statement goes to
+    exit: usize,
+}
+
+/// Represents the finally block of a try statement the code being visited is in
+struct Finalizer {
+    block: usize,
+    /// The number of loops around the try statement
+    loops: usize,
+    /// Where the jumps that go through the finally block were going,
+    /// with the loops around their target and their edge label
+    exits: Vec<(usize, usize, Option<&'static str>)>,
+    /// True if an exception goes through the finally block
+    rethrows: bool,
+}
+
+/// Represents a pass that splits a function body into basic blocks
+struct CfgBuilder {
+    graph: ControlFlowGraph,
+    /// The block statements are currently appended to,
+    /// None if the code being visited is unreachable
+    current: Option<usize>,
+    /// Functions declared in the body, which get their own graphs
+    functions: Vec<Function>,
+    /// The blocks thrown values go to, innermost last
+    handlers: Vec<usize>,
+    /// The loops around the code being visited, innermost last
+    loops: Vec<LoopTargets>,
+    /// The finally blocks around the code being visited, innermost last
+    finalizers: Vec<Finalizer>,
+    printer: AstPrinter,
+}
+
+impl CfgBuilder {
+    /// Constructs a new CfgBuilder positioned at the entry block
+    fn new(name: String) -> CfgBuilder {
+        CfgBuilder {
+            graph: ControlFlowGraph::new(name),
+            current: Some(ControlFlowGraph::ENTRY),
+            functions: Vec::new(),
+            handlers: Vec::new(),
+            loops: Vec::new(),
+            finalizers: Vec::new(),
+            printer: AstPrinter::new(),
+        }
+    }
+
+    /// Adds a new empty block to the graph and returns its index
+    fn new_block(&mut self) -> usize {
+        self.graph.blocks.push(BasicBlock::default());
+        self.graph.blocks.len() - 1
+    }
+
+    /// Returns the current block, starting a new unreachable
+    /// block if control cannot get here
+    fn current_block(&mut self) -> usize {
+        match self.current {
+            Some(block) => block,
+            None => {
+                let block = self.new_block();
+                self.current = Some(block);
+                block
+            }
+        }
+    }
+
+    /// Appends a printed statement to the current block
+    fn push(&mut self, statement: String) {
+        let block = self.current_block();
+        self.graph.blocks[block].statements.push(statement);
+    }
+
+    /// Adds an edge from a block to another, if the first block is reachable
+    fn link(&mut self, from: Option<usize>, to: usize, label: Option<&'static str>) {
+        if let Some(from) = from {
+            self.graph.blocks[from].successors.push((to, label));
+        }
+    }
+
+    /// Visits a list of statements with the given block handling thrown values
+    fn visit_handled(&mut self, statements: Vec<Stmt>, handler: Option<usize>) {
+        if let Some(handler) = handler {
+            self.handlers.push(handler);
+        }
+        for statement in statements {
+            statement.accept(self);
+        }
+        if handler.is_some() {
+            self.handlers.pop();
+        }
+    }
+
+    /// Ends the current block with a jump out of the loop named by the
+    /// label, or out of the innermost loop if there is no label
+    fn jump(
+        &mut self,
+        statement: String,
+        label: Option<String>,
+        to_exit: bool,
+        kind: &'static str,
+    ) {
+        self.push(statement);
+        let index = self
+            .loops
+            .iter()
+            .rposition(|targets| label.is_none() || targets.label == label);
+        if let Some(index) = index {
+            let target = match to_exit {
+                true => self.loops[index].exit,
+                false => self.loops[index].next,
+            };
+            self.leave(self.current, target, index + 1, Some(kind));
+        }
+        // anything after a jump is unreachable
+        self.current = None;
+    }
+
+    /// Links a block to a target inside the given number of loops, going
+    /// through the finally block of the innermost try statement it leaves
+    fn leave(
+        &mut self,
+        from: Option<usize>,
+        target: usize,
+        loops: usize,
+        label: Option<&'static str>,
+    ) {
+        match self.finalizers.last_mut() {
+            Some(finalizer) if from.is_some() && finalizer.loops >= loops => {
+                let block = finalizer.block;
+                if !finalizer.exits.contains(&(target, loops, label)) {
+                    finalizer.exits.push((target, loops, label));
+                }
+                self.link(from, block, label);
+            }
+            _ => self.link(from, target, label),
+        }
+    }
+
+    /// Connects the last reachable block to the exit block
+    fn finish(&mut self) {
+        self.link(self.current, ControlFlowGraph::EXIT, None);
+    }
+}
+
+impl StmtVisitor<()> for CfgBuilder {
+    fn visit_block_stmt(&mut self, stmt: &Block) {
+        for statement in stmt.statements() {
+            statement.accept(self);
+        }
+    }
+
+    fn visit_break_stmt(&mut self, stmt: &Break) {
+        let statement = self.printer.print_stmt(Stmt::Break(stmt.clone()));
+        let label = stmt.label().map(|label| label.lexeme());
+        self.jump(statement, label, true, "break");
+    }
+
+    fn visit_class_stmt(&mut self, stmt: &Class) {
+        self.push(format!("class {}", stmt.name().lexeme()));
+        self.functions.extend(stmt.methods());
+    }
+
+    fn visit_continue_stmt(&mut self, stmt: &Continue) {
+        let statement = self.printer.print_stmt(Stmt::Continue(stmt.clone()));
+        let label = stmt.label().map(|label| label.lexeme());
+        self.jump(statement, label, false, "continue");
+    }
+
+    fn visit_enum_stmt(&mut self, stmt: &Enum) {
+        self.push(format!("enum {}", stmt.name().lexeme()));
+    }
+
+    fn visit_expression_stmt(&mut self, stmt: &Expression) {
+        let statement = self.printer.print_stmt(Stmt::Expression(stmt.clone()));
+        self.push(statement);
+    }
+
+    fn visit_for_in_stmt(&mut self, stmt: &ForIn) {
+        let header = self.new_block();
+        self.link(self.current, header, None);
+        let iterable = self.printer.print(stmt.iterable());
+        self.graph.blocks[header].statements.push(format!(
+            "for {} in {}",
+            stmt.name().lexeme(),
+            iterable
+        ));
+
+        let body = self.new_block();
+        let exit = self.new_block();
+        self.link(Some(header), body, Some("next"));
+        self.link(Some(header), exit, Some("done"));
+
+        self.loops.push(LoopTargets {
+            label: stmt.label().map(|label| label.lexeme()),
+            next: header,
+            exit,
+        });
+        self.current = Some(body);
+        stmt.body().accept(self);
+        self.loops.pop();
+
+        self.link(self.current, header, None);
+        self.current = Some(exit);
+    }
+
+    fn visit_function_stmt(&mut self, stmt: &Function) {
+        self.push(format!("fun {}", stmt.name().lexeme()));
+        self.functions.push(stmt.clone());
+    }
+
+    fn visit_if_stmt(&mut self, stmt: &If) {
+        let condition = self.printer.print(stmt.condition());
+        self.push(format!("if {}", condition));
+        let branch = self.current;
+        let join = self.new_block();
+
+        let then_block = self.new_block();
+        self.link(branch, then_block, Some("true"));
+        self.current = Some(then_block);
+        stmt.then_branch().accept(self);
+        self.link(self.current, join, None);
+
+        match stmt.else_branch() {
+            Some(else_branch) => {
+                let else_block = self.new_block();
+                self.link(branch, else_block, Some("false"));
+                self.current = Some(else_block);
+                else_branch.accept(self);
+                self.link(self.current, join, None);
+            }
+            None => self.link(branch, join, Some("false")),
+        }
+
+        self.current = Some(join);
+    }
+
+    fn visit_match_stmt(&mut self, stmt: &Match) {
+        let subject = self.printer.print(stmt.subject());
+        self.push(format!("match {}", subject));
+        let branch = self.current;
+        let join = self.new_block();
+
+        // an unguarded arm that matches anything leaves no way past the arms
+        let mut exhaustive = false;
+        for arm in stmt.arms() {
+            let case = self.new_block();
+            self.link(branch, case, Some("case"));
+            self.current = Some(case);
+            let pattern = arm.pattern();
+            match arm.guard() {
+                Some(guard) => {
+                    let guard = self.printer.print(guard);
+                    self.push(format!("case {} if {}", pattern, guard));
+                }
+                None => {
+                    self.push(format!("case {}", pattern));
+                    exhaustive |= matches!(pattern, Pattern::Wildcard(_) | Pattern::Binding(_));
+                }
+            }
+            arm.body().accept(self);
+            self.link(self.current, join, None);
+        }
+
+        if !exhaustive {
+            self.link(branch, join, Some("no match"));
+        }
+        self.current = Some(join);
+    }
+
+    fn visit_print_stmt(&mut self, stmt: &Print) {
+        let statement = self.printer.print_stmt(Stmt::Print(stmt.clone()));
+        self.push(statement);
+    }
+
+    fn visit_return_stmt(&mut self, stmt: &Return) {
+        let statement = self.printer.print_stmt(Stmt::Return(stmt.clone()));
+        self.push(statement);
+        self.leave(self.current, ControlFlowGraph::EXIT, 0, None);
+        // anything after a return is unreachable
+        self.current = None;
+    }
+
+    fn visit_throw_stmt(&mut self, stmt: &Throw) {
+        let statement = self.printer.print_stmt(Stmt::Throw(stmt.clone()));
+        self.push(statement);
+        let handler = self
+            .handlers
+            .last()
+            .copied()
+            .unwrap_or(ControlFlowGraph::EXIT);
+        if let Some(finalizer) = self.finalizers.iter_mut().find(|f| f.block == handler) {
+            finalizer.rethrows = true;
+        }
+        self.link(self.current, handler, Some("throw"));
+        self.current = None;
+    }
+
+    fn visit_trait_stmt(&mut self, stmt: &Trait) {
+        self.push(format!("trait {}", stmt.name().lexeme()));
+        self.functions.extend(stmt.methods());
+    }
+
+    fn visit_try_stmt(&mut self, stmt: &Try) {
+        self.push("try".to_string());
+        let body = self.new_block();
+        let catch_block = stmt.catch().map(|_| self.new_block());
+        let finally_block = stmt.finally().map(|_| self.new_block());
+        let after = self.new_block();
+        // control leaves the body and the catch clause through the finally block
+        let end = finally_block.unwrap_or(after);
+
+        if let Some(block) = finally_block {
+            self.finalizers.push(Finalizer {
+                block,
+                loops: self.loops.len(),
+                exits: Vec::new(),
+                rethrows: catch_block.is_none(),
+            });
+        }
+
+        self.link(self.current, body, None);
+        self.current = Some(body);
+        self.visit_handled(stmt.body(), catch_block.or(finally_block));
+        self.link(self.current, end, None);
+        // anything in the body may throw, unless a throw statement there already does
+        let handler = catch_block.or(finally_block);
+        if let Some(handler) = handler {
+            if !self.graph.blocks[body]
+                .successors
+                .iter()
+                .any(|(to, _)| *to == handler)
+            {
+                let label = match catch_block {
+                    Some(_) => "catch",
+                    None => "throw",
+                };
+                self.link(Some(body), handler, Some(label));
+            }
+        }
+
+        if let (Some(catch), Some(catch_block)) = (stmt.catch(), catch_block) {
+            self.current = Some(catch_block);
+            self.push(format!("catch {}", catch.name().lexeme()));
+            self.visit_handled(catch.body(), finally_block);
+            self.link(self.current, end, None);
+        }
+
+        if let (Some(finally), Some(finally_block)) = (stmt.finally(), finally_block) {
+            let finalizer = self
+                .finalizers
+                .pop()
+                .expect("the finalizer was pushed above");
+            self.current = Some(finally_block);
+            self.push("finally".to_string());
+            self.visit_handled(finally, None);
+            self.link(self.current, after, None);
+
+            // then carries on with whatever made control leave the rest of the statement
+            let end = self.current;
+            for (target, loops, label) in finalizer.exits {
+                self.leave(end, target, loops, label);
+            }
+            if finalizer.rethrows {
+                let handler = self
+                    .handlers
+                    .last()
+                    .copied()
+                    .unwrap_or(ControlFlowGraph::EXIT);
+                self.link(end, handler, Some("throw"));
+            }
+        }
+
+        self.current = Some(after);
+    }
+
+    fn visit_var_stmt(&mut self, stmt: &Var) {
+        let statement = self.printer.print_stmt(Stmt::Var(stmt.clone()));
+        self.push(statement);
+    }
+
+    fn visit_var_pattern_stmt(&mut self, stmt: &VarPattern) {
+        let statement = self.printer.print_stmt(Stmt::VarPattern(stmt.clone()));
+        self.push(statement);
+    }
+
+    fn visit_while_stmt(&mut self, stmt: &While) {
+        let header = self.new_block();
+        self.link(self.current, header, None);
+        let condition = self.printer.print(stmt.condition());
+        self.graph.blocks[header]
+            .statements
+            .push(format!("while {}", condition));
+
+        let body = self.new_block();
+        let increment = stmt
+            .increment()
+            .map(|increment| (self.new_block(), increment));
+        let exit = self.new_block();
+        self.link(Some(header), body, Some("true"));
+        self.link(Some(header), exit, Some("false"));
+
+        self.loops.push(LoopTargets {
+            label: stmt.label().map(|label| label.lexeme()),
+            next: increment.as_ref().map_or(header, |(block, _)| *block),
+            exit,
+        });
+        self.current = Some(body);
+        stmt.body().accept(self);
+        self.loops.pop();
+
+        match increment {
+            Some((block, increment)) => {
+                let statement = self.printer.print_stmt(Stmt::Expression(Expression::new(
+                    NodeId::default(),
+                    increment,
+                )));
+                self.graph.blocks[block].statements.push(statement);
+                self.link(self.current, block, None);
+                self.link(Some(block), header, None);
+            }
+            None => self.link(self.current, header, None),
+        }
+        self.current = Some(exit);
+    }
+
+    fn visit_yield_stmt(&mut self, stmt: &Yield) {
+        // the generator pauses here and carries on from the next statement
+        let statement = self.printer.print_stmt(Stmt::Yield(stmt.clone()));
+        self.push(statement);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rlox::{parser::Parser, scanner::Scanner};
+
+    fn build_source(source: &str) -> Vec<ControlFlowGraph> {
+        let tokens = Scanner::new(source.to_string()).scan_tokens().unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+        build(statements)
+    }
+
+    #[test]
+    fn test_build_per_function() {
+        let graphs = build_source(
+            r#"
+fun outer() {
+    fun inner() { return 1; }
+    return inner;
+}
+class Point { init(x) { this.x = x; } get() { return this.x; } }
+print outer;
+"#,
+        );
+        let names = graphs.iter().map(|g| g.name()).collect::<Vec<&str>>();
+        assert_eq!(names, vec!["<script>", "outer", "init", "get", "inner"]);
+    }
+
+    #[test]
+    fn test_build_if_and_return() {
+        let graphs = build_source(
+            r#"
+fun sign(n) {
+    if (n < 0) return -1;
+    return 1;
+}
+"#,
+        );
+        let blocks = graphs[1].blocks();
+
+        // entry branches to the then block and to the join block
+        assert_eq!(blocks[0].statements(), &vec!["if (< n 0)".to_string()]);
+        assert_eq!(
+            blocks[0].successors(),
+            &vec![(3, Some("true")), (2, Some("false"))]
+        );
+        // the then block returns straight to the exit block
+        assert_eq!(
+            blocks[3].successors(),
+            &vec![(ControlFlowGraph::EXIT, None)]
+        );
+        assert_eq!(blocks[2].statements(), &vec!["(return 1)".to_string()]);
+        assert_eq!(
+            blocks[2].successors(),
+            &vec![(ControlFlowGraph::EXIT, None)]
+        );
+    }
+
+    #[test]
+    fn test_build_for_loop() {
+        let graphs = build_source("for (var i = 0; i < 3; i = i + 1) print i;");
+        let blocks = graphs[0].blocks();
+
+        // entry -> header, header -> body / after, body -> increment -> header
+        assert_eq!(blocks[0].successors(), &vec![(2, None)]);
+        assert_eq!(blocks[2].statements(), &vec!["while (< i 3)".to_string()]);
+        assert_eq!(
+            blocks[2].successors(),
+            &vec![(3, Some("true")), (5, Some("false"))]
+        );
+        assert_eq!(blocks[3].statements(), &vec!["(print i)".to_string()]);
+        assert_eq!(blocks[3].successors(), &vec![(4, None)]);
+        assert_eq!(
+            blocks[4].statements(),
+            &vec!["(; (= i (+ i 1)))".to_string()]
+        );
+        assert_eq!(blocks[4].successors(), &vec![(2, None)]);
+        assert_eq!(
+            blocks[5].successors(),
+            &vec![(ControlFlowGraph::EXIT, None)]
+        );
+
+        assert!(to_dot(&graphs).contains("f0_b2 -> f0_b3 [label=\"true\"];"));
+    }
+
+    #[test]
+    fn test_build_break_and_continue() {
+        let graphs = build_source(
+            "outer: for (var i = 0; i < 3; i = i + 1) { while (a) { if (b) continue outer; break; } }",
+        );
+        let blocks = graphs[0].blocks();
+
+        // blocks: 2 outer header, 3 outer body, 4 increment, 5 after,
+        // 6 inner header, 7 inner body, 8 inner after, 9 join, 10 then
+        assert_eq!(
+            blocks[10].statements(),
+            &vec!["(continue outer)".to_string()]
+        );
+        assert_eq!(blocks[10].successors(), &vec![(4, Some("continue"))]);
+        assert_eq!(blocks[9].statements(), &vec!["(break)".to_string()]);
+        assert_eq!(blocks[9].successors(), &vec![(8, Some("break"))]);
+        assert_eq!(blocks[8].successors(), &vec![(4, None)]);
+    }
+
+    #[test]
+    fn test_build_for_in_loop() {
+        let graphs = build_source("for (var x in xs) { if (x) break; print x; }");
+        let blocks = graphs[0].blocks();
+
+        // entry -> header, header -> body / after, body -> header
+        assert_eq!(blocks[0].successors(), &vec![(2, None)]);
+        assert_eq!(blocks[2].statements(), &vec!["for x in xs".to_string()]);
+        assert_eq!(
+            blocks[2].successors(),
+            &vec![(3, Some("next")), (4, Some("done"))]
+        );
+        // blocks: 5 join, 6 then
+        assert_eq!(blocks[6].successors(), &vec![(4, Some("break"))]);
+        assert_eq!(blocks[5].successors(), &vec![(2, None)]);
+    }
+
+    #[test]
+    fn test_build_try_catch() {
+        let graphs =
+            build_source("try { if (a) throw 1; f(); } catch (e) { print e; } finally { g(); }");
+        let blocks = graphs[0].blocks();
+
+        // entry -> body, body -> catch / finally
+        assert_eq!(blocks[0].statements(), &vec!["try".to_string()]);
+        assert_eq!(blocks[0].successors(), &vec![(2, None)]);
+        assert_eq!(blocks[2].successors()[2], (3, Some("catch")));
+        // the throw goes to the catch clause
+        assert_eq!(blocks[7].statements(), &vec!["(throw 1)".to_string()]);
+        assert_eq!(blocks[7].successors(), &vec![(3, Some("throw"))]);
+        // the body and catch clause both leave through the finally block
+        assert_eq!(blocks[6].successors(), &vec![(4, None)]);
+        assert_eq!(blocks[3].successors(), &vec![(4, None)]);
+        assert_eq!(
+            blocks[4].statements(),
+            &vec!["finally".to_string(), "(; (call g))".to_string()]
+        );
+        assert_eq!(blocks[4].successors(), &vec![(5, None)]);
+    }
+
+    #[test]
+    fn test_build_try_finally_jumps() {
+        let graphs = build_source(
+            "fun f() { try { throw 1; } catch (e) { return 2; } finally { print 3; } }",
+        );
+        let blocks = graphs[1].blocks();
+
+        // blocks: 2 body, 3 catch, 4 finally, 5 after
+        assert_eq!(blocks[2].successors(), &vec![(3, Some("throw"))]);
+        // the return leaves through the finally block, which goes on to the exit
+        assert_eq!(blocks[3].successors(), &vec![(4, None)]);
+        assert_eq!(
+            blocks[4].successors(),
+            &vec![(5, None), (ControlFlowGraph::EXIT, None)]
+        );
+
+        let graphs = build_source(
+            "while (a) { try { try { if (b) break; continue; } finally { f(); } } finally { g(); } }",
+        );
+        let blocks = graphs[0].blocks();
+
+        // blocks: 2 header, 3 body, 4 after, 5 outer try body, 6 outer finally,
+        // 7 outer after, 8 inner body, 9 inner finally, 10 inner after, 11 join, 12 then
+        assert_eq!(blocks[12].successors(), &vec![(9, Some("break"))]);
+        assert_eq!(blocks[11].successors(), &vec![(9, Some("continue"))]);
+        // both jumps go through the inner and then the outer finally block,
+        // and so does an exception, as there is no catch clause
+        assert_eq!(
+            blocks[9].successors(),
+            &vec![
+                (10, None),
+                (6, Some("break")),
+                (6, Some("continue")),
+                (6, Some("throw"))
+            ]
+        );
+        assert_eq!(
+            blocks[6].successors(),
+            &vec![
+                (7, None),
+                (4, Some("break")),
+                (2, Some("continue")),
+                (ControlFlowGraph::EXIT, Some("throw"))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_match() {
+        let graphs = build_source(r#"match (x) { 1 => print "one"; n if n > 1 => return n; }"#);
+        let blocks = graphs[0].blocks();
+
+        // every arm branches off the match, and nothing may match
+        assert_eq!(blocks[0].statements(), &vec!["match x".to_string()]);
+        assert_eq!(
+            blocks[0].successors(),
+            &vec![(3, Some("case")), (4, Some("case")), (2, Some("no match"))]
+        );
+        assert_eq!(blocks[3].successors(), &vec![(2, None)]);
+        assert_eq!(
+            blocks[4].statements(),
+            &vec!["case n if (> n 1)".to_string(), "(return n)".to_string()]
+        );
+        assert_eq!(
+            blocks[4].successors(),
+            &vec![(ControlFlowGraph::EXIT, None)]
+        );
+
+        // an unguarded wildcard always matches
+        let graphs = build_source("match (x) { 1 => f(); _ => g(); }");
+        let blocks = graphs[0].blocks();
+        assert_eq!(
+            blocks[0].successors(),
+            &vec![(3, Some("case")), (4, Some("case"))]
+        );
+    }
+}
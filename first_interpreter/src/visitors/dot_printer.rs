@@ -0,0 +1,502 @@
+use crate::{
+    expressions::{
+        expr::{Expr, Visitor as ExprVisitor},
+        Assign, AssignPattern, Binary, Call, Get, Grouping, Index, Lambda, List, Literal, Logical,
+        Map, Set, SetIndex, Super, Ternary, This, Unary, Variable,
+    },
+    rlox::TokenLiteral,
+    stmt::{
+        stmt::Visitor as StmtVisitor, Block, Break, Class, Continue, Enum, Expression, ForIn,
+        Function, If, Match, Param, Print, Return, Stmt, Throw, Trait, Try, Var, VarPattern, While,
+        Yield,
+    },
+};
+
+/// Represents a printer that renders the abstract syntax tree
+/// as a Graphviz DOT graph
+#[derive(Default)]
+pub struct DotPrinter {
+    /// The node declarations emitted so far
+    nodes: Vec<String>,
+    /// The edge declarations emitted so far
+    edges: Vec<String>,
+}
+
+impl DotPrinter {
+    /// Constructs a new DotPrinter
+    pub fn new() -> DotPrinter {
+        DotPrinter {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    /// Renders a list of statements as a DOT graph rooted at a program node
+    pub fn print_program(&mut self, statements: Vec<Stmt>) -> String {
+        self.nodes.clear();
+        self.edges.clear();
+
+        let root = self.node("program");
+        for statement in statements {
+            let child = statement.accept(self);
+            self.edge(root, child, None);
+        }
+
+        let mut builder = String::new();
+        builder.push_str("digraph ast {\n");
+        builder.push_str("  node [shape=box, fontname=\"monospace\"];\n");
+        for line in self.nodes.iter().chain(self.edges.iter()) {
+            builder.push_str("  ");
+            builder.push_str(line);
+            builder.push('\n');
+        }
+        builder.push('}');
+
+        builder
+    }
+
+    /// Declares a new node with the given label and returns its id
+    fn node(&mut self, label: &str) -> usize {
+        let id = self.nodes.len();
+        self.nodes
+            .push(format!("n{} [label=\"{}\"];", id, escape(label)));
+        id
+    }
+
+    /// Declares an edge between two nodes, optionally naming the child's role
+    fn edge(&mut self, from: usize, to: usize, label: Option<&str>) {
+        match label {
+            Some(label) => self.edges.push(format!(
+                "n{} -> n{} [label=\"{}\"];",
+                from,
+                to,
+                escape(label)
+            )),
+            None => self.edges.push(format!("n{} -> n{};", from, to)),
+        }
+    }
+
+    /// Declares a node and links every expression to it as a child
+    fn node_with_exprs(&mut self, label: &str, exprs: Vec<(Expr, Option<&str>)>) -> usize {
+        let id = self.node(label);
+        for (expr, role) in exprs {
+            let child = expr.accept(self);
+            self.edge(id, child, role);
+        }
+        id
+    }
+
+    /// Declares a node for a function, linking the default value of
+    /// each parameter and then every statement of the body to it
+    fn function_node(&mut self, name: &str, params: Vec<Param>, body: Vec<Stmt>) -> usize {
+        let signature = params
+            .iter()
+            .map(|param| match param.is_rest() {
+                true => format!("...{}", param.name().lexeme()),
+                false => param.name().lexeme(),
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+        let id = self.node(&format!("{}({})", name, signature));
+        for param in params {
+            if let Some(default) = param.default() {
+                let child = default.accept(self);
+                self.edge(
+                    id,
+                    child,
+                    Some(&format!("default {}", param.name().lexeme())),
+                );
+            }
+        }
+        for statement in body {
+            let child = statement.accept(self);
+            self.edge(id, child, None);
+        }
+        id
+    }
+
+    /// Declares a node and links every statement to it as a child
+    fn node_with_stmts(&mut self, label: &str, statements: Vec<Stmt>) -> usize {
+        let id = self.node(label);
+        for statement in statements {
+            let child = statement.accept(self);
+            self.edge(id, child, None);
+        }
+        id
+    }
+}
+
+/// Escapes a label so it can be embedded in a quoted DOT string
+pub(crate) fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Implements the expression Visitor trait for DotPrinter
+impl ExprVisitor<usize> for DotPrinter {
+    fn visit_assign_expr(&mut self, expr: &Assign) -> usize {
+        let label = match expr.operator() {
+            Some(operator) => format!("assign {} {}=", expr.name().lexeme(), operator.lexeme()),
+            None => format!("assign {}", expr.name().lexeme()),
+        };
+        self.node_with_exprs(&label, vec![(expr.value(), None)])
+    }
+
+    fn visit_assign_pattern_expr(&mut self, expr: &AssignPattern) -> usize {
+        let label = format!("assign {}", expr.pattern());
+        self.node_with_exprs(&label, vec![(expr.value(), None)])
+    }
+
+    fn visit_binary_expr(&mut self, expr: &Binary) -> usize {
+        let label = format!("binary {}", expr.operator().lexeme());
+        self.node_with_exprs(&label, vec![(expr.left(), None), (expr.right(), None)])
+    }
+
+    fn visit_call_expr(&mut self, expr: &Call) -> usize {
+        let mut exprs = vec![(expr.callee(), Some("callee"))];
+        for argument in expr.arguments() {
+            exprs.push((argument.clone(), Some("arg")));
+        }
+        let id = self.node_with_exprs("call", exprs);
+        for (name, argument) in expr.named_arguments() {
+            let child = argument.accept(self);
+            self.edge(id, child, Some(&format!("arg {}", name.lexeme())));
+        }
+        id
+    }
+
+    fn visit_get_expr(&mut self, expr: &Get) -> usize {
+        let label = match expr.is_optional() {
+            true => format!("get? {}", expr.name().lexeme()),
+            false => format!("get {}", expr.name().lexeme()),
+        };
+        self.node_with_exprs(&label, vec![(expr.object(), None)])
+    }
+
+    fn visit_grouping_expr(&mut self, expr: &Grouping) -> usize {
+        self.node_with_exprs("group", vec![(expr.expression(), None)])
+    }
+
+    fn visit_index_expr(&mut self, expr: &Index) -> usize {
+        self.node_with_exprs(
+            "index",
+            vec![
+                (expr.object(), Some("object")),
+                (expr.index(), Some("index")),
+            ],
+        )
+    }
+
+    fn visit_lambda_expr(&mut self, expr: &Lambda) -> usize {
+        let label = match expr.is_generator() {
+            true => "lambda*",
+            false => "lambda",
+        };
+        self.function_node(label, expr.params(), expr.body())
+    }
+
+    fn visit_list_expr(&mut self, expr: &List) -> usize {
+        let elements = expr
+            .elements()
+            .iter()
+            .map(|element| (element.clone(), None))
+            .collect();
+        self.node_with_exprs("list", elements)
+    }
+
+    fn visit_literal_expr(&mut self, expr: &Literal) -> usize {
+        let value = expr
+            .value()
+            .owned::<TokenLiteral>()
+            .expect("Must be a valid literal");
+        self.node(&format!("literal {}", value))
+    }
+
+    fn visit_logical_expr(&mut self, expr: &Logical) -> usize {
+        let label = format!("logical {}", expr.operator().lexeme());
+        self.node_with_exprs(&label, vec![(expr.left(), None), (expr.right(), None)])
+    }
+
+    fn visit_map_expr(&mut self, expr: &Map) -> usize {
+        let mut exprs = Vec::new();
+        for (key, value) in expr.entries() {
+            exprs.push((key.clone(), Some("key")));
+            exprs.push((value.clone(), Some("value")));
+        }
+        self.node_with_exprs("map", exprs)
+    }
+
+    fn visit_set_expr(&mut self, expr: &Set) -> usize {
+        let label = match expr.operator() {
+            Some(operator) => format!("set {} {}=", expr.name().lexeme(), operator.lexeme()),
+            None => format!("set {}", expr.name().lexeme()),
+        };
+        self.node_with_exprs(
+            &label,
+            vec![
+                (expr.object(), Some("object")),
+                (expr.value(), Some("value")),
+            ],
+        )
+    }
+
+    fn visit_super_expr(&mut self, expr: &Super) -> usize {
+        self.node(&format!("super {}", expr.method().lexeme()))
+    }
+
+    fn visit_ternary_expr(&mut self, expr: &Ternary) -> usize {
+        self.node_with_exprs(
+            "?:",
+            vec![
+                (expr.condition(), Some("condition")),
+                (expr.then_branch(), Some("then")),
+                (expr.else_branch(), Some("else")),
+            ],
+        )
+    }
+
+    fn visit_this_expr(&mut self, _expr: &This) -> usize {
+        self.node("this")
+    }
+
+    fn visit_set_index_expr(&mut self, expr: &SetIndex) -> usize {
+        let label = match expr.operator() {
+            Some(operator) => format!("set index {}=", operator.lexeme()),
+            None => "set index".to_string(),
+        };
+        self.node_with_exprs(
+            &label,
+            vec![
+                (expr.object(), Some("object")),
+                (expr.index(), Some("index")),
+                (expr.value(), Some("value")),
+            ],
+        )
+    }
+
+    fn visit_unary_expr(&mut self, expr: &Unary) -> usize {
+        let label = format!("unary {}", expr.operator().lexeme());
+        self.node_with_exprs(&label, vec![(expr.right(), None)])
+    }
+
+    fn visit_variable_expr(&mut self, expr: &Variable) -> usize {
+        self.node(&format!("variable {}", expr.name().lexeme()))
+    }
+}
+
+/// Implements the statement Visitor trait for DotPrinter
+impl StmtVisitor<usize> for DotPrinter {
+    fn visit_block_stmt(&mut self, stmt: &Block) -> usize {
+        self.node_with_stmts("block", stmt.statements())
+    }
+
+    fn visit_break_stmt(&mut self, stmt: &Break) -> usize {
+        match stmt.label() {
+            Some(label) => self.node(&format!("break {}", label.lexeme())),
+            None => self.node("break"),
+        }
+    }
+
+    fn visit_class_stmt(&mut self, stmt: &Class) -> usize {
+        let mut label = match stmt.superclass() {
+            Some(superclass) => format!(
+                "class {} < {}",
+                stmt.name().lexeme(),
+                superclass.name().lexeme()
+            ),
+            None => format!("class {}", stmt.name().lexeme()),
+        };
+        let traits: Vec<String> = stmt
+            .traits()
+            .iter()
+            .map(|trait_| trait_.name().lexeme())
+            .collect();
+        if !traits.is_empty() {
+            label = format!("{} with {}", label, traits.join(", "));
+        }
+        let methods = stmt.methods().into_iter().map(Stmt::Function).collect();
+        let id = self.node_with_stmts(&label, methods);
+        for field in stmt.fields() {
+            let label = format!("class {}", field.name().lexeme());
+            let child = self.node_with_exprs(&label, vec![(field.initializer(), None)]);
+            self.edge(id, child, Some("field"));
+        }
+        id
+    }
+
+    fn visit_continue_stmt(&mut self, stmt: &Continue) -> usize {
+        match stmt.label() {
+            Some(label) => self.node(&format!("continue {}", label.lexeme())),
+            None => self.node("continue"),
+        }
+    }
+
+    fn visit_enum_stmt(&mut self, stmt: &Enum) -> usize {
+        let id = self.node(&format!("enum {}", stmt.name().lexeme()));
+        for variant in stmt.variants() {
+            let child = self.node(&format!("variant {}", variant));
+            self.edge(id, child, None);
+        }
+        id
+    }
+
+    fn visit_expression_stmt(&mut self, stmt: &Expression) -> usize {
+        self.node_with_exprs("expression", vec![(stmt.expression(), None)])
+    }
+
+    fn visit_for_in_stmt(&mut self, stmt: &ForIn) -> usize {
+        let label = match stmt.label() {
+            Some(label) => format!("for {}: {} in", label.lexeme(), stmt.name().lexeme()),
+            None => format!("for {} in", stmt.name().lexeme()),
+        };
+        let id = self.node_with_exprs(&label, vec![(stmt.iterable(), Some("iterable"))]);
+        let body = stmt.body().accept(self);
+        self.edge(id, body, Some("body"));
+        id
+    }
+
+    fn visit_function_stmt(&mut self, stmt: &Function) -> usize {
+        let keyword = match stmt.is_generator() {
+            true => "fun*",
+            false => "fun",
+        };
+        let name = match (stmt.is_class_method(), stmt.is_getter()) {
+            (true, true) => format!("class getter {}", stmt.name().lexeme()),
+            (true, false) => format!("class {} {}", keyword, stmt.name().lexeme()),
+            (false, true) => format!("getter {}", stmt.name().lexeme()),
+            (false, false) => format!("{} {}", keyword, stmt.name().lexeme()),
+        };
+        self.function_node(&name, stmt.params(), stmt.body())
+    }
+
+    fn visit_if_stmt(&mut self, stmt: &If) -> usize {
+        let id = self.node_with_exprs("if", vec![(stmt.condition(), Some("condition"))]);
+        let then_branch = stmt.then_branch().accept(self);
+        self.edge(id, then_branch, Some("then"));
+        if let Some(else_branch) = stmt.else_branch() {
+            let else_branch = else_branch.accept(self);
+            self.edge(id, else_branch, Some("else"));
+        }
+        id
+    }
+
+    fn visit_match_stmt(&mut self, stmt: &Match) -> usize {
+        let id = self.node_with_exprs("match", vec![(stmt.subject(), Some("subject"))]);
+        for arm in stmt.arms() {
+            let label = format!("case {}", arm.pattern());
+            let case = match arm.guard() {
+                Some(guard) => self.node_with_exprs(&label, vec![(guard, Some("guard"))]),
+                None => self.node(&label),
+            };
+            let body = arm.body().accept(self);
+            self.edge(case, body, Some("body"));
+            self.edge(id, case, Some("arm"));
+        }
+        id
+    }
+
+    fn visit_print_stmt(&mut self, stmt: &Print) -> usize {
+        self.node_with_exprs("print", vec![(stmt.expression(), None)])
+    }
+
+    fn visit_return_stmt(&mut self, stmt: &Return) -> usize {
+        match stmt.value() {
+            Some(value) => self.node_with_exprs("return", vec![(value, None)]),
+            None => self.node("return"),
+        }
+    }
+
+    fn visit_throw_stmt(&mut self, stmt: &Throw) -> usize {
+        self.node_with_exprs("throw", vec![(stmt.value(), None)])
+    }
+
+    fn visit_trait_stmt(&mut self, stmt: &Trait) -> usize {
+        let label = format!("trait {}", stmt.name().lexeme());
+        let methods = stmt.methods().into_iter().map(Stmt::Function).collect();
+        self.node_with_stmts(&label, methods)
+    }
+
+    fn visit_try_stmt(&mut self, stmt: &Try) -> usize {
+        let id = self.node("try");
+        let body = self.node_with_stmts("block", stmt.body());
+        self.edge(id, body, Some("body"));
+        if let Some(catch) = stmt.catch() {
+            let label = format!("catch {}", catch.name().lexeme());
+            let catch = self.node_with_stmts(&label, catch.body());
+            self.edge(id, catch, Some("catch"));
+        }
+        if let Some(finally) = stmt.finally() {
+            let finally = self.node_with_stmts("block", finally);
+            self.edge(id, finally, Some("finally"));
+        }
+        id
+    }
+
+    fn visit_var_stmt(&mut self, stmt: &Var) -> usize {
+        let keyword = if stmt.is_const() { "const" } else { "var" };
+        let label = format!("{} {}", keyword, stmt.name().lexeme());
+        match stmt.is_initialized() {
+            true => self.node_with_exprs(&label, vec![(stmt.initializer(), None)]),
+            false => self.node(&label),
+        }
+    }
+
+    fn visit_var_pattern_stmt(&mut self, stmt: &VarPattern) -> usize {
+        let keyword = if stmt.is_const() { "const" } else { "var" };
+        let label = format!("{} {}", keyword, stmt.pattern());
+        self.node_with_exprs(&label, vec![(stmt.initializer(), None)])
+    }
+
+    fn visit_while_stmt(&mut self, stmt: &While) -> usize {
+        let label = match stmt.label() {
+            Some(label) => format!("while {}:", label.lexeme()),
+            None => "while".to_string(),
+        };
+        let id = self.node_with_exprs(&label, vec![(stmt.condition(), Some("condition"))]);
+        let body = stmt.body().accept(self);
+        self.edge(id, body, Some("body"));
+        if let Some(increment) = stmt.increment() {
+            let increment = increment.accept(self);
+            self.edge(id, increment, Some("increment"));
+        }
+        id
+    }
+
+    fn visit_yield_stmt(&mut self, stmt: &Yield) -> usize {
+        self.node_with_exprs("yield", vec![(stmt.value(), None)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rlox::{parser::Parser, scanner::Scanner};
+
+    #[test]
+    fn test_dot_printer() {
+        let tokens = Scanner::new(r#"if (a < 1) print "one";"#.to_string())
+            .scan_tokens()
+            .unwrap();
+        let statements = Parser::new(tokens).parse().unwrap();
+        let output = DotPrinter::new().print_program(statements);
+
+        assert_eq!(
+            output,
+            r#"digraph ast {
+  node [shape=box, fontname="monospace"];
+  n0 [label="program"];
+  n1 [label="if"];
+  n2 [label="binary <"];
+  n3 [label="variable a"];
+  n4 [label="literal 1"];
+  n5 [label="print"];
+  n6 [label="literal \"one\""];
+  n2 -> n3;
+  n2 -> n4;
+  n1 -> n2 [label="condition"];
+  n5 -> n6;
+  n1 -> n5 [label="then"];
+  n0 -> n1;
+}"#
+        );
+    }
+}
@@ -0,0 +1,1128 @@
+//! Pretty-prints the syntax tree back into Lox source code with
+//! consistent indentation and spacing.
+//!
+//! Comments and blank lines are not part of the syntax tree, so they are
+//! taken from the trivia the parser recorded for every statement list,
+//! see [`Parser::statement_trivia`]. Comments inside a statement, like
+//! one between the arguments of a call, have nowhere to go once the
+//! statement is reprinted, so such a statement is printed from its
+//! tokens instead, keeping its line breaks and every comment in place.
+//!
+//! [`Parser::statement_trivia`]: crate::rlox::parser::Parser::statement_trivia
+
+use std::collections::VecDeque;
+
+use crate::{
+    expressions::{
+        expr::{Expr, Visitor as ExprVisitor},
+        Assign, AssignPattern, Binary, Call, Get, Grouping, Index, Lambda, List, Literal, Logical,
+        Map, Set, SetIndex, Super, Ternary, This, Unary, Variable,
+    },
+    rlox::{
+        parser::StatementTrivia,
+        token::{TokenType, Trivia},
+        Token, TokenLiteral,
+    },
+    stmt::{
+        stmt::Visitor as StmtVisitor, Block, Break, Class, Continue, Enum, EnumVariant, Expression,
+        ForIn, Function, If, Match, MatchArm, Param, Print, Return, Stmt, Throw, Trait, Try, Var,
+        VarPattern, While, Yield,
+    },
+};
+
+/// The text used for one level of indentation
+const INDENT: &str = "    ";
+/// The width after which the arguments of a call and the elements
+/// of a list are put on their own lines
+const LINE_WIDTH: usize = 100;
+
+/// Represents a printer that turns the abstract syntax tree into source code
+pub struct Formatter {
+    /// The lines written so far
+    lines: Vec<String>,
+    /// The current indentation level
+    indent: usize,
+    /// Text to start the next line with in place of the indentation,
+    /// used to keep a statement on the same line as its keyword
+    prefix: Option<String>,
+    /// The trivia of the statements that have not been written yet
+    trivia: VecDeque<StatementTrivia>,
+    /// The column the expression being printed starts at
+    column: usize,
+}
+
+impl Formatter {
+    /// Constructs a new Formatter from the trivia recorded by the parser
+    pub fn new(trivia: Vec<StatementTrivia>) -> Formatter {
+        Formatter {
+            lines: Vec::new(),
+            indent: 0,
+            prefix: None,
+            trivia: VecDeque::from(trivia),
+            column: 0,
+        }
+    }
+
+    /// Formats a program, ending it with a single newline
+    pub fn format(&mut self, statements: Vec<Stmt>) -> String {
+        self.statement_list(statements);
+
+        let mut output = self.lines.join("\n");
+        output.push('\n');
+        output
+    }
+
+    /// Writes the statements of a list along with their trivia
+    fn statement_list(&mut self, statements: Vec<Stmt>) {
+        for statement in statements {
+            self.member(|formatter| statement.accept(formatter));
+        }
+
+        // comments before the end of the list stay inside it
+        let end = self.trivia.pop_front().unwrap_or_default();
+        self.leading_trivia(end.leading, false);
+    }
+
+    /// Writes comments and, if allowed, blank lines
+    fn leading_trivia(&mut self, trivia: Vec<Trivia>, blank_lines: bool) {
+        for item in trivia {
+            match item {
+                Trivia::Comment(text) => self.line(text.trim_end()),
+                Trivia::TrailingComment(text) => self.append(text.trim_end()),
+                Trivia::BlankLine if blank_lines => self.blank_line(),
+                Trivia::BlankLine => {}
+            }
+        }
+    }
+
+    /// Writes a statement, or a member of a class or a match, after the
+    /// trivia before it, printing it from its tokens if it has a comment
+    /// inside it and with the closure otherwise
+    fn member(&mut self, write: impl FnOnce(&mut Formatter)) {
+        let trivia = self.trivia.pop_front().unwrap_or_default();
+        self.leading_trivia(trivia.leading, true);
+        if trivia.tokens.is_empty() {
+            write(self);
+            return;
+        }
+
+        // the statements nested in it are printed along with it
+        self.trivia.drain(..trivia.nested.min(self.trivia.len()));
+        self.tokens(&trivia.tokens);
+    }
+
+    /// Writes tokens with the line breaks they were written with, and
+    /// the comments found before each, indenting lines by the brackets
+    /// open at their start
+    ///
+    /// The comments before the first token were already written.
+    fn tokens(&mut self, tokens: &[Token]) {
+        let mut text = String::new();
+        let mut depth = 0usize;
+        let mut previous: Option<&Token> = None;
+        // a minus is unary unless it follows an operand
+        let mut unary = false;
+        for token in tokens {
+            if let Some(previous) = previous {
+                for item in token.trivia() {
+                    match item {
+                        Trivia::Comment(comment) => {
+                            self.token_line(&mut text);
+                            self.line(&format!("{}{}", INDENT.repeat(depth), comment.trim_end()));
+                        }
+                        Trivia::TrailingComment(comment) => {
+                            text = format!("{} {}", text, comment.trim_end());
+                            self.token_line(&mut text);
+                        }
+                        Trivia::BlankLine => {}
+                    }
+                }
+                if token.line() > previous.line() {
+                    self.token_line(&mut text);
+                }
+            }
+
+            if is_closing(token) {
+                depth = depth.saturating_sub(1);
+            }
+            match previous {
+                Some(previous) if !text.is_empty() && !unary && spaced(previous, token) => {
+                    text.push(' ')
+                }
+                _ => {}
+            }
+            if text.is_empty() {
+                text.push_str(&INDENT.repeat(depth));
+            }
+            text.push_str(&token.lexeme());
+            if is_opening(token) {
+                depth += 1;
+            }
+            unary = token.kind() == TokenType::Minus && !previous.is_some_and(ends_operand);
+            previous = Some(token);
+        }
+        self.token_line(&mut text);
+    }
+
+    /// Writes the line of tokens gathered so far, if there is one
+    fn token_line(&mut self, text: &mut String) {
+        if !text.is_empty() {
+            let line = std::mem::take(text);
+            self.line(&line);
+        }
+    }
+
+    /// Writes a line at the current indentation, or after the pending prefix
+    fn line(&mut self, text: &str) {
+        let start = match self.prefix.take() {
+            Some(prefix) => prefix,
+            None => INDENT.repeat(self.indent),
+        };
+        // expressions split over several lines carry their own indentation
+        let mut parts = text.split('\n');
+        self.lines
+            .push(format!("{}{}", start, parts.next().unwrap_or_default()));
+        self.lines.extend(parts.map(|part| part.to_string()));
+    }
+
+    /// Appends a comment to the last line written
+    fn append(&mut self, text: &str) {
+        match self.lines.last_mut() {
+            Some(last) => {
+                last.push(' ');
+                last.push_str(text);
+            }
+            None => self.line(text),
+        }
+    }
+
+    /// Writes an empty line, unless it would open a file or a block
+    fn blank_line(&mut self) {
+        match self.lines.last() {
+            Some(last) if !last.is_empty() && !last.ends_with('{') => {
+                self.lines.push(String::new())
+            }
+            _ => {}
+        }
+    }
+
+    /// Adds text to the start of the next line
+    fn push_prefix(&mut self, text: &str) {
+        let start = match self.prefix.take() {
+            Some(prefix) => prefix,
+            None => INDENT.repeat(self.indent),
+        };
+        self.prefix = Some(format!("{}{}", start, text));
+    }
+
+    /// Returns the column the next character written would be at
+    fn current_column(&self) -> usize {
+        match &self.prefix {
+            Some(prefix) => prefix.len(),
+            None => INDENT.len() * self.indent,
+        }
+    }
+
+    /// Prints an expression that starts `offset` characters into the line
+    fn expr(&mut self, expr: Expr, offset: usize) -> String {
+        self.column = self.current_column() + offset;
+        expr.accept(self)
+    }
+
+    /// Writes a braced list of statements
+    fn block(&mut self, statements: Vec<Stmt>) {
+        self.braced(|formatter| formatter.statement_list(statements));
+    }
+
+    /// Writes braces around the lines the closure writes one level deeper,
+    /// keeping both braces on one line if it writes nothing
+    fn braced(&mut self, inner: impl FnOnce(&mut Formatter)) {
+        self.line("{");
+        let open = self.lines.len() - 1;
+
+        self.indent += 1;
+        inner(self);
+        self.indent -= 1;
+
+        if self.lines.len() == open + 1 {
+            self.lines[open].push('}');
+        } else {
+            self.line("}");
+        }
+    }
+
+    /// Writes the fields of a class along with their trivia
+    fn class_fields(&mut self, fields: Vec<Var>) {
+        for field in fields {
+            self.member(|formatter| {
+                let name = field.name().lexeme();
+                match field.is_initialized() {
+                    true => {
+                        let initializer = formatter.expr(field.initializer(), name.len() + 9);
+                        formatter.line(&format!("class {} = {};", name, initializer));
+                    }
+                    false => formatter.line(&format!("class {};", name)),
+                }
+            });
+        }
+    }
+
+    /// Writes the methods of a class along with their trivia
+    fn methods(&mut self, methods: Vec<Function>) {
+        for method in methods {
+            self.member(
+                |formatter| match (method.is_class_method(), method.is_generator()) {
+                    (true, true) => formatter.function(&method, "class *"),
+                    (true, false) => formatter.function(&method, "class "),
+                    (false, true) => formatter.function(&method, "*"),
+                    (false, false) => formatter.function(&method, ""),
+                },
+            );
+        }
+
+        let end = self.trivia.pop_front().unwrap_or_default();
+        self.leading_trivia(end.leading, false);
+    }
+
+    /// Writes the arms of a match statement along with their trivia
+    fn arms(&mut self, arms: Vec<MatchArm>) {
+        for arm in arms {
+            self.member(|formatter| {
+                let mut head = arm.pattern().to_string();
+                if let Some(guard) = arm.guard() {
+                    let guard = formatter.expr(guard, head.len() + 4);
+                    head = format!("{} if {}", head, guard);
+                }
+                formatter.push_prefix(&format!("{} => ", head));
+                arm.body().accept(formatter);
+            });
+        }
+
+        let end = self.trivia.pop_front().unwrap_or_default();
+        self.leading_trivia(end.leading, false);
+    }
+
+    /// Writes another braced list on the line closing the last one,
+    /// as in `} catch (e) {`
+    fn continue_block(&mut self, keyword: &str, statements: Vec<Stmt>) {
+        let last = self.lines.pop().unwrap_or_default();
+        self.prefix = Some(format!("{} {} ", last, keyword));
+        self.block(statements);
+    }
+
+    /// Writes a `for` loop from the block the parser desugared it into
+    fn for_loop(&mut self, block: &Block) {
+        let mut statements = block.statements();
+        let while_ = match statements.pop() {
+            Some(Stmt::While(while_)) => while_,
+            _ => panic!("This should not happen, a for loop should end with a while statement"),
+        };
+        self.loop_label(while_.label());
+
+        let initializer = match statements.pop() {
+            Some(Stmt::Var(var)) => self.var(&var, 5),
+            Some(Stmt::Expression(stmt)) => self.expr(stmt.expression(), 5),
+            _ => String::new(),
+        };
+        let mut header = format!("for ({};", initializer);
+        let offset = header.len() + 1;
+        header.push_str(&format!(" {};", self.expr(while_.condition(), offset)));
+        match while_.increment() {
+            Some(increment) => {
+                let offset = header.len() + 1;
+                header.push_str(&format!(" {}) ", self.expr(increment, offset)));
+            }
+            None => header.push_str(") "),
+        }
+
+        self.push_prefix(&header);
+        while_.body().accept(self);
+    }
+
+    /// Writes the label naming a loop, if it has one
+    fn loop_label(&mut self, label: Option<Token>) {
+        if let Some(label) = label {
+            self.push_prefix(&format!("{}: ", label.lexeme()));
+        }
+    }
+
+    /// Prints a variable declaration without its semicolon
+    fn var(&mut self, stmt: &Var, offset: usize) -> String {
+        let keyword = if stmt.is_const() { "const" } else { "var" };
+        let name = stmt.name().lexeme();
+        match stmt.is_initialized() {
+            true => {
+                let offset = offset + keyword.len() + name.len() + 4;
+                let value = self.expr(stmt.initializer(), offset);
+                format!("{} {} = {}", keyword, name, value)
+            }
+            false => format!("{} {}", keyword, name),
+        }
+    }
+
+    /// Prints the parameters of a function with their default values
+    fn params(&mut self, params: Vec<Param>) -> String {
+        params
+            .iter()
+            .map(|param| match (param.default(), param.is_rest()) {
+                (Some(default), _) => {
+                    format!("{} = {}", param.name().lexeme(), default.accept(self))
+                }
+                (None, true) => format!("...{}", param.name().lexeme()),
+                (None, false) => param.name().lexeme(),
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    /// Prints the arguments of a call on one line, named ones last
+    fn arguments(&mut self, expr: &Call) -> Vec<String> {
+        let mut arguments = Vec::new();
+        for argument in expr.arguments() {
+            arguments.push(self.argument(None, argument));
+        }
+        for (name, argument) in expr.named_arguments() {
+            arguments.push(self.argument(Some(name), argument));
+        }
+        arguments
+    }
+
+    /// Prints a call argument, after its parameter name if it has one
+    fn argument(&mut self, name: Option<&Token>, argument: &Expr) -> String {
+        match name {
+            Some(name) => {
+                self.column += name.lexeme().len() + 2;
+                format!("{}: {}", name.lexeme(), argument.accept(self))
+            }
+            None => argument.accept(self),
+        }
+    }
+
+    /// Prints every item on a line of its own, one level deeper than the
+    /// line the brackets around them open on, which the caller adds
+    fn one_per_line<T>(
+        &mut self,
+        items: &[T],
+        mut print: impl FnMut(&mut Formatter, &T) -> String,
+    ) -> String {
+        self.indent += 1;
+        let inner = INDENT.repeat(self.indent);
+        let items = items
+            .iter()
+            .map(|item| {
+                self.column = inner.len();
+                format!("{}{}", inner, print(self, item))
+            })
+            .collect::<Vec<String>>();
+        self.indent -= 1;
+
+        format!("\n{}\n{}", items.join(",\n"), INDENT.repeat(self.indent))
+    }
+
+    /// Writes a function's signature and body, with an optional keyword
+    fn function(&mut self, stmt: &Function, keyword: &str) {
+        let signature = match stmt.is_getter() {
+            true => stmt.name().lexeme(),
+            false => format!("{}({})", stmt.name().lexeme(), self.params(stmt.params())),
+        };
+        self.push_prefix(&format!("{}{} ", keyword, signature));
+        self.block(stmt.body());
+    }
+}
+
+/// Returns true if the statement is written inside braces
+fn is_braced(stmt: &Stmt) -> bool {
+    matches!(stmt, Stmt::Block(block) if !block.is_for_loop())
+}
+
+/// Returns true if the token opens a pair of brackets
+fn is_opening(token: &Token) -> bool {
+    matches!(
+        token.kind(),
+        TokenType::LeftParen | TokenType::LeftBracket | TokenType::LeftBrace
+    )
+}
+
+/// Returns true if the token closes a pair of brackets
+fn is_closing(token: &Token) -> bool {
+    matches!(
+        token.kind(),
+        TokenType::RightParen | TokenType::RightBracket | TokenType::RightBrace
+    )
+}
+
+/// Returns true if the token can end an operand, so a minus after it is binary
+fn ends_operand(token: &Token) -> bool {
+    matches!(
+        token.kind(),
+        TokenType::Identifier
+            | TokenType::PrivateIdentifier
+            | TokenType::String
+            | TokenType::Integer
+            | TokenType::Float
+            | TokenType::True
+            | TokenType::False
+            | TokenType::Nil
+            | TokenType::This
+            | TokenType::RightParen
+            | TokenType::RightBracket
+    )
+}
+
+/// Returns true if a space goes between two tokens written on one line
+fn spaced(previous: &Token, token: &Token) -> bool {
+    match (previous.kind(), token.kind()) {
+        (
+            _,
+            TokenType::Comma
+            | TokenType::Semicolon
+            | TokenType::Colon
+            | TokenType::Dot
+            | TokenType::QuestionDot
+            | TokenType::RightParen
+            | TokenType::RightBracket,
+        ) => false,
+        (
+            TokenType::Dot
+            | TokenType::QuestionDot
+            | TokenType::LeftParen
+            | TokenType::LeftBracket
+            | TokenType::Ellipsis
+            | TokenType::Bang
+            | TokenType::Tilde,
+            _,
+        ) => false,
+        // calls and indexing stick to what they apply to
+        (_, TokenType::LeftParen | TokenType::LeftBracket) => !ends_operand(previous),
+        (TokenType::Fun, TokenType::Star) => false,
+        _ => true,
+    }
+}
+
+/// Returns the operator an assignment is written with, like `=` or `+=`
+fn assignment(operator: Option<Token>) -> String {
+    match operator {
+        Some(operator) => format!("{}=", operator.lexeme()),
+        None => "=".to_string(),
+    }
+}
+
+/// Implements the expression Visitor trait for Formatter
+impl ExprVisitor<String> for Formatter {
+    fn visit_assign_expr(&mut self, expr: &Assign) -> String {
+        let name = expr.name().lexeme();
+        let equals = assignment(expr.operator());
+        self.column += name.len() + equals.len() + 2;
+        format!("{} {} {}", name, equals, expr.value().accept(self))
+    }
+
+    fn visit_assign_pattern_expr(&mut self, expr: &AssignPattern) -> String {
+        let pattern = expr.pattern().to_string();
+        self.column += pattern.len() + 3;
+        format!("{} = {}", pattern, expr.value().accept(self))
+    }
+
+    fn visit_binary_expr(&mut self, expr: &Binary) -> String {
+        let left = expr.left().accept(self);
+        format!(
+            "{} {} {}",
+            left,
+            expr.operator().lexeme(),
+            expr.right().accept(self)
+        )
+    }
+
+    fn visit_call_expr(&mut self, expr: &Call) -> String {
+        let column = self.column;
+        let callee = expr.callee().accept(self);
+
+        let arguments = self.arguments(expr);
+        let flat = format!("{}({})", callee, arguments.join(", "));
+        if arguments.is_empty() || column + flat.len() <= LINE_WIDTH {
+            return flat;
+        }
+
+        let arguments = expr
+            .arguments()
+            .iter()
+            .map(|argument| (None, argument))
+            .chain(
+                expr.named_arguments()
+                    .iter()
+                    .map(|(name, argument)| (Some(name), argument)),
+            )
+            .collect::<Vec<_>>();
+        let arguments = self.one_per_line(&arguments, |formatter, (name, argument)| {
+            formatter.argument(*name, argument)
+        });
+        format!("{}({})", callee, arguments)
+    }
+
+    fn visit_get_expr(&mut self, expr: &Get) -> String {
+        let dot = if expr.is_optional() { "?." } else { "." };
+        format!(
+            "{}{}{}",
+            expr.object().accept(self),
+            dot,
+            expr.name().lexeme()
+        )
+    }
+
+    fn visit_grouping_expr(&mut self, expr: &Grouping) -> String {
+        self.column += 1;
+        format!("({})", expr.expression().accept(self))
+    }
+
+    fn visit_index_expr(&mut self, expr: &Index) -> String {
+        let object = expr.object().accept(self);
+        self.column += 1;
+        format!("{}[{}]", object, expr.index().accept(self))
+    }
+
+    fn visit_lambda_expr(&mut self, expr: &Lambda) -> String {
+        let params = self.params(expr.params());
+        let header = match expr.keyword().kind() {
+            TokenType::Arrow => format!("({}) => ", params),
+            _ if expr.is_generator() => format!("fun* ({}) ", params),
+            _ => format!("fun ({}) ", params),
+        };
+        if let Some(value) = expr.arrow_expression() {
+            self.column += header.len();
+            return format!("{}{}", header, value.accept(self));
+        }
+
+        // write the body as lines of its own, indented from the
+        // statement holding the function, then hand them back as text
+        let lines = std::mem::take(&mut self.lines);
+        let prefix = self.prefix.replace(String::new());
+        self.block(expr.body());
+        let body = std::mem::replace(&mut self.lines, lines).join("\n");
+        self.prefix = prefix;
+        format!("{}{}", header, body)
+    }
+
+    fn visit_list_expr(&mut self, expr: &List) -> String {
+        let column = self.column;
+        self.column += 1;
+        let elements = expr
+            .elements()
+            .iter()
+            .map(|element| element.accept(self))
+            .collect::<Vec<String>>();
+        let flat = format!("[{}]", elements.join(", "));
+        if elements.is_empty() || column + flat.len() <= LINE_WIDTH {
+            return flat;
+        }
+
+        let elements = self.one_per_line(expr.elements(), |formatter, element| {
+            element.accept(formatter)
+        });
+        format!("[{}]", elements)
+    }
+
+    fn visit_literal_expr(&mut self, expr: &Literal) -> String {
+        let value = expr
+            .value()
+            .owned::<TokenLiteral>()
+            .expect("Must be a valid literal");
+        match value {
+            TokenLiteral::String(v) => format!("\"{}\"", v),
+            TokenLiteral::Float(v) => {
+                // keep the fractional part so it scans as a float again
+                let text = v.to_string();
+                match text.contains('.') {
+                    true => text,
+                    false => format!("{}.0", text),
+                }
+            }
+            _ => value.to_string(),
+        }
+    }
+
+    fn visit_logical_expr(&mut self, expr: &Logical) -> String {
+        let left = expr.left().accept(self);
+        format!(
+            "{} {} {}",
+            left,
+            expr.operator().lexeme(),
+            expr.right().accept(self)
+        )
+    }
+
+    fn visit_map_expr(&mut self, expr: &Map) -> String {
+        self.column += 1;
+        let entries = expr
+            .entries()
+            .iter()
+            .map(|(key, value)| {
+                let key = key.accept(self);
+                self.column += 2;
+                format!("{}: {}", key, value.accept(self))
+            })
+            .collect::<Vec<String>>();
+        format!("{{{}}}", entries.join(", "))
+    }
+
+    fn visit_set_expr(&mut self, expr: &Set) -> String {
+        let object = expr.object().accept(self);
+        let name = expr.name().lexeme();
+        let equals = assignment(expr.operator());
+        self.column += name.len() + equals.len() + 3;
+        let value = expr.value().accept(self);
+        format!("{}.{} {} {}", object, name, equals, value)
+    }
+
+    fn visit_super_expr(&mut self, expr: &Super) -> String {
+        format!("super.{}", expr.method().lexeme())
+    }
+
+    fn visit_ternary_expr(&mut self, expr: &Ternary) -> String {
+        let condition = expr.condition().accept(self);
+        self.column += 3;
+        let then_branch = expr.then_branch().accept(self);
+        self.column += 3;
+        let else_branch = expr.else_branch().accept(self);
+        format!("{} ? {} : {}", condition, then_branch, else_branch)
+    }
+
+    fn visit_this_expr(&mut self, _expr: &This) -> String {
+        "this".to_string()
+    }
+
+    fn visit_set_index_expr(&mut self, expr: &SetIndex) -> String {
+        let object = expr.object().accept(self);
+        self.column += 1;
+        let index = expr.index().accept(self);
+        let equals = assignment(expr.operator());
+        self.column += equals.len() + 3;
+        let value = expr.value().accept(self);
+        format!("{}[{}] {} {}", object, index, equals, value)
+    }
+
+    fn visit_unary_expr(&mut self, expr: &Unary) -> String {
+        self.column += 1;
+        format!("{}{}", expr.operator().lexeme(), expr.right().accept(self))
+    }
+
+    fn visit_variable_expr(&mut self, expr: &Variable) -> String {
+        expr.name().lexeme()
+    }
+}
+
+/// Implements the statement Visitor trait for Formatter
+impl StmtVisitor<()> for Formatter {
+    fn visit_block_stmt(&mut self, stmt: &Block) {
+        match stmt.is_for_loop() {
+            true => self.for_loop(stmt),
+            false => self.block(stmt.statements()),
+        }
+    }
+
+    fn visit_break_stmt(&mut self, stmt: &Break) {
+        match stmt.label() {
+            Some(label) => self.line(&format!("break {};", label.lexeme())),
+            None => self.line("break;"),
+        }
+    }
+
+    fn visit_class_stmt(&mut self, stmt: &Class) {
+        let mut header = match stmt.superclass() {
+            Some(superclass) => format!(
+                "class {} < {}",
+                stmt.name().lexeme(),
+                superclass.name().lexeme()
+            ),
+            None => format!("class {}", stmt.name().lexeme()),
+        };
+        let traits: Vec<String> = stmt
+            .traits()
+            .iter()
+            .map(|trait_| trait_.name().lexeme())
+            .collect();
+        if !traits.is_empty() {
+            header = format!("{} with {}", header, traits.join(", "));
+        }
+        self.push_prefix(&format!("{} ", header));
+        self.braced(|formatter| {
+            formatter.class_fields(stmt.fields());
+            formatter.methods(stmt.methods());
+        });
+    }
+
+    fn visit_continue_stmt(&mut self, stmt: &Continue) {
+        match stmt.label() {
+            Some(label) => self.line(&format!("continue {};", label.lexeme())),
+            None => self.line("continue;"),
+        }
+    }
+
+    fn visit_enum_stmt(&mut self, stmt: &Enum) {
+        let variants: Vec<String> = stmt.variants().iter().map(EnumVariant::to_string).collect();
+        match variants.is_empty() {
+            true => self.line(&format!("enum {} {{}}", stmt.name().lexeme())),
+            false => self.line(&format!(
+                "enum {} {{ {} }}",
+                stmt.name().lexeme(),
+                variants.join(", ")
+            )),
+        }
+    }
+
+    fn visit_expression_stmt(&mut self, stmt: &Expression) {
+        let expr = self.expr(stmt.expression(), 0);
+        self.line(&format!("{};", expr));
+    }
+
+    fn visit_for_in_stmt(&mut self, stmt: &ForIn) {
+        self.loop_label(stmt.label());
+        let name = stmt.name().lexeme();
+        let iterable = self.expr(stmt.iterable(), name.len() + 13);
+        self.push_prefix(&format!("for (var {} in {}) ", name, iterable));
+        stmt.body().accept(self);
+    }
+
+    fn visit_function_stmt(&mut self, stmt: &Function) {
+        match stmt.is_generator() {
+            true => self.function(stmt, "fun* "),
+            false => self.function(stmt, "fun "),
+        }
+    }
+
+    fn visit_if_stmt(&mut self, stmt: &If) {
+        let condition = self.expr(stmt.condition(), 4);
+        self.push_prefix(&format!("if ({}) ", condition));
+
+        let then_branch = stmt.then_branch();
+        then_branch.accept(self);
+
+        if let Some(else_branch) = stmt.else_branch() {
+            if is_braced(&then_branch) {
+                // keep `else` on the line of the closing brace
+                let last = self.lines.pop().unwrap_or_default();
+                self.prefix = Some(format!("{} else ", last));
+            } else {
+                self.push_prefix("else ");
+            }
+            else_branch.accept(self);
+        }
+    }
+
+    fn visit_match_stmt(&mut self, stmt: &Match) {
+        let subject = self.expr(stmt.subject(), 7);
+        self.push_prefix(&format!("match ({}) ", subject));
+        self.braced(|formatter| formatter.arms(stmt.arms()));
+    }
+
+    fn visit_print_stmt(&mut self, stmt: &Print) {
+        let expr = self.expr(stmt.expression(), 6);
+        self.line(&format!("print {};", expr));
+    }
+
+    fn visit_return_stmt(&mut self, stmt: &Return) {
+        match stmt.value() {
+            Some(value) => {
+                let value = self.expr(value, 7);
+                self.line(&format!("return {};", value));
+            }
+            None => self.line("return;"),
+        }
+    }
+
+    fn visit_throw_stmt(&mut self, stmt: &Throw) {
+        let value = self.expr(stmt.value(), 6);
+        self.line(&format!("throw {};", value));
+    }
+
+    fn visit_trait_stmt(&mut self, stmt: &Trait) {
+        self.push_prefix(&format!("trait {} ", stmt.name().lexeme()));
+        self.braced(|formatter| formatter.methods(stmt.methods()));
+    }
+
+    fn visit_try_stmt(&mut self, stmt: &Try) {
+        self.push_prefix("try ");
+        self.block(stmt.body());
+        if let Some(catch) = stmt.catch() {
+            let keyword = format!("catch ({})", catch.name().lexeme());
+            self.continue_block(&keyword, catch.body());
+        }
+        if let Some(finally) = stmt.finally() {
+            self.continue_block("finally", finally);
+        }
+    }
+
+    fn visit_var_stmt(&mut self, stmt: &Var) {
+        let var = self.var(stmt, 0);
+        self.line(&format!("{};", var));
+    }
+
+    fn visit_var_pattern_stmt(&mut self, stmt: &VarPattern) {
+        let keyword = if stmt.is_const() { "const" } else { "var" };
+        let pattern = stmt.pattern().to_string();
+        let initializer = self.expr(stmt.initializer(), keyword.len() + pattern.len() + 4);
+        self.line(&format!("{} {} = {};", keyword, pattern, initializer));
+    }
+
+    fn visit_while_stmt(&mut self, stmt: &While) {
+        self.loop_label(stmt.label());
+        let condition = self.expr(stmt.condition(), 7);
+        self.push_prefix(&format!("while ({}) ", condition));
+        stmt.body().accept(self);
+    }
+
+    fn visit_yield_stmt(&mut self, stmt: &Yield) {
+        let value = self.expr(stmt.value(), 6);
+        self.line(&format!("yield {};", value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rlox::{parser::Parser, scanner::Scanner};
+
+    fn format_source(source: &str) -> String {
+        let tokens = Scanner::new(source.to_string()).scan_tokens().unwrap();
+        let mut parser = Parser::new(tokens);
+        let statements = parser.parse().unwrap();
+        Formatter::new(parser.statement_trivia().clone()).format(statements)
+    }
+
+    #[test]
+    fn test_format_layout() {
+        let source = r#"
+var a=1;var b;const c=a;a+=b%2**3;xs[a]*=c?1:~0<<2;print a?.b??c?.d();
+fun add(x,y){return x+y;}
+if(a<2)print "small";else{print -a;}
+try{throw f(1).message;}catch(e){print e;}finally{print "done";}
+while (a < 10) a = a * 2.0;
+var xs=[1,2,[ ]];xs[0]=xs [1];var m={"a":1,"b":{},};
+for(var i=0;i<3;i=i+1){print i;}
+for(;;){}
+outer:for(var j=0;j<3;j=j+1){while(true){if(j>1)break outer;continue;}}
+class A{}class B<A{init(x){this.x=super.get(x);}
+size(){return this.x;}}
+each:for(var c in "ab")print c;
+var double=(x)=>x*2;xs.sort(fun(a,b){return a<b;});var noop=fun(){};var f=(a,b=2,...c)=>g(a,key:b);
+match(a){0|-1=>print "low";[x,...rest] if(x>1)=>{print rest;},{"k":v,n}=>print n;_=>{}}
+var [p,q,...r]=xs;[p,q]=[q,p];const {k,"n":m}=mm;
+enum Shape{Circle(r),Rect(w,h),Empty,}enum Never{}match(s){Shape.Circle(r)=>print r;Shape.Rect|Shape.Empty=>{}}
+class C{class n=0;class m;class make(){return C();}area{return 1;}}
+trait T{size{return 1;}}class D<C with T,U{}
+fun*count(n){while(true){yield n;n=n+1;}}class E{*iterator(){yield 1;}}var g=fun*(){yield 2;};
+"#;
+        let expected = r#"var a = 1;
+var b;
+const c = a;
+a += b % 2 ** 3;
+xs[a] *= c ? 1 : ~0 << 2;
+print a?.b ?? c?.d();
+fun add(x, y) {
+    return x + y;
+}
+if (a < 2) print "small";
+else {
+    print -a;
+}
+try {
+    throw f(1).message;
+} catch (e) {
+    print e;
+} finally {
+    print "done";
+}
+while (a < 10) a = a * 2.0;
+var xs = [1, 2, []];
+xs[0] = xs[1];
+var m = {"a": 1, "b": {}};
+for (var i = 0; i < 3; i = i + 1) {
+    print i;
+}
+for (; true;) {}
+outer: for (var j = 0; j < 3; j = j + 1) {
+    while (true) {
+        if (j > 1) break outer;
+        continue;
+    }
+}
+class A {}
+class B < A {
+    init(x) {
+        this.x = super.get(x);
+    }
+    size() {
+        return this.x;
+    }
+}
+each: for (var c in "ab") print c;
+var double = (x) => x * 2;
+xs.sort(fun (a, b) {
+    return a < b;
+});
+var noop = fun () {};
+var f = (a, b = 2, ...c) => g(a, key: b);
+match (a) {
+    0 | -1 => print "low";
+    [x, ...rest] if (x > 1) => {
+        print rest;
+    }
+    {"k": v, n} => print n;
+    _ => {}
+}
+var [p, q, ...r] = xs;
+[p, q] = [q, p];
+const {k, "n": m} = mm;
+enum Shape { Circle(r), Rect(w, h), Empty }
+enum Never {}
+match (s) {
+    Shape.Circle(r) => print r;
+    Shape.Rect | Shape.Empty => {}
+}
+class C {
+    class n = 0;
+    class m;
+    class make() {
+        return C();
+    }
+    area {
+        return 1;
+    }
+}
+trait T {
+    size {
+        return 1;
+    }
+}
+class D < C with T, U {}
+fun* count(n) {
+    while (true) {
+        yield n;
+        n = n + 1;
+    }
+}
+class E {
+    *iterator() {
+        yield 1;
+    }
+}
+var g = fun* () {
+    yield 2;
+};
+"#;
+        assert_eq!(format_source(source), expected);
+        assert_eq!(format_source(expected), expected);
+    }
+
+    #[test]
+    fn test_format_keeps_comments() {
+        let source = r#"// Counts things
+var count = 0; // starts empty
+
+
+// grow it
+{
+  count = count + 1;
+  // nothing else
+}
+class Counter {
+    // starts at zero
+    init() { this.n = 0; }
+
+    // the current count
+    get() { return this.n; } // no side effects
+    // no more methods
+}
+if (count > 0) {
+    print count;
+} else {
+    print "none";
+}
+// the end
+"#;
+        let expected = r#"// Counts things
+var count = 0; // starts empty
+
+// grow it
+{
+    count = count + 1;
+    // nothing else
+}
+class Counter {
+    // starts at zero
+    init() {
+        this.n = 0;
+    }
+
+    // the current count
+    get() {
+        return this.n;
+    } // no side effects
+    // no more methods
+}
+if (count > 0) {
+    print count;
+} else {
+    print "none";
+}
+// the end
+"#;
+        assert_eq!(format_source(source), expected);
+        assert_eq!(format_source(expected), expected);
+    }
+
+    #[test]
+    fn test_format_line_width() {
+        let source = "print describe(\"a rather long argument\", \"another long argument\", \"and then one more very long argument\");";
+        let expected = r#"print describe(
+    "a rather long argument",
+    "another long argument",
+    "and then one more very long argument"
+);
+"#;
+        assert_eq!(format_source(source), expected);
+        assert_eq!(format_source(expected), expected);
+
+        let source = "var words = [\"a rather long element\", \"another long element\", \"and then one more rather long element\"];";
+        let expected = r#"var words = [
+    "a rather long element",
+    "another long element",
+    "and then one more rather long element"
+];
+"#;
+        assert_eq!(format_source(source), expected);
+        assert_eq!(format_source(expected), expected);
+    }
+
+    #[test]
+    fn test_format_keeps_inner_comments() {
+        let source = r#"var a=1;
+if (a) print 1; // c1
+else print 2;
+var xs = [1, // one
+  -2, a - 1];
+class A {
+    f(x) {}
+    g() {
+        this.f(
+        // nothing
+        );
+    }
+}
+"#;
+        let expected = r#"var a = 1;
+if (a) print 1; // c1
+else print 2;
+var xs = [1, // one
+    -2, a - 1];
+class A {
+    f(x) {}
+    g() {
+        this.f(
+            // nothing
+        );
+    }
+}
+"#;
+        assert_eq!(format_source(source), expected);
+        assert_eq!(format_source(expected), expected);
+    }
+}
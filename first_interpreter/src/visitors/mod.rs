@@ -1,3 +1,6 @@
 //! This module holds all the visitors that implement the expression traits
 
-pub mod ast_printer;
\ No newline at end of file
+pub mod ast_printer;
+pub mod cfg;
+pub mod dot_printer;
+pub mod formatter;
\ No newline at end of file
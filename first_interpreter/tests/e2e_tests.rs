@@ -1,8 +1,39 @@
 use first_interpreter::{
     self,
-    rlox::{interpreter::Interpreter, parser::Parser, scanner::Scanner},
+    rlox::{interpreter::Interpreter, json, parser::Parser, scanner::Scanner},
 };
 
+/// Scans, parses and interprets a program, returning the
+/// message of the first error it hits
+fn run(source_code: &str) -> Result<(), String> {
+    run_with(&mut Interpreter::new(), source_code)
+}
+
+/// Runs a program on an Interpreter that may have run others before,
+/// the way the prompt does
+fn run_with(interpreter: &mut Interpreter, source_code: &str) -> Result<(), String> {
+    let tokens = Scanner::new(source_code.to_string())
+        .scan_tokens()
+        .map_err(|err| err.to_string())?;
+    let statements = Parser::new(tokens).parse().map_err(|err| err.to_string())?;
+    interpreter
+        .interpret(statements)
+        .map_err(|err| err.to_string())
+}
+
+/// Asserts that a program fails with an error carrying the given message
+fn assert_fails(source_code: &str, message: &str) {
+    match run(source_code) {
+        Ok(()) => panic!("expected the program to fail with {:?}", message),
+        Err(err) => assert!(
+            err.contains(message),
+            "expected {:?} but got {}",
+            message,
+            err
+        ),
+    }
+}
+
 #[test]
 fn test_syntax() {
     let source_code = r#"
@@ -32,8 +63,8 @@ print d;
 
 var a = 1;
 {
-  var a = a + 2;
-  print a;
+  var b = a + 2;
+  print b;
 }
 print a;
 
@@ -201,3 +232,1284 @@ fn test_dynamic_scoping() {
     let res = interpreter.interpret(statements.unwrap());
     assert!(res.is_ok());
 }
+
+#[test]
+fn test_read_local_in_own_initializer() {
+    let source_code = r#"
+var a = 1;
+{
+  var a = a + 2;
+  print a;
+}
+"#;
+
+    let mut scanner = Scanner::new(source_code.to_string());
+
+    let res = scanner.scan_tokens();
+    assert!(res.is_ok());
+
+    let tokens = res.unwrap();
+
+    let mut parser = Parser::new(tokens);
+    let statements = parser.parse();
+    assert!(statements.is_ok());
+
+    let mut interpreter = Interpreter::new();
+    let res = interpreter.interpret(statements.unwrap());
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_return_from_nested_statements() {
+    let source_code = r#"
+    fun find(n) {
+        var i = 0;
+        while (i < 10) {
+            if (i == n) {
+                return i;
+            }
+            i = i + 1;
+        }
+        return -1;
+    }
+
+    fun cleanup() {
+        try {
+            return find(3);
+        } finally {
+            print "cleaned up";
+        }
+    }
+
+    print cleanup();
+"#;
+
+    let mut scanner = Scanner::new(source_code.to_string());
+
+    let res = scanner.scan_tokens();
+    assert!(res.is_ok());
+
+    let tokens = res.unwrap();
+
+    let mut parser = Parser::new(tokens);
+    let statements = parser.parse();
+    assert!(statements.is_ok());
+
+    let mut interpreter = Interpreter::new();
+    let res = interpreter.interpret(statements.unwrap());
+    assert!(res.is_ok());
+}
+
+#[test]
+fn test_break_and_continue() {
+    run(r#"
+    var total = 0;
+    var last = nil;
+    outer: for (var i = 0; i < 5; i = i + 1) {
+        if (i == 1) continue;
+        var j = 0;
+        while (true) {
+            j = j + 1;
+            if (j == 3) continue outer;
+            if (i == 4) break outer;
+            total = total + j;
+        }
+    }
+    if (total != 9) throw total;
+
+    // the increment runs before the loop continues
+    for (var i = 0; i < 3; i = i + 1) {
+        last = i;
+        continue;
+    }
+    if (last != 2) throw last;
+"#)
+    .unwrap();
+}
+
+#[test]
+fn test_break_outside_loop() {
+    let source_code = r#"
+    fun stop() {
+        break;
+    }
+
+    while (true) {
+        stop();
+    }
+"#;
+
+    let mut scanner = Scanner::new(source_code.to_string());
+
+    let res = scanner.scan_tokens();
+    assert!(res.is_ok());
+
+    let tokens = res.unwrap();
+
+    let mut parser = Parser::new(tokens);
+    let statements = parser.parse();
+    assert!(statements.is_ok());
+
+    let mut interpreter = Interpreter::new();
+    let res = interpreter.interpret(statements.unwrap());
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_lists() {
+    run(r#"
+    fun square(n) {
+        return n * n;
+    }
+
+    fun odd(n) {
+        return n - n / 2 * 2 == 1;
+    }
+
+    var xs = [5, 3, 1, 4, 2];
+    var alias = xs;
+    alias.push(6);
+    xs.sort();
+    xs[0] = xs[xs.len() - 1];
+
+    // both names see the same list, sorted with the pushed element
+    if (alias.len() != 6 or alias[0] != 6 or alias[5] != 6) throw "alias";
+
+    var squares = xs.filter(odd).map(square);
+    if (squares.len() != 2 or squares[0] != 9 or squares[1] != 25) throw squares;
+
+    var slice = alias.slice(1, 3);
+    if (slice.len() != 2 or slice[0] != 2 or slice[1] != 3) throw slice;
+"#)
+    .unwrap();
+}
+
+#[test]
+fn test_maps() {
+    run(r#"
+    var scores = {"ada": 3, "bob": 1,};
+    scores["cy"] = 2;
+    scores["ada"] = scores["ada"] + 1;
+    if (scores.remove("bob") != 1) throw "remove";
+
+    // the keys keep the order they were added in
+    var names = scores.keys();
+    if (names.len() != 2 or names[0] != "ada" or names[1] != "cy") throw names;
+    if (scores["ada"] != 4 or scores["cy"] != 2) throw scores;
+
+    // a missing key reads as nil
+    if (scores.has("bob") or !(scores["bob"] == nil)) throw "bob";
+"#)
+    .unwrap();
+}
+
+#[test]
+fn test_exceptions() {
+    let source_code = r#"
+    fun divide(a, b) {
+        if (b == 0) throw "division by zero";
+        return a / b;
+    }
+
+    try {
+        divide(1, 0);
+    } catch (e) {
+        print e;
+    } finally {
+        print "done";
+    }
+
+    try {
+        print 1 + "one";
+    } catch (e) {
+        print e.message;
+        print e.line;
+    }
+"#;
+
+    let mut scanner = Scanner::new(source_code.to_string());
+
+    let res = scanner.scan_tokens();
+    assert!(res.is_ok());
+
+    let tokens = res.unwrap();
+
+    let mut parser = Parser::new(tokens);
+    let statements = parser.parse();
+    assert!(statements.is_ok());
+
+    let mut interpreter = Interpreter::new();
+    let res = interpreter.interpret(statements.unwrap());
+    assert!(res.is_ok());
+
+    // exceptions unwind through native methods calling back into functions
+    run(r#"
+    var caught = nil;
+    try {
+        [1, 2].map(fun (x) { throw x * 10; });
+    } catch (e) {
+        caught = e;
+    }
+    if (caught != 10) throw "expected the exception of the first element";
+
+    class Loud {
+        __str__() { throw "loud"; }
+    }
+    try {
+        print Loud();
+    } catch (e) {
+        caught = e;
+    }
+    if (caught != "loud") throw "expected the exception of __str__";
+"#)
+    .unwrap();
+    assert_fails("throw \"oops\";", "Uncaught exception");
+}
+
+#[test]
+fn test_uncaught_exception() {
+    let source_code = r#"
+    try {
+        throw "caught";
+    } catch (e) {
+        throw e;
+    }
+"#;
+
+    let mut scanner = Scanner::new(source_code.to_string());
+
+    let res = scanner.scan_tokens();
+    assert!(res.is_ok());
+
+    let tokens = res.unwrap();
+
+    let mut parser = Parser::new(tokens);
+    let statements = parser.parse();
+    assert!(statements.is_ok());
+
+    let mut interpreter = Interpreter::new();
+    let res = interpreter.interpret(statements.unwrap());
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_interpret_from_json() {
+    let source_code = r#"
+    fun fib(n) {
+        if (n <= 1) return n;
+        return fib(n - 2) + fib(n - 1);
+    }
+
+    for (var i = 0; i < 10; i = i + 1) {
+        print fib(i);
+    }
+"#;
+
+    let mut scanner = Scanner::new(source_code.to_string());
+
+    let res = scanner.scan_tokens();
+    assert!(res.is_ok());
+
+    let tokens = res.unwrap();
+
+    let mut parser = Parser::new(tokens);
+    let statements = parser.parse();
+    assert!(statements.is_ok());
+
+    let encoded = json::to_json(&statements.unwrap());
+    assert!(encoded.is_ok());
+
+    let decoded = json::from_json(&encoded.unwrap());
+    assert!(decoded.is_ok());
+
+    let mut interpreter = Interpreter::new();
+    let res = interpreter.interpret(decoded.unwrap());
+    assert!(res.is_ok());
+}
+
+#[test]
+fn test_interpret_from_edited_json() {
+    let source_code = r#"
+    fun outer() {
+        var a = 1;
+        var b = 2;
+        fun inner() {
+            var c = 3;
+            return a * 100 + b * 10 + c;
+        }
+        if (inner() != 123) throw "locals";
+    }
+    outer();
+"#;
+    let tokens = Scanner::new(source_code.to_string()).scan_tokens().unwrap();
+    let statements = Parser::new(tokens).parse().unwrap();
+    let encoded = json::to_json(&statements).unwrap();
+
+    // the same id on every node, as a hand-edited file might have, where
+    // objects with one key are the tags of the kind of node
+    fn set_ids(value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(object) => {
+                object.values_mut().for_each(set_ids);
+                if object.len() > 1 {
+                    object.insert("id".to_string(), serde_json::Value::from(7));
+                }
+            }
+            serde_json::Value::Array(values) => values.iter_mut().for_each(set_ids),
+            _ => {}
+        }
+    }
+    let mut edited: serde_json::Value = serde_json::from_str(&encoded).unwrap();
+    set_ids(&mut edited);
+
+    for json in [encoded, edited.to_string()] {
+        let decoded = json::from_json(&json).unwrap();
+        Interpreter::new().interpret(decoded).unwrap();
+    }
+}
+
+#[test]
+fn test_classes() {
+    let source_code = r#"
+    class Animal {
+        init(name) { this.name = name; }
+        speak() { return this.name + " makes a sound"; }
+    }
+    class Dog < Animal {
+        init(name) {
+            super.init(name);
+            this.tricks = 0;
+        }
+        speak() { return super.speak() + " (woof)"; }
+    }
+
+    var dog = Dog("rex");
+    dog.tricks = dog.tricks + 1;
+    print dog.speak();
+    print dog.tricks;
+    print dog.init("max").name;
+"#;
+
+    let mut scanner = Scanner::new(source_code.to_string());
+
+    let res = scanner.scan_tokens();
+    assert!(res.is_ok());
+
+    let tokens = res.unwrap();
+
+    let mut parser = Parser::new(tokens);
+    let statements = parser.parse();
+    assert!(statements.is_ok());
+
+    let mut interpreter = Interpreter::new();
+    let res = interpreter.interpret(statements.unwrap());
+    assert!(res.is_ok());
+}
+
+#[test]
+fn test_for_in() {
+    let source_code = r#"
+    class Countdown {
+        init(n) { this.n = n; }
+        iterator() { return this; }
+        next() {
+            if (this.n == 0) return {"done": true};
+            this.n = this.n - 1;
+            return {"done": false, "value": this.n + 1};
+        }
+    }
+
+    // an iterator can produce nil like any other value
+    class Nils {
+        init() { this.left = 2; }
+        iterator() { return this; }
+        next() {
+            this.left = this.left - 1;
+            return {"done": this.left < 0, "value": nil};
+        }
+    }
+    var nils = 0;
+    for (var x in Nils()) if (x == nil) nils = nils + 1;
+    if (nils != 2) throw "nils";
+
+    var total = 0;
+    for (var i in Countdown(3)) total = total + i;
+    for (var i in range(0, 10, 2)) total = total + i;
+    for (var x in [1, 2, 3]) total = total + x;
+    for (var key in {"a": 1, "b": 2}) print key;
+    outer: for (var c in "abc") {
+        for (var n in range(3, 0, -1)) {
+            if (c == "b") continue outer;
+            if (c == "c") break outer;
+        }
+        print c;
+    }
+    print total;
+    if (total != 32) throw "total";
+"#;
+
+    let mut scanner = Scanner::new(source_code.to_string());
+
+    let res = scanner.scan_tokens();
+    assert!(res.is_ok());
+
+    let tokens = res.unwrap();
+
+    let mut parser = Parser::new(tokens);
+    let statements = parser.parse();
+    assert!(statements.is_ok());
+
+    let mut interpreter = Interpreter::new();
+    let res = interpreter.interpret(statements.unwrap());
+    assert!(res.is_ok());
+
+    assert_fails(
+        "class I { iterator() { return this; } next() { return 1; } } for (var x in I()) {}",
+        "Method 'next' must return a map with a 'done' of 'true' or 'false'.",
+    );
+    assert_fails(
+        "class I { iterator() { return this; } next() { return {}; } } for (var x in I()) {}",
+        "Method 'next' must return a map with a 'done' of 'true' or 'false'.",
+    );
+    assert_fails("range(0, 10, 0);", "Range step cannot be zero.");
+    run(r#"
+    try {
+        range(0, 10, 0);
+    } catch (e) {
+        if (e.line != 3) throw "line";
+    }
+    "#)
+    .unwrap();
+}
+
+#[test]
+fn test_lambdas() {
+    run(r#"
+    fun counter() {
+        var n = 0;
+        return fun () {
+            n = n + 1;
+            return n;
+        };
+    }
+    var next = counter();
+    next();
+    if (next() != 2) throw "counter";
+
+    var double = (x) => x * 2;
+    var add = (a, b) => { return a + b; };
+    var big = [1, 2, 3].map(double).filter((x) => x > 2);
+    if (big.len() != 2 or big[0] != 4 or big[1] != 6) throw big;
+    if (add(double(2), 1) != 5) throw "add";
+
+    // every iteration has its own i for the lambda to capture
+    var adders = [];
+    for (var i in range(0, 3, 1)) adders.push((x) => x + i);
+    var sums = adders.map((f) => f(10));
+    if (sums.len() != 3 or sums[0] != 10 or sums[1] != 11 or sums[2] != 12) throw sums;
+"#)
+    .unwrap();
+}
+
+#[test]
+fn test_call_arguments() {
+    run(r#"
+    fun greet(name, greeting = "Hello", times = 1) {
+        var out = "";
+        for (var i in range(times)) out = out + greeting + " " + name;
+        return out;
+    }
+    if (greet("Ann") != "Hello Ann") throw "defaults";
+    if (greet("Bob", times: 2) != "Hello BobHello Bob") throw "named";
+    if (greet(greeting: "Hi", name: "Cy") != "Hi Cy") throw "all named";
+
+    fun sum(first, ...rest) {
+        for (var x in rest) first = first + x;
+        return first;
+    }
+    if (sum(1, 2, 3) != 6) throw "rest";
+    if (sum(1) != 1) throw "empty rest";
+
+    var scale = (x, by = x) => x * by;
+    // a default can use the parameters before it
+    if (scale(3) != 9 or scale(3, by: 2) != 6) throw "lambda";
+
+    class Point {
+        init(x, y = 0) {
+            this.x = x;
+            this.y = y;
+        }
+    }
+    var point = Point(y: 2, x: 1);
+    if (point.x != 1 or point.y != 2) throw "initializer";
+
+    var message = nil;
+    try {
+        greet("Di", nope: 1);
+    } catch (e) {
+        message = e.message;
+    }
+    if (message != "Unknown parameter 'nope'.") throw message;
+"#)
+    .unwrap();
+
+    for source_code in ["fun f(...rest, a) {}", "fun f(a = 1, b) {}", "f(a: 1, 2);"] {
+        let tokens = Scanner::new(source_code.to_string()).scan_tokens().unwrap();
+        assert!(Parser::new(tokens).parse().is_err());
+    }
+}
+
+#[test]
+fn test_constants() {
+    run(r#"
+    const limit = 3;
+    fun clamp(n) {
+        const low = 0;
+        if (n < low) return low;
+        if (n > limit) return limit;
+        return n;
+    }
+    print clamp(5);
+    {
+        var limit = 10;
+        limit = limit + 1;
+    }
+"#)
+    .unwrap();
+
+    // the resolver rejects assignments it can see are to a constant
+    assert_fails(
+        "const limit = 3; limit = 4;",
+        "Cannot assign to constant 'limit'.",
+    );
+
+    // and the environment rejects the ones to globals declared later
+    assert_fails(
+        r#"
+    fun bump() {
+        limit = limit + 1;
+    }
+    const limit = 3;
+    bump();
+"#,
+        "Cannot assign to constant 'limit'.",
+    );
+
+    // a constant cannot be redeclared to make it assignable
+    assert_fails(
+        "const limit = 3; var limit = 4; limit = 5;",
+        "Cannot redeclare constant 'limit'.",
+    );
+
+    // not even by a later program run on the same globals, like in the prompt
+    let mut interpreter = Interpreter::new();
+    run_with(&mut interpreter, "const limit = 3;").unwrap();
+    let err = run_with(&mut interpreter, "var limit = 4;").unwrap_err();
+    assert!(
+        err.contains("Cannot redeclare constant 'limit'."),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_later_programs() {
+    // functions from an earlier program still find their local variables
+    let mut interpreter = Interpreter::new();
+    run_with(
+        &mut interpreter,
+        r#"
+    fun f() { var a = "local"; { if (a != "local") throw "f"; } }
+    fun counter() {
+        var n = 0;
+        return fun () { n += 1; return n; };
+    }
+    var next = counter();
+    "#,
+    )
+    .unwrap();
+    run_with(
+        &mut interpreter,
+        r#"
+    fun k() { var a = "p2"; { if (a != "p2") throw "k"; } }
+    f();
+    k();
+    next();
+    if (next() != 2) throw "next";
+    "#,
+    )
+    .unwrap();
+
+    // and so do the ones from a decoded syntax tree
+    let tokens = Scanner::new("fun g() { var b = 1; { return b; } }".to_string())
+        .scan_tokens()
+        .unwrap();
+    let statements = Parser::new(tokens).parse().unwrap();
+    let decoded = json::from_json(&json::to_json(&statements).unwrap()).unwrap();
+    interpreter.interpret(decoded).unwrap();
+    run_with(&mut interpreter, "f(); if (g() != 1) throw \"g\";").unwrap();
+}
+
+#[test]
+fn test_operators() {
+    run(r#"
+    var flags = 0;
+    for (var bit = 0; bit < 4; bit += 1) {
+        flags = flags | 1 << bit;
+    }
+    print flags == 15 ? "all set" : "missing";
+    print 2 ** 10 % 1000;
+    print ~flags & 255;
+
+    var counts = [0, 0];
+    var i = 0;
+    while (i < 6) {
+        counts[i % 2] += i;
+        i += 1;
+    }
+    print counts;
+
+    var greeting = "hello";
+    greeting += ", world";
+    print greeting;
+"#)
+    .unwrap();
+
+    assert_fails("print 1 % 0;", "MODULO by zero is not allowed");
+    assert_fails("print 1.5 % 0.0;", "MODULO by zero is not allowed");
+    assert_fails(
+        "print (-2147483647 - 1) % -1;",
+        "MODULO result does not fit in an integer",
+    );
+
+    assert_fails("print 1 / 0;", "DIVISION by zero is not allowed");
+    assert_fails(
+        "print 2147483647 + 1;",
+        "ADD result does not fit in an integer",
+    );
+
+    // runtime errors from operators can be caught like any other
+    run(r#"
+    var caught = 0;
+    try { 1 / 0; } catch (e) { caught += 1; }
+    try { 1.0 / 0.0; } catch (e) { caught += 1; }
+    try { 1073741824 * 2; } catch (e) { caught += 1; }
+    var min = -2147483647 - 1;
+    try { -min; } catch (e) { caught += 1; }
+    try { 1.0 % 0.0; } catch (e) { caught += 1; }
+    if (caught != 5) throw "uncaught";
+"#)
+    .unwrap();
+
+    assert_fails("print 1.5 & 1;", "BITWISE AND must have integer operands");
+
+    assert_fails(
+        r#"print "yes" ? 1 : 2;"#,
+        "Condition in conditional expression must evaluate to 'true' or 'false'",
+    );
+
+    // operands other than literals are reported at the operator, with its line
+    let cases = [
+        ("print [1] + 1;", "Unsupported operand type for '+'."),
+        ("print [1] < 2;", "Unsupported operand type for '<'."),
+        ("print -[1];", "MINUS must have a numeric operand"),
+        ("print ![1];", "NEGATION must have a valid operand"),
+        (
+            "print true and [1];",
+            "Right operand must be a boolean expression",
+        ),
+        (
+            "if ([1]) print 1;",
+            "Condition in if statement must evaluate to 'true' or 'false'",
+        ),
+        (
+            "match (1) { n if n < [1] => print n; }",
+            "Unsupported operand type for '<'.",
+        ),
+    ];
+    for (source, message) in cases {
+        let err = run(&format!("\n{}", source)).unwrap_err();
+        assert!(err.contains(message) && err.contains("[line 2]"), "{}", err);
+    }
+
+    // lists and maps holding themselves print as `[...]` and `{...}`
+    run(r#"
+    var xs = [1];
+    xs.push(xs);
+    var m = {"self": nil};
+    m["self"] = m;
+    print xs;
+    print m;
+    print [xs, xs];
+"#)
+    .unwrap();
+}
+
+#[test]
+fn test_nil_safety() {
+    run(r#"
+    class Record {
+        init(name, owner) {
+            this.name = name;
+            this.owner = owner;
+        }
+        describe() {
+            return this.name + " owned by " + (this.owner?.name ?? "nobody");
+        }
+    }
+    var records = [Record("a", Record("root", nil)), Record("b", nil), nil];
+    for (var record in records) {
+        print record?.describe() ?? "missing record";
+        print record?.owner?.owner?.name;
+    }
+    print nil ?? false ?? true;
+"#)
+    .unwrap();
+
+    // the call is skipped along with its arguments
+    run(r#"
+    fun fail() { throw "evaluated"; }
+    var record = nil;
+    record?.describe(fail());
+"#)
+    .unwrap();
+
+    // a nil found by `?.` skips the rest of the chain
+    run(r#"
+    var record = nil;
+    if (!(record?.owner.name == nil)) throw "get";
+    if (!(record?.owner.describe() == nil)) throw "call";
+    if (!(record?.owner.names[0].len() == nil)) throw "index";
+"#)
+    .unwrap();
+
+    // but only the chain it is part of, and not a nil found past it
+    assert_fails(
+        "var record = nil; print (record?.owner).name;",
+        "Only instances have properties.",
+    );
+    assert_fails(
+        "class R {} var record = R(); record.owner = nil; print record?.owner.name;",
+        "Only instances have properties.",
+    );
+}
+
+#[test]
+fn test_match() {
+    run(
+        r#"
+    fun area(shape) {
+        match (shape) {
+            {kind: "circle", radius} => return 3 * radius * radius;
+            {kind: "rect", width, height} => return width * height;
+            ["square", side] => return side * side;
+            [] | nil => return 0;
+            _ => throw "unknown shape";
+        }
+    }
+    var total = 0;
+    for (var shape in [{"kind": "circle", "radius": 2}, {"kind": "rect", "width": 2, "height": 3}, ["square", 4], []]) {
+        total += area(shape);
+    }
+    if (total != 34) throw total;
+
+    fun sum(xs) {
+        match (xs) {
+            [] => return 0;
+            [head, ...tail] => return head + sum(tail);
+        }
+    }
+    if (sum([1, 2, 3, 4]) != 10) throw "sum";
+
+    fun classify(n) {
+        match (n) {
+            0 => return "zero";
+            -1 | 1 => return "unit";
+            m if m < 0 => return "negative";
+            _ => return "positive";
+        }
+    }
+    if (classify(-1) != "unit" or classify(-5) != "negative" or classify(7) != "positive") {
+        throw "classify";
+    }
+"#,
+    ).unwrap();
+
+    // a guard has to be a boolean
+    assert_fails(
+        "match (1) { n if n => print n; }",
+        "Guard in match arm must evaluate to 'true' or 'false'",
+    );
+}
+
+#[test]
+fn test_destructuring() {
+    run(r#"
+    var [a, b] = [1, 2];
+    [a, b] = [b, a];
+    if (a != 2 or b != 1) throw "swap";
+
+    var {name, age} = {"name": "ada", "age": 36};
+    if (name != "ada" or age != 36) throw "map";
+
+    class Point { init(x, y) { this.x = x; this.y = y; } }
+    var {x, "y": y} = Point(3, 4);
+    if (x + y != 7) throw "instance";
+
+    var [head, ...tail] = [1, 2, 3];
+    if (head != 1 or tail.len() != 2 or tail[1] != 3) throw "rest";
+
+    fun firsts(pairs) {
+        var total = 0;
+        for (var pair in pairs) {
+            const [first, [second]] = pair;
+            total += first * second;
+        }
+        return total;
+    }
+    if (firsts([[1, [2]], [3, [4, 5]]]) != 14) throw "nested";
+"#)
+    .unwrap();
+
+    // missing elements and keys are runtime errors
+    assert_fails(
+        "var [a, b] = [1];",
+        "Expected at least 2 elements to destructure but got 1.",
+    );
+    assert_fails(r#"var {a} = {"b": 1};"#, "Missing key a to destructure.");
+    assert_fails(
+        "var [a] = 1;",
+        "Only lists can be destructured with a list pattern.",
+    );
+}
+
+#[test]
+fn test_enums() {
+    run(r#"
+    enum Shape { Circle(r), Rect(w, h), Empty }
+
+    fun area(shape) {
+        match (shape) {
+            Shape.Circle(r) => return 3 * r * r;
+            Rect(w, h) => return w * h;
+            Shape.Empty => return 0;
+        }
+    }
+    var total = 0;
+    for (var shape in [Shape.Circle(2), Shape.Rect(2, 3), Shape.Empty]) {
+        total += area(shape);
+    }
+    if (total != 18) throw total;
+
+    var circle = Shape.Circle(2);
+    if (circle.r != 2 or circle.variant != "Circle") throw "fields";
+    if (circle != Shape.Circle(2) or circle == Shape.Circle(3)) throw "equality";
+    if (Shape.Empty != Shape.Empty or Shape.Empty == circle or circle == 2) throw "variants";
+
+    enum Option { Some(value), None }
+    if (Option.Some(Shape.Empty) != Option.Some(Shape.Empty)) throw "nested";
+
+    // other objects in fields are equal only to themselves
+    var xs = [1];
+    var m = {"a": 1};
+    fun f() {}
+    if (!(xs == xs and m == m and f == f and xs.push == xs.push)) throw "identity";
+    if (xs == [1] or m == {"a": 1} or f == fun () {} or xs.push == xs.pop) throw "distinct";
+    if (!(Option.Some(xs) == Option.Some(xs)) or Option.Some(xs) == Option.Some([1])) {
+        throw "fields";
+    }
+    match (Option.Some([1, 2])) {
+        Option.Some([first, ...rest]) => if (first != 1) throw "destructure";
+        _ => throw "no match";
+    }
+"#)
+    .unwrap();
+
+    // constructors check their arguments and enums their variants
+    assert_fails("enum E { A(x) } E.A();", "Expected 1 arguments but got 0");
+    assert_fails("enum E { A(x) } E.B(1);", "Enum E has no variant 'B'.");
+    assert_fails(
+        "enum E { A } var [x] = [E.A]; E.A.x;",
+        "Undefined property 'x'.",
+    );
+}
+
+#[test]
+fn test_operator_overloading() {
+    run(r#"
+    class Money {
+        init(cents) { this.cents = cents; }
+        __add__(other) { return Money(this.cents + other.cents); }
+        __sub__(other) { return Money(this.cents - other.cents); }
+        __mul__(times) { return Money(this.cents * times); }
+        __eq__(other) { return other != nil and this.cents == other.cents; }
+        __lt__(other) { return this.cents < other.cents; }
+        __str__() { return "money"; }
+    }
+    var total = Money(150) + Money(50) * 2 - Money(25);
+    if (total != Money(225) or total == nil) throw "arithmetic";
+    if (!(Money(1) < Money(2)) or Money(1) > Money(2)) throw "less";
+    if (!(Money(2) <= Money(2)) or !(Money(3) >= Money(2))) throw "or equal";
+    total += Money(75);
+    if (total.cents != 300) throw "compound";
+
+    class Grid {
+        init(rows) { this.rows = rows; }
+        __index__(row) { return this.rows[row]; }
+        __call__(row, column) { return this[row][column]; }
+    }
+    var grid = Grid([[1, 2], [3, 4]]);
+    if (grid[1][0] != 3 or grid(0, 1) != 2) throw "grid";
+
+    // without __eq__ instances are only equal to themselves
+    class Plain {}
+    var plain = Plain();
+    if (plain != plain or plain == Plain()) throw "identity";
+"#)
+    .unwrap();
+
+    // operators without a special method and bad results are errors
+    assert_fails(
+        "class A {} A() + 1;",
+        "Class A does not define '__add__' for '+'.",
+    );
+    assert_fails(
+        "class V { __add__(o) { return 1; } } 1 + V();",
+        "Unsupported operand for '+', an instance of V has to be on its left.",
+    );
+    assert_fails(
+        "class V { __add__(o) { return 1; } } \"x\" + V();",
+        "Unsupported operand for '+', an instance of V has to be on its left.",
+    );
+    assert_fails(
+        "class V { __lt__(o) { return true; } } V() > 1;",
+        "Unsupported operand for '>', an instance of V has to be on its right.",
+    );
+    assert_fails(
+        "class A { __lt__(o) { return 1; } } A() < A();",
+        "Method '__lt__' must return 'true' or 'false'.",
+    );
+    assert_fails(
+        "class A { __str__() { return 1; } } print A();",
+        "Method '__str__' must return a string.",
+    );
+    assert_fails("class A {} A()();", "Can only call functions and classes.");
+}
+
+#[test]
+fn test_class_members() {
+    run(r#"
+    class Rect {
+        class count = 0;
+        class unit;
+        init(w, h) {
+            this.w = w;
+            this.h = h;
+            Rect.count += 1;
+        }
+        class square(side) { return this(side, side); }
+        class created { return this.count * 10; }
+        area { return this.w * this.h; }
+    }
+    var square = Rect.square(3);
+    if (square.area != 9 or Rect(2, 3).area != 6) throw "area";
+    if (Rect.count != 2 or Rect.created != 20) throw "count";
+    if (!(Rect.unit == nil)) throw "uninitialized";
+
+    // class members are inherited, and fields are shared with the superclass
+    class Box < Rect {
+        volume { return this.area * 2; }
+    }
+    var box = Box.square(2);
+    if (box.volume != 8 or Box.count != 3) throw "inherited";
+
+    // fields shadow getters of the same name
+    square.area = 1;
+    if (square.area != 1) throw "shadow";
+
+    // super finds class methods from a class method, and calls getters
+    class Cube < Box {
+        class square(side) { return super.square(side * 2); }
+        class created { return super.created + 1; }
+        volume { return super.volume * 2; }
+    }
+    var cube = Cube.square(1);
+    if (cube.volume != 16 or Cube.created != 41) throw "super";
+"#)
+    .unwrap();
+
+    // class members are not found on instances, nor methods on the class
+    assert_fails(
+        "class A { class f() {} } A().f();",
+        "Undefined property 'f'.",
+    );
+    assert_fails("class A { f() {} } A.f();", "Undefined property 'f'.");
+    assert_fails(
+        "class A { f() {} class x = 1; }",
+        "Class fields must come before methods.",
+    );
+}
+
+#[test]
+fn test_traits() {
+    run(r#"
+    trait Comparable {
+        less(other) { return this.compare(other) < 0; }
+        max(other) { return this.less(other) ? other : this; }
+    }
+    trait Serializable {
+        serialize() { return "{" + this.name + "}"; }
+        kind { return "serializable"; }
+    }
+    class Named {
+        init(name) { this.name = name; }
+        compare(other) { return this.name < other.name ? -1 : 1; }
+    }
+    class Tag < Named with Comparable, Serializable {
+        kind { return "tag"; }
+    }
+    var a = Tag("a");
+    var b = Tag("b");
+    if (!a.less(b) or a.max(b) != b) throw "comparable";
+    if (a.serialize() != "{a}") throw "serializable";
+    if (a.kind != "tag") throw "override";
+
+    // trait methods override those inherited from the superclass
+    trait Loud { compare(other) { return 0; } }
+    class Quiet < Named with Loud {}
+    if (Quiet("a").compare(Quiet("b")) != 0) throw "superclass";
+"#)
+    .unwrap();
+
+    // only traits can be used, and traits are not classes
+    assert_fails(
+        "class A {} class B with A {}",
+        "A class can only be declared with traits.",
+    );
+    assert_fails("trait T {} T();", "Can only call functions and classes.");
+    assert_fails(
+        "trait T { f() {} } var U = T; class A with T, U {}",
+        "Trait T is used more than once.",
+    );
+}
+
+#[test]
+fn test_private_members() {
+    run(r#"
+    class Account {
+        class #opened = 0;
+        init(balance) {
+            this.#balance = balance;
+            Account.#opened += 1;
+        }
+        #covers(amount) { return amount <= this.#balance; }
+        withdraw(amount) {
+            if (!this.#covers(amount)) return false;
+            this.#balance -= amount;
+            return true;
+        }
+        // other objects of the same class are accessible too
+        transfer(other, amount) {
+            if (this.withdraw(amount)) other.#balance += amount;
+        }
+        balance { return this.#balance; }
+        class opened { return Account.#opened; }
+    }
+    class Savings < Account {
+        interest() { return this.balance / 10; }
+    }
+    var a = Account(10);
+    var b = Savings(20);
+    a.transfer(b, 5);
+    if (a.balance != 5 or b.balance != 25 or a.withdraw(6)) throw "balance";
+    if (Account.opened != 2 or b.interest() != 2) throw "members";
+"#)
+    .unwrap();
+
+    // another class cannot reach in, even with an object of the declaring class
+    let other_class = r#"
+    class A { init() { this.#x = 1; } }
+    class B { read(a) { return a.#x; } }
+    B().read(A());
+"#;
+    assert_fails(
+        other_class,
+        "Cannot access private property '#x' outside of its class.",
+    );
+    assert_fails(
+        r##"class A { init() { this.#x = 1; } } var {"#x": x} = A();"##,
+        "Cannot destructure private property '#x'.",
+    );
+
+    // nor can a subclass, whether for fields, methods or class members
+    for source_code in [
+        "class B < A { read() { return this.#x; } } B().read();",
+        "class B < A { write() { this.#x = 2; } } B().write();",
+        "class B < A { call() { return this.#f(); } } B().call();",
+        "class B < A { class read() { return B.#count; } } B.read();",
+    ] {
+        let classes = "class A { class #count = 0; init() { this.#x = 1; } #f() {} }";
+        assert_fails(
+            &format!("{} {}", classes, source_code),
+            "outside of its class.",
+        );
+    }
+}
+
+#[test]
+fn test_generators() {
+    run(r#"
+    fun* naturals() {
+        var n = 0;
+        while (true) { yield n; n += 1; }
+    }
+    fun* take(source, count) {
+        if (count == 0) return;
+        for (var x in source) {
+            yield x;
+            count -= 1;
+            if (count == 0) break;
+        }
+    }
+    fun* map(source, f) { for (var x in source) yield f(x); }
+    // lists are compared by reference, so their elements are compared here
+    fun same(xs, ys) {
+        if (xs.len() != ys.len()) return false;
+        for (var i = 0; i < xs.len(); i += 1) if (!(xs[i] == ys[i])) return false;
+        return true;
+    }
+
+    // only as much of an infinite generator runs as is asked for
+    var squares = [];
+    for (var x in take(map(naturals(), (x) => x * x), 4)) squares.push(x);
+    if (!same(squares, [0, 1, 4, 9])) throw "pipeline";
+
+    var started = false;
+    var lazy = fun* () { started = true; yield 1; };
+    var g = lazy();
+    if (started) throw "eager";
+    if (g.next() != 1 or !started) throw "next";
+    if (!(g.next() == nil and g.next() == nil)) throw "done";
+
+    // a yielded nil is a value, which hasNext() tells apart from the end
+    fun* maybe() { yield nil; yield 1; }
+    var values = [];
+    for (var x in maybe()) values.push(x);
+    if (!same(values, [nil, 1])) throw "nil";
+    var m = maybe();
+    if (!(m.hasNext() and m.hasNext() and m.next() == nil)) throw "peek";
+    if (!(m.next() == 1) or m.hasNext() or !(m.next() == nil)) throw "end";
+
+    fun* steps() {
+        try {
+            yield 1;
+            throw "caught";
+        } catch (e) {
+            yield e;
+        } finally {
+            yield 2;
+        }
+        match ([3, 4]) { [a, b] => { yield a; yield b; } _ => {} }
+        for (var i = 5; i < 9; i += 1) {
+            if (i == 6) continue;
+            if (i == 8) break;
+            yield i;
+        }
+    }
+    var seen = [];
+    for (var step in steps()) seen.push(step);
+    if (!same(seen, [1, "caught", 2, 3, 4, 5, 7])) throw "steps";
+
+    class Pair {
+        init(a, b) { this.a = a; this.b = b; }
+        *iterator() { yield this.a; yield this.b; }
+    }
+    var total = 0;
+    for (var x in Pair(1, 2)) total += x;
+    if (total != 3) throw "method";
+"#)
+    .unwrap();
+
+    // an exception from the body reaches the caller of next() and ends the generator
+    run(r#"
+    fun* failing() { yield 1; throw "inner"; yield 2; }
+    var g = failing();
+    g.next();
+    var caught = nil;
+    try { g.next(); } catch (e) { caught = e; }
+    if (!(caught == "inner" and g.next() == nil)) throw "failing";
+"#)
+    .unwrap();
+
+    // a generator cannot resume itself
+    assert_fails(
+        "var g; fun* f() { yield g.next(); } g = f(); g.next();",
+        "Cannot resume a generator from inside its own body.",
+    );
+}
+
+#[test]
+fn test_fibers() {
+    run(r#"
+    // a fiber can yield from inside the functions it calls
+    fun ask(question) { return Fiber.yield(question); }
+    var fiber = Fiber(fun (name) {
+        var age = ask(name + "?");
+        var city = ask("where?");
+        return age + city;
+    });
+    if (!(fiber.resume("name") == "name?")) throw "first";
+    if (!(fiber.resume(30) == "where?") or fiber.isDone()) throw "second";
+    if (!(fiber.resume(12) == 42) or !fiber.isDone()) throw "last";
+
+    var finished = false;
+    try { fiber.resume(); } catch (e) { finished = true; }
+    if (!finished) throw "finished";
+
+    // an exception in a fiber reaches whoever resumed it
+    var failing = Fiber(fun () { throw "inner"; });
+    var caught = nil;
+    try { failing.resume(); } catch (e) { caught = e; }
+    if (!(caught == "inner") or !failing.isDone()) throw "failing";
+
+    // a fiber that returns right away hands back what it returned
+    if (!(Fiber(fun () {}).resume() == nil)) throw "empty";
+
+    // agents take turns, and sleeping moves the virtual clock on,
+    // even when a function the fiber called does the sleeping
+    var log = [];
+    fun nap(delay) { Fiber.sleep(delay); }
+    fun agent(name, delay) {
+        return fun () {
+            for (var i = 0; i < 2; i += 1) {
+                log.push(name);
+                log.push(Fiber.now());
+                nap(delay);
+            }
+        };
+    }
+    Fiber.spawn(agent("a", 10));
+    Fiber.spawn(agent("b", 5));
+    Fiber.spawn(fun () {
+        Fiber.yield();
+        Fiber.spawn(agent("c", 1));
+    });
+    Fiber.run();
+    var expected = ["a", 0, "b", 0, "c", 0, "c", 1, "b", 5, "a", 10];
+    if (log.len() != expected.len()) throw "log";
+    for (var i = 0; i < log.len(); i += 1) if (!(log[i] == expected[i])) throw "order";
+    if (Fiber.now() != 20) throw "clock";
+"#)
+    .unwrap();
+
+    assert_fails("Fiber.yield();", "Cannot yield outside of a fiber.");
+    assert_fails("Fiber.sleep(1);", "Cannot sleep outside of a fiber.");
+    assert_fails(
+        "Fiber.spawn(fun () { Fiber.sleep(-1); }); Fiber.run();",
+        "Sleep duration must be a non-negative integer.",
+    );
+    assert_fails(
+        "var f; f = Fiber(fun () { f.resume(); }); f.resume();",
+        "Cannot resume a fiber that is already running.",
+    );
+}